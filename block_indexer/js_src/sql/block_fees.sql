@@ -0,0 +1,29 @@
+DROP TABLE IF EXISTS public.block_fees;
+
+CREATE TABLE IF NOT EXISTS public.block_fees
+(
+    slot bigint NOT NULL,
+    source character varying(16) COLLATE pg_catalog."default" NOT NULL,
+    transaction_count INT NOT NULL,
+    compute_unit_price_p25 numeric(20,0) NOT NULL,
+    compute_unit_price_p50 numeric(20,0) NOT NULL,
+    compute_unit_price_p75 numeric(20,0) NOT NULL,
+    compute_unit_price_p90 numeric(20,0) NOT NULL,
+    compute_unit_price_p99 numeric(20,0) NOT NULL,
+    priority_fee_p50 bigint NOT NULL,
+    priority_fee_p90 bigint NOT NULL,
+    compute_units_consumed_p50 INT NOT NULL,
+    created INT,
+    CONSTRAINT block_fees_pkey PRIMARY KEY (slot, source)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.block_fees
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.block_fees TO app_backend;
+
+GRANT ALL ON TABLE public.block_fees TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS block_fees_source_slot_idx ON public.block_fees USING btree (source ASC, slot DESC);
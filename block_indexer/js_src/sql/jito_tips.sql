@@ -0,0 +1,23 @@
+DROP TABLE IF EXISTS public.jito_tips;
+
+CREATE TABLE IF NOT EXISTS public.jito_tips
+(
+    slot bigint NOT NULL,
+    transaction_count INT NOT NULL,
+    tip_count INT NOT NULL,
+    total_lamports numeric(20,0) NOT NULL,
+    max_lamports numeric(20,0) NOT NULL,
+    created INT,
+    CONSTRAINT jito_tips_pkey PRIMARY KEY (slot)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.jito_tips
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.jito_tips TO app_backend;
+
+GRANT ALL ON TABLE public.jito_tips TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS jito_tips_created_idx ON public.jito_tips USING btree (created ASC);
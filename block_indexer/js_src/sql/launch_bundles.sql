@@ -0,0 +1,29 @@
+DROP TABLE IF EXISTS public.launch_bundles;
+
+CREATE TABLE IF NOT EXISTS public.launch_bundles
+(
+    mint character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    mint_slot bigint NOT NULL,
+    creator character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    wallet character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    in_creation_transaction boolean NOT NULL,
+    funded_by_creator boolean NOT NULL,
+    funding_lamports numeric(20,0) NOT NULL,
+    token_amount numeric(30,0) NOT NULL,
+    sol_amount numeric(30,0) NOT NULL,
+    buy_count INT NOT NULL,
+    created INT,
+    CONSTRAINT launch_bundles_pkey PRIMARY KEY (mint, wallet)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.launch_bundles
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.launch_bundles TO app_backend;
+
+GRANT ALL ON TABLE public.launch_bundles TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS launch_bundles_wallet_idx ON public.launch_bundles USING btree (wallet ASC);
+CREATE INDEX IF NOT EXISTS launch_bundles_creator_idx ON public.launch_bundles USING btree (creator ASC);
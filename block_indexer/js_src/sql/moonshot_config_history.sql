@@ -0,0 +1,38 @@
+DROP TABLE IF EXISTS public.moonshot_config_history;
+
+CREATE TABLE IF NOT EXISTS public.moonshot_config_history
+(
+    slot bigint NOT NULL,
+    transaction_id character varying(255) COLLATE pg_catalog."default" NOT NULL,
+    instruction character varying(16) COLLATE pg_catalog."default" NOT NULL, -- ConfigInit, ConfigUpdate
+    signer character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    config_account character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    -- the values below are NULL when the instruction didn't change them
+    migration_authority character varying(44) COLLATE pg_catalog."default",
+    backend_authority character varying(44) COLLATE pg_catalog."default",
+    config_authority character varying(44) COLLATE pg_catalog."default",
+    helio_fee character varying(44) COLLATE pg_catalog."default",
+    dex_fee character varying(44) COLLATE pg_catalog."default",
+    fee_bps INT,
+    dex_fee_share INT,
+    migration_fee numeric(30,0),
+    marketcap_threshold numeric(30,0),
+    marketcap_currency INT,
+    min_supported_decimal_places INT,
+    max_supported_decimal_places INT,
+    min_supported_token_supply numeric(30,0),
+    max_supported_token_supply numeric(30,0),
+    coef_b bigint,
+    timestamp INT
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.moonshot_config_history
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.moonshot_config_history TO app_backend;
+
+GRANT ALL ON TABLE public.moonshot_config_history TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS moonshot_config_history_config_account_slot_idx ON public.moonshot_config_history (config_account, slot);
@@ -61,4 +61,9 @@ ALTER TABLE public.moonshot_data ADD COLUMN total_holders INT DEFAULT 0;
 
 ALTER TABLE public.moonshot_data ADD COLUMN curve_token_amount numeric(30,0);
 ALTER TABLE public.moonshot_data ADD COLUMN curve_sol_amount numeric(30,0);
-ALTER TABLE public.moonshot_data ADD COLUMN curve_liquidity numeric(30,0);
\ No newline at end of file
+ALTER TABLE public.moonshot_data ADD COLUMN curve_liquidity numeric(30,0);
+ALTER TABLE public.moonshot_data ADD COLUMN marketcap_usd numeric(30,10);
+ALTER TABLE public.moonshot_data ADD COLUMN volume_usd numeric(30,10);
+
+-- raw CurveAccount data, used for the supply and decimals of the tokens created before the indexed blocks
+ALTER TABLE public.moonshot_data ADD COLUMN curve_account_data bytea;
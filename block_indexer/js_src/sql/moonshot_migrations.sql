@@ -0,0 +1,30 @@
+DROP TABLE IF EXISTS public.moonshot_migrations;
+
+CREATE TABLE IF NOT EXISTS public.moonshot_migrations
+(
+    slot bigint NOT NULL,
+    transaction_id character varying(255) COLLATE pg_catalog."default" NOT NULL,
+    mint character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    curve_account character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    migration_authority character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    migration_target INT, -- 0 Raydium, 1 Meteora
+    -- the values below are NULL when the MigrationEvent is not in the logs
+    tokens_migrated numeric(30,0),
+    tokens_burned numeric(30,0),
+    collateral_migrated numeric(30,0),
+    fee numeric(30,0),
+    label character varying(255) COLLATE pg_catalog."default",
+    created INT,
+    CONSTRAINT moonshot_migrations_pkey PRIMARY KEY (transaction_id, mint)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.moonshot_migrations
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.moonshot_migrations TO app_backend;
+
+GRANT ALL ON TABLE public.moonshot_migrations TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS moonshot_migrations_mint_idx ON public.moonshot_migrations (mint);
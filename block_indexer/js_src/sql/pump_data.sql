@@ -49,3 +49,8 @@ ALTER TABLE public.pump_data ADD COLUMN sell_volume numeric(30,0) DEFAULT 0;
 ALTER TABLE public.pump_data ADD COLUMN dev_hold_sum numeric(30,0) DEFAULT 0;
 ALTER TABLE public.pump_data ADD COLUMN total_amount numeric(30,0) DEFAULT 0;
 ALTER TABLE public.pump_data ADD COLUMN total_holders INT DEFAULT 0;
+
+ALTER TABLE public.pump_data ADD COLUMN marketcap numeric(30,10);
+
+ALTER TABLE public.pump_data ADD COLUMN marketcap_usd numeric(30,10);
+ALTER TABLE public.pump_data ADD COLUMN volume_usd numeric(30,10);
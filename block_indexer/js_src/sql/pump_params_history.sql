@@ -0,0 +1,25 @@
+DROP TABLE IF EXISTS public.pump_params_history;
+
+CREATE TABLE IF NOT EXISTS public.pump_params_history
+(
+    slot bigint NOT NULL,
+    transaction_id character varying(255) COLLATE pg_catalog."default" NOT NULL,
+    fee_recipient character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    initial_virtual_token_reserves numeric(30,0),
+    initial_virtual_sol_reserves numeric(30,0),
+    initial_real_token_reserves numeric(30,0),
+    token_total_supply numeric(30,0),
+    fee_basis_points bigint,
+    timestamp INT
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.pump_params_history
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.pump_params_history TO app_backend;
+
+GRANT ALL ON TABLE public.pump_params_history TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS pump_params_history_slot_idx ON public.pump_params_history (slot);
@@ -29,3 +29,9 @@ ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS failed_transact
 
 CREATE INDEX IF NOT EXISTS pump_trade_signer_idx ON public.pump_trade (signer);
 CREATE INDEX IF NOT EXISTS pump_trade_created_idx ON public.pump_trade (created);
+
+ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS priority_fee bigint;
+ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS compute_unit_limit INT;
+ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS compute_units_consumed INT;
+ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS base_fee bigint;
+ALTER TABLE IF EXISTS public.pump_trade ADD COLUMN IF NOT EXISTS jito_tip bigint;
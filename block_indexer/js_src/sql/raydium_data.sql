@@ -76,4 +76,7 @@ UPDATE public.raydium_data SET sell_count = 0 WHERE sell_count IS NULL;
 
 ALTER TABLE public.raydium_data ADD COLUMN dev_hold_sum numeric(30,0) DEFAULT 0;
 ALTER TABLE public.raydium_data ADD COLUMN total_amount numeric(30,0) DEFAULT 0;
-ALTER TABLE public.raydium_data ADD COLUMN total_holders INT DEFAULT 0;
\ No newline at end of file
+ALTER TABLE public.raydium_data ADD COLUMN total_holders INT DEFAULT 0;
+ALTER TABLE public.raydium_data ADD COLUMN price_usd numeric(30,10);
+ALTER TABLE public.raydium_data ADD COLUMN volume_usd numeric(30,10);
+ALTER TABLE public.raydium_data ADD COLUMN marketcap_usd numeric(30,10);
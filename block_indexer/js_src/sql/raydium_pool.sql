@@ -0,0 +1,35 @@
+DROP TABLE IF EXISTS public.raydium_pool;
+
+-- every Raydium pool v4 seen by the indexer (raydium_data only keeps the pools of our tokens)
+CREATE TABLE IF NOT EXISTS public.raydium_pool
+(
+    amm character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    coin_mint character varying(44) COLLATE pg_catalog."default",
+    pc_mint character varying(44) COLLATE pg_catalog."default",
+    pool_coin_token_account character varying(44) COLLATE pg_catalog."default",
+    pool_pc_token_account character varying(44) COLLATE pg_catalog."default",
+    lp_mint character varying(44) COLLATE pg_catalog."default",
+    amm_open_orders character varying(44) COLLATE pg_catalog."default",
+    amm_target_orders character varying(44) COLLATE pg_catalog."default",
+    market character varying(44) COLLATE pg_catalog."default",
+    coin_decimals INT,
+    pc_decimals INT,
+    open_time bigint,
+    -- raw AmmInfo account data, used when the pool was created before the indexed blocks
+    amm_info bytea,
+    created bigint,
+    create_event_slot bigint,
+    CONSTRAINT raydium_pool_pkey PRIMARY KEY (amm)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.raydium_pool
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.raydium_pool TO app_backend;
+
+GRANT ALL ON TABLE public.raydium_pool TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS raydium_pool_coin_mint_idx ON public.raydium_pool (coin_mint);
+CREATE INDEX IF NOT EXISTS raydium_pool_pc_mint_idx ON public.raydium_pool (pc_mint);
@@ -51,3 +51,14 @@ ALTER TABLE IF EXISTS public.raydium_trade ALTER COLUMN direction TYPE SMALLINT
 
 CREATE INDEX IF NOT EXISTS raydium_trade_log_type_idx ON public.raydium_trade (log_type);
 CREATE INDEX IF NOT EXISTS raydium_trade_direction_idx ON public.raydium_trade (direction);
+
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS mint character varying(44) COLLATE pg_catalog."default";
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS is_buy BOOLEAN;
+
+CREATE INDEX IF NOT EXISTS raydium_trade_mint_idx ON public.raydium_trade (mint);
+
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS priority_fee bigint;
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS compute_unit_limit INT;
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS compute_units_consumed INT;
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS base_fee bigint;
+ALTER TABLE IF EXISTS public.raydium_trade ADD COLUMN IF NOT EXISTS jito_tip bigint;
@@ -0,0 +1,32 @@
+DROP TABLE IF EXISTS public.sandwiches;
+
+CREATE TABLE IF NOT EXISTS public.sandwiches
+(
+    slot bigint NOT NULL,
+    source character varying(16) COLLATE pg_catalog."default" NOT NULL,
+    mint character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    attacker character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    victim character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    front_run_transaction_id character varying(88) COLLATE pg_catalog."default" NOT NULL,
+    victim_transaction_id character varying(88) COLLATE pg_catalog."default" NOT NULL,
+    back_run_transaction_id character varying(88) COLLATE pg_catalog."default" NOT NULL,
+    victim_token_amount numeric(30,0) NOT NULL,
+    victim_sol_amount numeric(30,0) NOT NULL,
+    victim_loss numeric(30,0) NOT NULL,
+    attacker_profit numeric(30,0) NOT NULL,
+    created INT,
+    CONSTRAINT sandwiches_pkey PRIMARY KEY (victim_transaction_id, mint)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.sandwiches
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.sandwiches TO app_backend;
+
+GRANT ALL ON TABLE public.sandwiches TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS sandwiches_mint_idx ON public.sandwiches USING btree (mint ASC);
+CREATE INDEX IF NOT EXISTS sandwiches_attacker_idx ON public.sandwiches USING btree (attacker ASC);
+CREATE INDEX IF NOT EXISTS sandwiches_victim_idx ON public.sandwiches USING btree (victim ASC);
@@ -0,0 +1,18 @@
+DROP TABLE IF EXISTS public.sol_price;
+
+CREATE TABLE IF NOT EXISTS public.sol_price
+(
+    slot bigint NOT NULL,
+    price double precision NOT NULL,
+    created INT,
+    CONSTRAINT sol_price_pkey PRIMARY KEY (slot)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.sol_price
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.sol_price TO app_backend;
+
+GRANT ALL ON TABLE public.sol_price TO app_indexer;
@@ -0,0 +1,30 @@
+DROP TABLE IF EXISTS public.token_state;
+
+CREATE TABLE IF NOT EXISTS public.token_state
+(
+    mint character varying(44) COLLATE pg_catalog."default" NOT NULL,
+    protocol character varying(16) COLLATE pg_catalog."default" NOT NULL,
+    phase character varying(16) COLLATE pg_catalog."default" NOT NULL,
+    curve_progress double precision NOT NULL,
+    created_at bigint,
+    created_slot bigint,
+    first_trade_at bigint,
+    last_trade_at bigint,
+    completed_at bigint,
+    migrated_at bigint,
+    migration_pool character varying(44) COLLATE pg_catalog."default",
+    updated_slot bigint NOT NULL,
+    CONSTRAINT token_state_pkey PRIMARY KEY (mint)
+)
+
+TABLESPACE pg_default;
+
+ALTER TABLE IF EXISTS public.token_state
+    OWNER to app_indexer;
+
+GRANT ALL ON TABLE public.token_state TO app_backend;
+
+GRANT ALL ON TABLE public.token_state TO app_indexer;
+
+CREATE INDEX IF NOT EXISTS token_state_phase_idx ON public.token_state USING btree (phase ASC);
+CREATE INDEX IF NOT EXISTS token_state_migration_pool_idx ON public.token_state USING btree (migration_pool ASC);
@@ -0,0 +1,185 @@
+// Fees paid by a transaction: the base fee (signatures) and the priority fee,
+// which is the compute unit price (set with a ComputeBudget instruction) times
+// the compute unit limit.
+
+use serde_json::Value;
+use solana_sdk::bs58;
+
+pub mod storage;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+// limit of the transactions without SetComputeUnitLimit
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+enum ComputeBudgetDiscriminator {
+    SetComputeUnitLimit = 2,
+    SetComputeUnitPrice = 3,
+}
+
+#[derive(Debug, Clone)]
+pub enum ComputeBudgetInstructionData {
+    // units: u32
+    SetComputeUnitLimit(u32),
+    // micro_lamports: u64
+    SetComputeUnitPrice(u64),
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransactionFees {
+    // fee of the signatures
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub compute_unit_limit: u64,
+    // micro-lamports per compute unit
+    pub compute_unit_price: u64,
+    pub compute_units_consumed: u64,
+    // lamports sent to the Jito tip accounts
+    pub jito_tip: u64,
+}
+
+pub fn decode_compute_budget_instruction_data(
+    instruction_data: &[u8],
+) -> ComputeBudgetInstructionData {
+    let Some(discriminator) = instruction_data.first() else {
+        return ComputeBudgetInstructionData::Unknown;
+    };
+    let mut instruction_data_mut = &instruction_data[1..];
+
+    if *discriminator == ComputeBudgetDiscriminator::SetComputeUnitLimit as u8 {
+        if let Ok(units) = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut) {
+            return ComputeBudgetInstructionData::SetComputeUnitLimit(units);
+        }
+    } else if *discriminator == ComputeBudgetDiscriminator::SetComputeUnitPrice as u8 {
+        if let Ok(micro_lamports) = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut)
+        {
+            return ComputeBudgetInstructionData::SetComputeUnitPrice(micro_lamports);
+        }
+    }
+
+    ComputeBudgetInstructionData::Unknown
+}
+
+/// The priority fee is paid for the requested units, not for the consumed ones.
+/// The Jito tip is read from the parsed transfers.
+pub fn read_transaction_fees(
+    transaction_obj: &serde_json::Map<String, Value>,
+    addresses: &[String],
+) -> TransactionFees {
+    let meta = transaction_obj.get("meta").unwrap();
+    let instructions = transaction_obj
+        .get("transaction")
+        .and_then(|transaction| transaction.get("message"))
+        .and_then(|message| message.get("instructions"))
+        .and_then(|instructions| instructions.as_array());
+
+    let mut compute_unit_limit: Option<u64> = None;
+    let mut compute_unit_price: u64 = 0;
+    let mut instructions_count: u64 = 0;
+
+    for instruction in instructions.into_iter().flatten() {
+        let program_id_index = instruction.get("programIdIndex").unwrap().as_u64().unwrap();
+        let program_id = addresses.get(program_id_index as usize).unwrap();
+        if program_id != COMPUTE_BUDGET_PROGRAM_ID {
+            instructions_count += 1;
+            continue;
+        }
+
+        let instruction_data_str = instruction.get("data").unwrap().as_str().unwrap();
+        let instruction_data_bytes = bs58::decode(instruction_data_str).into_vec().unwrap();
+        match decode_compute_budget_instruction_data(&instruction_data_bytes) {
+            ComputeBudgetInstructionData::SetComputeUnitLimit(units) => {
+                compute_unit_limit = Some(units as u64);
+            }
+            ComputeBudgetInstructionData::SetComputeUnitPrice(micro_lamports) => {
+                compute_unit_price = micro_lamports;
+            }
+            ComputeBudgetInstructionData::Unknown => {}
+        }
+    }
+
+    let compute_unit_limit = compute_unit_limit
+        .unwrap_or(instructions_count * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let priority_fee = (compute_unit_price as u128 * compute_unit_limit as u128)
+        .div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64;
+    let fee = meta.get("fee").and_then(|fee| fee.as_u64()).unwrap_or(0);
+
+    TransactionFees {
+        base_fee: fee.saturating_sub(priority_fee),
+        priority_fee,
+        compute_unit_limit,
+        compute_unit_price,
+        compute_units_consumed: meta
+            .get("computeUnitsConsumed")
+            .and_then(|units| units.as_u64())
+            .unwrap_or(0),
+        jito_tip: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn build_instruction(program_id_index: u64, data: &[u8]) -> Value {
+        json!({
+            "programIdIndex": program_id_index,
+            "accounts": [],
+            "data": bs58::encode(data).into_string(),
+        })
+    }
+
+    #[test]
+    fn test_read_transaction_fees() {
+        let addresses = vec![
+            "payer".to_string(),
+            COMPUTE_BUDGET_PROGRAM_ID.to_string(),
+            "program".to_string(),
+        ];
+        let mut set_limit = vec![2u8];
+        set_limit.extend_from_slice(&120_000u32.to_le_bytes());
+        let mut set_price = vec![3u8];
+        set_price.extend_from_slice(&1_500_001u64.to_le_bytes());
+
+        let transaction_obj = json!({
+            "meta": { "fee": 5000 + 180001, "computeUnitsConsumed": 80_000 },
+            "transaction": { "message": { "instructions": [
+                build_instruction(1, &set_limit),
+                build_instruction(1, &set_price),
+                build_instruction(2, &[]),
+            ]}},
+        });
+        let fees = read_transaction_fees(transaction_obj.as_object().unwrap(), &addresses);
+        assert_eq!(
+            fees,
+            TransactionFees {
+                base_fee: 5000,
+                priority_fee: 180001,
+                compute_unit_limit: 120_000,
+                compute_unit_price: 1_500_001,
+                compute_units_consumed: 80_000,
+                jito_tip: 0,
+            }
+        );
+
+        // without limit, 200k units per instruction
+        let transaction_obj = json!({
+            "meta": { "fee": 5000 + 800, "computeUnitsConsumed": 1000 },
+            "transaction": { "message": { "instructions": [
+                build_instruction(1, &set_price[..1]),
+                build_instruction(2, &[]),
+                build_instruction(2, &[]),
+            ]}},
+        });
+        let fees = read_transaction_fees(transaction_obj.as_object().unwrap(), &addresses);
+        assert_eq!(fees.compute_unit_limit, 400_000);
+        assert_eq!(fees.priority_fee, 0);
+        assert_eq!(fees.base_fee, 5800);
+    }
+}
@@ -0,0 +1,172 @@
+// Fee percentiles of the transactions of every protocol in a block, to price
+// our own transactions.
+
+use postgres::Transaction;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::compute_budget::TransactionFees;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::{Event, IndexerEventSource};
+
+fn block_fees_source(source: IndexerEventSource) -> Option<&'static str> {
+    match source {
+        IndexerEventSource::Solana => None,
+        _ => Some(source.name()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFees {
+    pub source: &'static str,
+    pub transaction_count: i32,
+    // micro-lamports per compute unit
+    pub compute_unit_price_p25: u64,
+    pub compute_unit_price_p50: u64,
+    pub compute_unit_price_p75: u64,
+    pub compute_unit_price_p90: u64,
+    pub compute_unit_price_p99: u64,
+    // lamports
+    pub priority_fee_p50: u64,
+    pub priority_fee_p90: u64,
+    pub compute_units_consumed_p50: u64,
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted_values: &[u64], percent: usize) -> u64 {
+    if sorted_values.is_empty() {
+        return 0;
+    }
+    let rank = (percent * sorted_values.len()).div_ceil(100).max(1);
+    sorted_values[rank - 1]
+}
+
+/// A transaction with several events of a protocol is counted once.
+pub fn compute_block_fees(events: &[Event]) -> Vec<BlockFees> {
+    // source -> transaction index -> fees
+    let mut fees_by_source: BTreeMap<&str, HashMap<u32, TransactionFees>> = BTreeMap::new();
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        let Some(source) = block_fees_source(function_call_event.source) else {
+            continue;
+        };
+        fees_by_source.entry(source).or_default().insert(
+            function_call_event.transaction_index,
+            function_call_event.fees,
+        );
+    }
+
+    fees_by_source
+        .into_iter()
+        .map(|(source, fees_by_transaction)| {
+            let sorted = |read: fn(&TransactionFees) -> u64| {
+                let mut values: Vec<u64> = fees_by_transaction.values().map(read).collect();
+                values.sort_unstable();
+                values
+            };
+            let compute_unit_prices = sorted(|fees| fees.compute_unit_price);
+            let priority_fees = sorted(|fees| fees.priority_fee);
+            let compute_units_consumed = sorted(|fees| fees.compute_units_consumed);
+
+            BlockFees {
+                source,
+                transaction_count: fees_by_transaction.len() as i32,
+                compute_unit_price_p25: percentile(&compute_unit_prices, 25),
+                compute_unit_price_p50: percentile(&compute_unit_prices, 50),
+                compute_unit_price_p75: percentile(&compute_unit_prices, 75),
+                compute_unit_price_p90: percentile(&compute_unit_prices, 90),
+                compute_unit_price_p99: percentile(&compute_unit_prices, 99),
+                priority_fee_p50: percentile(&priority_fees, 50),
+                priority_fee_p90: percentile(&priority_fees, 90),
+                compute_units_consumed_p50: percentile(&compute_units_consumed, 50),
+            }
+        })
+        .collect()
+}
+
+pub fn save_block_fees(
+    transaction: &mut Transaction,
+    events: &[Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let block_fees = compute_block_fees(events);
+    if block_fees.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO block_fees (
+            slot,
+            source,
+            transaction_count,
+            compute_unit_price_p25,
+            compute_unit_price_p50,
+            compute_unit_price_p75,
+            compute_unit_price_p90,
+            compute_unit_price_p99,
+            priority_fee_p50,
+            priority_fee_p90,
+            compute_units_consumed_p50,
+            created
+        ) VALUES {}
+        ON CONFLICT (slot, source) DO NOTHING;
+    ",
+        build_values_placeholders(
+            block_fees.len(),
+            &[
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(block_fees.len() * 12);
+    for fees in &block_fees {
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(fees.source.to_string()));
+        values.push(Box::new(fees.transaction_count));
+        values.push(Box::new(fees.compute_unit_price_p25.to_string()));
+        values.push(Box::new(fees.compute_unit_price_p50.to_string()));
+        values.push(Box::new(fees.compute_unit_price_p75.to_string()));
+        values.push(Box::new(fees.compute_unit_price_p90.to_string()));
+        values.push(Box::new(fees.compute_unit_price_p99.to_string()));
+        values.push(Box::new(fees.priority_fee_p50 as i64));
+        values.push(Box::new(fees.priority_fee_p90 as i64));
+        values.push(Box::new(fees.compute_units_consumed_p50 as i32));
+        values.push(Box::new(block_data.block_time as i32));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting block_fees: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<u64> = (1..=10).collect();
+        assert_eq!(percentile(&values, 25), 3);
+        assert_eq!(percentile(&values, 50), 5);
+        assert_eq!(percentile(&values, 90), 9);
+        assert_eq!(percentile(&values, 99), 10);
+        assert_eq!(percentile(&[7], 25), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+}
@@ -0,0 +1,178 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/diamond-hands.ts
+
+// Holding time and profit of every (mint, wallet), fed by the trades of all the
+// protocols. The bought tokens are kept as batches and the sells consume the
+// oldest batches first (FIFO). The amounts are integers (token units and
+// lamports), the cost of a batch is split proportionally when it is partially
+// sold, so the total cost is never lost to rounding.
+
+use std::collections::VecDeque;
+
+pub mod storage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldBatch {
+    pub timestamp: i64,
+    pub token_amount: u64,
+    // cost of the tokens of the batch that are still held
+    pub sol_amount: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiamondHandsTrade {
+    pub timestamp: i64,
+    pub is_buy: bool,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiamondHandsData {
+    pub held_batches: VecDeque<HeldBatch>,
+    // sum of token_amount * seconds held of the sold tokens
+    pub sold_holding_sum: i128,
+    // bought tokens
+    pub total_tokens: i128,
+    pub realized_profit: i128,
+    // seconds, truncated
+    pub average_holding_time: i128,
+    // realized profit plus the profit of the held tokens at the last price
+    pub total_profit: i128,
+    pub buy_count: i64,
+    pub sell_count: i64,
+}
+
+// `amount * part / total` without overflow, part <= total
+fn proportional(amount: u64, part: u64, total: u64) -> u64 {
+    (amount as u128 * part as u128 / total as u128) as u64
+}
+
+impl DiamondHandsData {
+    /// The trades of a wallet must be applied in the order they were executed.
+    /// The held tokens are valued at the price of this trade and their holding
+    /// time is counted up to `block_time`.
+    pub fn apply_trade(&mut self, trade: &DiamondHandsTrade, block_time: i64) {
+        if trade.token_amount == 0 || trade.sol_amount == 0 {
+            return;
+        }
+
+        if trade.is_buy {
+            self.held_batches.push_back(HeldBatch {
+                timestamp: trade.timestamp,
+                token_amount: trade.token_amount,
+                sol_amount: trade.sol_amount,
+            });
+            self.total_tokens += trade.token_amount as i128;
+            self.buy_count += 1;
+        } else {
+            self.sell(trade);
+            self.sell_count += 1;
+        }
+
+        let mut held_holding_sum: i128 = 0;
+        let mut held_tokens: i128 = 0;
+        let mut held_cost: i128 = 0;
+        for batch in &self.held_batches {
+            held_holding_sum += (block_time - batch.timestamp) as i128 * batch.token_amount as i128;
+            held_tokens += batch.token_amount as i128;
+            held_cost += batch.sol_amount as i128;
+        }
+        let held_value = held_tokens * trade.sol_amount as i128 / trade.token_amount as i128;
+
+        self.average_holding_time = if self.total_tokens > 0 {
+            (self.sold_holding_sum + held_holding_sum) / self.total_tokens
+        } else {
+            0
+        };
+        self.total_profit = self.realized_profit + held_value - held_cost;
+    }
+
+    // the tokens sold beyond the held batches (bought before they were tracked)
+    // are ignored
+    fn sell(&mut self, trade: &DiamondHandsTrade) {
+        let mut remaining_to_sell = trade.token_amount;
+        let mut sold_tokens: u64 = 0;
+        let mut sold_cost: i128 = 0;
+
+        while remaining_to_sell > 0 {
+            let Some(oldest_batch) = self.held_batches.front_mut() else {
+                break;
+            };
+            let time_held = (trade.timestamp - oldest_batch.timestamp) as i128;
+            let amount = remaining_to_sell.min(oldest_batch.token_amount);
+
+            if amount == oldest_batch.token_amount {
+                sold_cost += oldest_batch.sol_amount as i128;
+                self.held_batches.pop_front();
+            } else {
+                let cost = proportional(oldest_batch.sol_amount, amount, oldest_batch.token_amount);
+                sold_cost += cost as i128;
+                oldest_batch.token_amount -= amount;
+                oldest_batch.sol_amount -= cost;
+            }
+
+            self.sold_holding_sum += amount as i128 * time_held;
+            sold_tokens += amount;
+            remaining_to_sell -= amount;
+        }
+
+        let proceeds = proportional(trade.sol_amount, sold_tokens, trade.token_amount);
+        self.realized_profit += proceeds as i128 - sold_cost;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_trade(
+        timestamp: i64,
+        is_buy: bool,
+        token_amount: u64,
+        sol_amount: u64,
+    ) -> DiamondHandsTrade {
+        DiamondHandsTrade {
+            timestamp,
+            is_buy,
+            token_amount,
+            sol_amount,
+        }
+    }
+
+    #[test]
+    fn test_apply_trades() {
+        let mut data = DiamondHandsData::default();
+
+        // a sell without held tokens only counts
+        data.apply_trade(&build_trade(0, false, 500, 50), 0);
+        assert_eq!(data.sell_count, 1);
+        assert_eq!(data.realized_profit, 0);
+
+        data.apply_trade(&build_trade(0, true, 1000, 100), 0);
+        data.apply_trade(&build_trade(10, true, 1000, 200), 10);
+        assert_eq!(data.total_profit, 100);
+
+        // the first batch is sold entirely and the half of the second one
+        data.apply_trade(&build_trade(20, false, 1500, 450), 20);
+        assert_eq!(
+            data.held_batches,
+            VecDeque::from([HeldBatch {
+                timestamp: 10,
+                token_amount: 500,
+                sol_amount: 100,
+            }])
+        );
+        assert_eq!(data.sold_holding_sum, 1000 * 20 + 500 * 10);
+        assert_eq!(data.realized_profit, 450 - 200);
+        assert_eq!(data.average_holding_time, (25000 + 500 * 10) / 2000);
+        assert_eq!(data.total_profit, 250 + 150 - 100);
+        assert_eq!(data.buy_count, 2);
+        assert_eq!(data.sell_count, 2);
+
+        // the cost of a partially sold batch is kept entirely
+        data.apply_trade(&build_trade(30, false, 3, 1), 30);
+        data.apply_trade(&build_trade(30, false, 497, 150), 30);
+        assert!(data.held_batches.is_empty());
+        assert_eq!(data.realized_profit, 250 + 151 - 100);
+    }
+}
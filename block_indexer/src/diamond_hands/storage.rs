@@ -0,0 +1,317 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/diamond-hands.ts
+
+use postgres::Transaction;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::{DiamondHandsData, DiamondHandsTrade, HeldBatch};
+use crate::last_trades::LastTrade;
+use crate::storage::{
+    build_values_placeholders, execute_with_values, parse_numeric, BlockData, SqlValue,
+};
+
+// the price is only written for the readers of the Node format
+fn held_batch_to_json(batch: &HeldBatch) -> Value {
+    json!({
+        "timestamp": batch.timestamp,
+        "tokenAmount": batch.token_amount,
+        "solAmount": batch.sol_amount,
+        "price": batch.sol_amount as f64 / batch.token_amount as f64,
+    })
+}
+
+// integers above 2^53 are not exact as f64
+fn read_amount(amount: &Value) -> Option<u64> {
+    amount
+        .as_u64()
+        .or_else(|| amount.as_f64().map(|amount| amount as u64))
+}
+
+// the batches written by the Node indexer only have the price
+fn held_batch_from_json(batch: &Value) -> Option<HeldBatch> {
+    let timestamp = batch.get("timestamp")?.as_f64()? as i64;
+    let token_amount = read_amount(batch.get("tokenAmount")?)?;
+    let sol_amount = match batch.get("solAmount").and_then(read_amount) {
+        Some(sol_amount) => sol_amount,
+        None => (batch.get("price")?.as_f64()? * token_amount as f64).round() as u64,
+    };
+    Some(HeldBatch {
+        timestamp,
+        token_amount,
+        sol_amount,
+    })
+}
+
+fn read_current_diamond_hands_data(
+    transaction: &mut Transaction,
+    mints: &[&str],
+    wallets: &[&str],
+) -> Result<HashMap<(String, String), DiamondHandsData>, postgres::Error> {
+    let select_query = "
+        SELECT
+            mint,
+            wallet,
+            held_batches::text,
+            sold_holding_sum::text,
+            total_tokens::text,
+            realized_profit::text,
+            average_holding_time::text,
+            total_profit::text,
+            buy_count::text,
+            sell_count::text
+        FROM diamond_hands
+        WHERE (mint, wallet) IN (SELECT * FROM unnest($1::varchar[], $2::varchar[]))
+        FOR UPDATE;
+    ";
+
+    let rows = match transaction.query(select_query, &[&mints, &wallets]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error fetching diamond_hands: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let held_batches: Value = serde_json::from_str(row.get("held_batches")).unwrap();
+            let held_batches = held_batches
+                .as_array()
+                .map(|batches| batches.iter().filter_map(held_batch_from_json).collect())
+                .unwrap_or_default();
+            let data = DiamondHandsData {
+                held_batches,
+                sold_holding_sum: parse_numeric(row.get("sold_holding_sum")),
+                total_tokens: parse_numeric(row.get("total_tokens")),
+                realized_profit: parse_numeric(row.get("realized_profit")),
+                average_holding_time: parse_numeric(row.get("average_holding_time")),
+                total_profit: parse_numeric(row.get("total_profit")),
+                buy_count: parse_numeric(row.get("buy_count")) as i64,
+                sell_count: parse_numeric(row.get("sell_count")) as i64,
+            };
+            ((row.get("mint"), row.get("wallet")), data)
+        })
+        .collect())
+}
+
+// the (mint, wallet) pairs are returned in the order of their first trade
+fn apply_trades(
+    mut current_data: HashMap<(String, String), DiamondHandsData>,
+    trades: &[LastTrade],
+    block_time: i64,
+) -> Vec<((&str, &str), DiamondHandsData)> {
+    let mut updated_data: Vec<((&str, &str), DiamondHandsData)> = Vec::new();
+    let mut index_by_pair: HashMap<(&str, &str), usize> = HashMap::new();
+    for trade in trades {
+        let pair = (trade.mint.as_str(), trade.sender.as_str());
+        let index = *index_by_pair.entry(pair).or_insert_with(|| {
+            let data = current_data
+                .remove(&(pair.0.to_string(), pair.1.to_string()))
+                .unwrap_or_default();
+            updated_data.push((pair, data));
+            updated_data.len() - 1
+        });
+        updated_data[index].1.apply_trade(
+            &DiamondHandsTrade {
+                timestamp: trade.timestamp,
+                is_buy: trade.is_buy,
+                token_amount: trade.token_amount,
+                sol_amount: trade.sol_amount,
+            },
+            block_time,
+        );
+    }
+    updated_data
+}
+
+/// Only the executed trades are expected, in the order of the block.
+pub fn save_diamond_hands(
+    transaction: &mut Transaction,
+    trades: &[LastTrade],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if trades.is_empty() {
+        return Ok(0);
+    }
+
+    let mut pairs: Vec<(&str, &str)> = trades
+        .iter()
+        .map(|trade| (trade.mint.as_str(), trade.sender.as_str()))
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+    let mints: Vec<&str> = pairs.iter().map(|(mint, _)| *mint).collect();
+    let wallets: Vec<&str> = pairs.iter().map(|(_, wallet)| *wallet).collect();
+
+    let current_data = read_current_diamond_hands_data(transaction, &mints, &wallets)?;
+    let updated_data = apply_trades(current_data, trades, block_data.block_time);
+
+    let insert_query = format!(
+        "
+        INSERT INTO diamond_hands (
+            mint,
+            wallet,
+            held_batches,
+            sold_holding_sum,
+            total_tokens,
+            realized_profit,
+            average_holding_time,
+            total_profit,
+            buy_count,
+            sell_count,
+            updated
+        ) VALUES {}
+        ON CONFLICT (mint, wallet) DO UPDATE
+        SET
+            held_batches = EXCLUDED.held_batches,
+            sold_holding_sum = EXCLUDED.sold_holding_sum,
+            total_tokens = EXCLUDED.total_tokens,
+            realized_profit = EXCLUDED.realized_profit,
+            average_holding_time = EXCLUDED.average_holding_time,
+            total_profit = EXCLUDED.total_profit,
+            buy_count = EXCLUDED.buy_count,
+            sell_count = EXCLUDED.sell_count,
+            updated = EXCLUDED.updated;
+    ",
+        build_values_placeholders(
+            updated_data.len(),
+            &[
+                "::varchar",
+                "::varchar",
+                "::text::jsonb",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::bigint",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(updated_data.len() * 11);
+    for ((mint, wallet), data) in &updated_data {
+        let held_batches: Vec<Value> = data.held_batches.iter().map(held_batch_to_json).collect();
+
+        values.push(Box::new(mint.to_string()));
+        values.push(Box::new(wallet.to_string()));
+        values.push(Box::new(Value::Array(held_batches).to_string()));
+        values.push(Box::new(data.sold_holding_sum.to_string()));
+        values.push(Box::new(data.total_tokens.to_string()));
+        values.push(Box::new(data.realized_profit.to_string()));
+        values.push(Box::new(data.average_holding_time.to_string()));
+        values.push(Box::new(data.total_profit.to_string()));
+        values.push(Box::new(data.buy_count.to_string()));
+        values.push(Box::new(data.sell_count.to_string()));
+        values.push(Box::new(block_data.block_time));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating diamond_hands: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn build_trade(
+        mint: &str,
+        sender: &str,
+        timestamp: i64,
+        is_buy: bool,
+        token_amount: u64,
+        sol_amount: u64,
+    ) -> LastTrade {
+        LastTrade {
+            mint: mint.to_string(),
+            slot: 0,
+            transaction_index: 0,
+            instruction_index: 0,
+            timestamp,
+            is_buy,
+            token_amount,
+            sol_amount,
+            sender: sender.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_trades() {
+        let mut current_data = HashMap::new();
+        current_data.insert(
+            ("mint".to_string(), "wallet_a".to_string()),
+            DiamondHandsData {
+                held_batches: VecDeque::from([HeldBatch {
+                    timestamp: 0,
+                    token_amount: 1000,
+                    sol_amount: 100,
+                }]),
+                total_tokens: 1000,
+                buy_count: 1,
+                ..Default::default()
+            },
+        );
+        let trades = [
+            build_trade("mint", "wallet_b", 10, true, 500, 100),
+            build_trade("mint", "wallet_a", 10, true, 1000, 200),
+            build_trade("mint", "wallet_a", 20, false, 1500, 450),
+        ];
+
+        let updated_data = apply_trades(current_data, &trades, 20);
+        assert_eq!(updated_data.len(), 2);
+
+        let ((mint, wallet), data) = &updated_data[0];
+        assert_eq!((*mint, *wallet), ("mint", "wallet_b"));
+        assert_eq!(data.buy_count, 1);
+        assert_eq!(data.total_tokens, 500);
+        assert_eq!(data.average_holding_time, 10);
+
+        // the stored batch is sold first, then the half of the new one
+        let ((_, wallet), data) = &updated_data[1];
+        assert_eq!(*wallet, "wallet_a");
+        assert_eq!(
+            data.held_batches,
+            VecDeque::from([HeldBatch {
+                timestamp: 10,
+                token_amount: 500,
+                sol_amount: 100,
+            }])
+        );
+        assert_eq!(data.buy_count, 2);
+        assert_eq!(data.sell_count, 1);
+        assert_eq!(data.realized_profit, 450 - 200);
+        assert_eq!(data.total_profit, 250 + 150 - 100);
+    }
+
+    #[test]
+    fn test_held_batch_json() {
+        let batch = HeldBatch {
+            timestamp: 1739711240,
+            token_amount: 3_000_000,
+            sol_amount: 1_000_001,
+        };
+        assert_eq!(
+            held_batch_from_json(&held_batch_to_json(&batch)),
+            Some(batch)
+        );
+
+        let legacy_batch = json!({ "timestamp": 1739711000, "tokenAmount": 2000, "price": 0.25 });
+        assert_eq!(
+            held_batch_from_json(&legacy_batch),
+            Some(HeldBatch {
+                timestamp: 1739711000,
+                token_amount: 2000,
+                sol_amount: 500,
+            })
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/early-trades.storage.ts
+
+// Trades of a mint in its first slots (the snipers), of the protocols that
+// launch tokens. The mint slot comes from the mint cache, which has the mints
+// of the current block and reads the older ones from pump_data/moonshot_data.
+
+use postgres::{GenericClient, Transaction};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::mint_cache::{MintCache, MintData};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::IndexerEventSource;
+
+// slots after the mint slot (included) in which a trade is early, the env
+// variables of the same name replace them
+pub const PUMPFUN_EARLY_TRADES_SLOT_WINDOW: i64 = 10;
+pub const MOONSHOT_EARLY_TRADES_SLOT_WINDOW: i64 = 10;
+
+fn read_slot_window(name: &str, default: i64) -> i64 {
+    match std::env::var(name) {
+        Ok(slot_window) => slot_window
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number of slots", name)),
+        Err(_) => default,
+    }
+}
+
+static PUMPFUN_SLOT_WINDOW: LazyLock<i64> = LazyLock::new(|| {
+    read_slot_window(
+        "PUMPFUN_EARLY_TRADES_SLOT_WINDOW",
+        PUMPFUN_EARLY_TRADES_SLOT_WINDOW,
+    )
+});
+static MOONSHOT_SLOT_WINDOW: LazyLock<i64> = LazyLock::new(|| {
+    read_slot_window(
+        "MOONSHOT_EARLY_TRADES_SLOT_WINDOW",
+        MOONSHOT_EARLY_TRADES_SLOT_WINDOW,
+    )
+});
+
+pub fn early_trades_slot_window(protocol: IndexerEventSource) -> Option<i64> {
+    match protocol {
+        IndexerEventSource::Pumpfun => Some(*PUMPFUN_SLOT_WINDOW),
+        IndexerEventSource::Moonshot => Some(*MOONSHOT_SLOT_WINDOW),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EarlyTrade {
+    pub mint: String,
+    pub wallet: String,
+    pub transaction_id: String,
+    pub token_amount: u64,
+    pub is_buy: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sniper {
+    pub wallet: String,
+    // slots between the mint and the first trade of the wallet
+    pub first_trade_delay: i64,
+    pub bought_amount: u64,
+    pub sold_amount: u64,
+    pub trade_count: i64,
+}
+
+// a stored early trade, as read by get_snipers
+struct SniperTrade {
+    wallet: String,
+    trade_delay: i64,
+    token_amount: u64,
+    is_buy: bool,
+}
+
+/// Returns the trades in the window of their mint with the mint slot. The
+/// trades of the developer are not early trades. `slot_window` gives the
+/// window of each protocol, None for the protocols without early trades.
+pub fn select_early_trades<'a>(
+    trades: &'a [EarlyTrade],
+    mints_data: &HashMap<String, MintData>,
+    trade_slot: i64,
+    slot_window: impl Fn(IndexerEventSource) -> Option<i64>,
+) -> Vec<(&'a EarlyTrade, i64)> {
+    trades
+        .iter()
+        .filter_map(|trade| {
+            let mint_data = mints_data.get(&trade.mint)?;
+            let mint_slot = mint_data.create_event_slot?;
+            let slot_window = slot_window(mint_data.protocol)?;
+            if trade_slot < mint_slot
+                || trade_slot - mint_slot > slot_window
+                || mint_data.is_developer(&trade.wallet)
+            {
+                return None;
+            }
+            Some((trade, mint_slot))
+        })
+        .collect()
+}
+
+/// Only the executed trades are expected.
+pub fn save_early_trades(
+    transaction: &mut Transaction,
+    trades: &[EarlyTrade],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<u64, postgres::Error> {
+    if trades.is_empty() {
+        return Ok(0);
+    }
+
+    let mints: Vec<&str> = trades.iter().map(|trade| trade.mint.as_str()).collect();
+    let mints_data = mint_cache.resolve(transaction, &mints)?;
+
+    let early_trades = select_early_trades(
+        trades,
+        &mints_data,
+        block_data.slot,
+        early_trades_slot_window,
+    );
+    if early_trades.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO early_trades (
+            mint,
+            mint_slot,
+            wallet,
+            transaction_id,
+            token_amount,
+            is_buy,
+            trade_slot,
+            created
+        ) VALUES {};
+    ",
+        build_values_placeholders(
+            early_trades.len(),
+            &["", "", "", "", "::text::numeric", "", "", ""],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(early_trades.len() * 8);
+    for (trade, mint_slot) in early_trades {
+        values.push(Box::new(trade.mint.clone()));
+        values.push(Box::new(mint_slot));
+        values.push(Box::new(trade.wallet.clone()));
+        values.push(Box::new(trade.transaction_id.clone()));
+        values.push(Box::new(trade.token_amount.to_string()));
+        values.push(Box::new(trade.is_buy));
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(block_data.block_time as i32));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting early_trades: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+// only the wallets that bought, the first ones first
+fn build_snipers(trades: &[SniperTrade]) -> Vec<Sniper> {
+    let mut snipers: Vec<Sniper> = Vec::new();
+    let mut index_by_wallet: HashMap<&str, usize> = HashMap::new();
+    for trade in trades {
+        let index = *index_by_wallet
+            .entry(trade.wallet.as_str())
+            .or_insert_with(|| {
+                snipers.push(Sniper {
+                    wallet: trade.wallet.clone(),
+                    first_trade_delay: trade.trade_delay,
+                    bought_amount: 0,
+                    sold_amount: 0,
+                    trade_count: 0,
+                });
+                snipers.len() - 1
+            });
+        let sniper = &mut snipers[index];
+        sniper.first_trade_delay = sniper.first_trade_delay.min(trade.trade_delay);
+        if trade.is_buy {
+            sniper.bought_amount += trade.token_amount;
+        } else {
+            sniper.sold_amount += trade.token_amount;
+        }
+        sniper.trade_count += 1;
+    }
+
+    snipers.retain(|sniper| sniper.bought_amount > 0);
+    snipers.sort_by(|a, b| (a.first_trade_delay, &a.wallet).cmp(&(b.first_trade_delay, &b.wallet)));
+    snipers
+}
+
+/// Wallets that bought the mint in its first slots, the first ones first. The
+/// indexer only writes early_trades, this is the query of its readers.
+#[allow(dead_code)]
+pub fn get_snipers(
+    client: &mut impl GenericClient,
+    mint: &str,
+) -> Result<Vec<Sniper>, postgres::Error> {
+    let select_query = "
+        SELECT wallet, trade_slot - mint_slot AS trade_delay, token_amount::text, is_buy
+        FROM early_trades
+        WHERE mint = $1;
+    ";
+
+    let rows = match client.query(select_query, &[&mint]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading early_trades: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    let trades: Vec<SniperTrade> = rows
+        .iter()
+        .map(|row| SniperTrade {
+            wallet: row.get("wallet"),
+            trade_delay: row.get("trade_delay"),
+            token_amount: row
+                .get::<_, Option<&str>>("token_amount")
+                .map_or(0, |amount| amount.parse().unwrap()),
+            is_buy: row.get::<_, Option<bool>>("is_buy").unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(build_snipers(&trades))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_trade(mint: &str, wallet: &str) -> EarlyTrade {
+        EarlyTrade {
+            mint: mint.to_string(),
+            wallet: wallet.to_string(),
+            transaction_id: "signature".to_string(),
+            token_amount: 1000,
+            is_buy: true,
+        }
+    }
+
+    fn build_mint_data(mint: &str, protocol: IndexerEventSource, slot: i64) -> (String, MintData) {
+        (
+            mint.to_string(),
+            MintData {
+                mint: mint.to_string(),
+                protocol,
+                bonding_curve: Some("curve".to_string()),
+                developer: Some("dev".to_string()),
+                create_event_slot: Some(slot),
+            },
+        )
+    }
+
+    #[test]
+    fn test_select_early_trades() {
+        let mints_data = HashMap::from([
+            build_mint_data("pump", IndexerEventSource::Pumpfun, 100),
+            build_mint_data("moon", IndexerEventSource::Moonshot, 90),
+            build_mint_data("future", IndexerEventSource::Pumpfun, 120),
+        ]);
+        let trades = [
+            build_trade("pump", "sniper"),
+            build_trade("pump", "dev"),
+            build_trade("moon", "late"),
+            build_trade("future", "sniper"),
+            build_trade("unknown", "sniper"),
+        ];
+
+        let slot_window = |protocol| match protocol {
+            IndexerEventSource::Pumpfun => Some(10),
+            IndexerEventSource::Moonshot => Some(5),
+            _ => None,
+        };
+        let early_trades = select_early_trades(&trades, &mints_data, 110, slot_window);
+        assert_eq!(early_trades.len(), 1);
+        assert_eq!(early_trades[0].0.wallet, "sniper");
+        assert_eq!(early_trades[0].1, 100);
+    }
+
+    fn build_sniper_trade(
+        wallet: &str,
+        trade_delay: i64,
+        token_amount: u64,
+        is_buy: bool,
+    ) -> SniperTrade {
+        SniperTrade {
+            wallet: wallet.to_string(),
+            trade_delay,
+            token_amount,
+            is_buy,
+        }
+    }
+
+    #[test]
+    fn test_build_snipers() {
+        let trades = [
+            build_sniper_trade("b", 2, 500, true),
+            build_sniper_trade("a", 3, 1000, true),
+            build_sniper_trade("b", 1, 200, true),
+            build_sniper_trade("b", 4, 300, false),
+            build_sniper_trade("seller", 0, 100, false),
+            build_sniper_trade("c", 1, 100, true),
+        ];
+
+        assert_eq!(
+            build_snipers(&trades),
+            vec![
+                Sniper {
+                    wallet: "b".to_string(),
+                    first_trade_delay: 1,
+                    bought_amount: 700,
+                    sold_amount: 300,
+                    trade_count: 3,
+                },
+                Sniper {
+                    wallet: "c".to_string(),
+                    first_trade_delay: 1,
+                    bought_amount: 100,
+                    sold_amount: 0,
+                    trade_count: 1,
+                },
+                Sniper {
+                    wallet: "a".to_string(),
+                    first_trade_delay: 3,
+                    bought_amount: 1000,
+                    sold_amount: 0,
+                    trade_count: 1,
+                },
+            ]
+        );
+    }
+}
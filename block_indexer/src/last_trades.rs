@@ -0,0 +1,396 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/last-trades.storage.ts
+
+// Newest trades of every mint (of all the protocols), stored as a buffer of
+// LAST_TRADES_COUNT entries per mint. The entries are ordered by their position
+// in the chain (slot, transaction, instruction), newest first.
+
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use postgres::Transaction;
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::LazyLock;
+
+use crate::storage::{
+    build_values_placeholders, execute_with_values, BlockData, SqlValue, StorageError,
+};
+
+pub const LAST_TRADES_COUNT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LastTradesEncoding {
+    // last_trades (jsonb), merged by the database
+    Json,
+    // last_trades_zip (gzipped json), merged here
+    Compressed,
+}
+
+impl LastTradesEncoding {
+    pub fn parse(encoding: &str) -> Option<Self> {
+        match encoding {
+            "json" => Some(LastTradesEncoding::Json),
+            "compressed" => Some(LastTradesEncoding::Compressed),
+            _ => None,
+        }
+    }
+}
+
+// LAST_TRADES_ENCODING selects the table: json (default) or compressed
+pub static LAST_TRADES_ENCODING: LazyLock<LastTradesEncoding> =
+    LazyLock::new(|| match std::env::var("LAST_TRADES_ENCODING") {
+        Ok(encoding) => LastTradesEncoding::parse(&encoding)
+            .unwrap_or_else(|| panic!("Unknown LAST_TRADES_ENCODING: {}", encoding)),
+        Err(_) => LastTradesEncoding::Json,
+    });
+
+#[derive(Debug, Clone)]
+pub struct LastTrade {
+    pub mint: String,
+    pub slot: i64,
+    pub transaction_index: u32,
+    pub instruction_index: u32,
+    pub timestamp: i64,
+    pub is_buy: bool,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub sender: String,
+}
+
+impl LastTrade {
+    // the amounts are strings, as the ones written by the Node indexer
+    pub fn to_json(&self) -> Value {
+        json!({
+            "slot": self.slot,
+            "txIndex": self.transaction_index,
+            "ixIndex": self.instruction_index,
+            "timestamp": self.timestamp,
+            "isBuy": self.is_buy,
+            "tokenAmount": self.token_amount.to_string(),
+            "solAmount": self.sol_amount.to_string(),
+            "sender": self.sender,
+        })
+    }
+}
+
+// None for the entries written by the Node indexer (without position)
+fn trade_position(trade: &Value) -> Option<(i64, i64, i64)> {
+    Some((
+        trade.get("slot")?.as_i64()?,
+        trade.get("txIndex")?.as_i64()?,
+        trade.get("ixIndex")?.as_i64()?,
+    ))
+}
+
+// newest first, the entries without position go to the end in their order
+fn compare_trades(a: &Value, b: &Value) -> Ordering {
+    match (trade_position(a), trade_position(b)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Adds the new trades to the buffer of a mint. The trades that are already in
+/// the buffer (a block indexed again) are not repeated.
+pub fn merge_last_trades(current_trades: Vec<Value>, new_trades: Vec<Value>) -> Vec<Value> {
+    let mut trades = current_trades;
+    for new_trade in new_trades {
+        let position = trade_position(&new_trade);
+        if position.is_some() && trades.iter().any(|trade| trade_position(trade) == position) {
+            continue;
+        }
+        trades.push(new_trade);
+    }
+    trades.sort_by(compare_trades);
+    trades.truncate(LAST_TRADES_COUNT);
+    trades
+}
+
+fn group_by_mint(last_trades: &[LastTrade]) -> Vec<(&str, Vec<Value>)> {
+    let mut trades_by_mint: Vec<(&str, Vec<Value>)> = Vec::new();
+    let mut index_by_mint: HashMap<&str, usize> = HashMap::new();
+    for last_trade in last_trades {
+        let index = *index_by_mint
+            .entry(last_trade.mint.as_str())
+            .or_insert_with(|| {
+                trades_by_mint.push((&last_trade.mint, Vec::new()));
+                trades_by_mint.len() - 1
+            });
+        trades_by_mint[index].1.push(last_trade.to_json());
+    }
+    trades_by_mint
+        .into_iter()
+        .map(|(mint, trades)| (mint, merge_last_trades(Vec::new(), trades)))
+        .collect()
+}
+
+/// Only the executed trades are expected.
+pub fn save_last_trades(
+    transaction: &mut Transaction,
+    last_trades: &[LastTrade],
+    block_data: &BlockData,
+) -> Result<u64, StorageError> {
+    if last_trades.is_empty() {
+        return Ok(0);
+    }
+
+    match *LAST_TRADES_ENCODING {
+        LastTradesEncoding::Json => {
+            Ok(save_last_trades_json(transaction, last_trades, block_data)?)
+        }
+        LastTradesEncoding::Compressed => {
+            save_last_trades_zip(transaction, last_trades, block_data)
+        }
+    }
+}
+
+/// The buffers are merged by the query, a single round trip for all the mints.
+/// A buffer is only updated by blocks not older than its updated_slot.
+fn save_last_trades_json(
+    transaction: &mut Transaction,
+    last_trades: &[LastTrade],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let trades_by_mint = group_by_mint(last_trades);
+    let slot_placeholder = trades_by_mint.len() * 2 + 1;
+
+    let insert_query = format!(
+        "
+        WITH new_trades (mint, trades) AS (
+            VALUES {}
+        )
+        INSERT INTO last_trades (mint, trades, updated_slot)
+        SELECT
+            new_trades.mint,
+            (
+                SELECT jsonb_agg(
+                    merged.trade
+                    ORDER BY merged.slot DESC NULLS LAST, merged.tx_index DESC NULLS LAST,
+                        merged.ix_index DESC NULLS LAST, merged.position
+                )
+                FROM (
+                    SELECT
+                        all_trades.trade,
+                        all_trades.position,
+                        (all_trades.trade->>'slot')::bigint AS slot,
+                        (all_trades.trade->>'txIndex')::int AS tx_index,
+                        (all_trades.trade->>'ixIndex')::int AS ix_index
+                    FROM jsonb_array_elements(
+                        COALESCE(last_trades.trades, '[]'::jsonb) || (
+                            SELECT COALESCE(jsonb_agg(new_trade), '[]'::jsonb)
+                            FROM jsonb_array_elements(new_trades.trades) AS new_trade
+                            WHERE NOT COALESCE(last_trades.trades, '[]'::jsonb) @> jsonb_build_array(
+                                jsonb_build_object(
+                                    'slot', new_trade->'slot',
+                                    'txIndex', new_trade->'txIndex',
+                                    'ixIndex', new_trade->'ixIndex'
+                                )
+                            )
+                        )
+                    ) WITH ORDINALITY AS all_trades (trade, position)
+                    ORDER BY slot DESC NULLS LAST, tx_index DESC NULLS LAST,
+                        ix_index DESC NULLS LAST, position
+                    LIMIT {}
+                ) AS merged
+            ),
+            ${}::bigint
+        FROM new_trades
+        LEFT JOIN last_trades ON last_trades.mint = new_trades.mint
+        WHERE last_trades.updated_slot IS NULL OR last_trades.updated_slot <= ${}::bigint
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            trades = EXCLUDED.trades,
+            updated_slot = EXCLUDED.updated_slot
+        WHERE last_trades.updated_slot <= EXCLUDED.updated_slot;
+    ",
+        build_values_placeholders(trades_by_mint.len(), &["::varchar", "::text::jsonb"]),
+        LAST_TRADES_COUNT,
+        slot_placeholder,
+        slot_placeholder,
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(trades_by_mint.len() * 2 + 1);
+    for (mint, trades) in &trades_by_mint {
+        values.push(Box::new(mint.to_string()));
+        values.push(Box::new(Value::Array(trades.clone()).to_string()));
+    }
+    values.push(Box::new(block_data.slot));
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating last_trades: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn compress_trades(trades: &[Value]) -> std::io::Result<Vec<u8>> {
+    let json_str = Value::Array(trades.to_vec()).to_string();
+    let mut encoder = GzEncoder::new(json_str.as_bytes(), Compression::default());
+    let mut compressed_json = Vec::new();
+    encoder.read_to_end(&mut compressed_json)?;
+    Ok(compressed_json)
+}
+
+fn decompress_trades(compressed_json: &[u8]) -> std::io::Result<Vec<Value>> {
+    let mut decoder = GzDecoder::new(compressed_json);
+    let mut json_data = Vec::new();
+    decoder.read_to_end(&mut json_data)?;
+    match serde_json::from_slice(&json_data) {
+        Ok(Value::Array(trades)) => Ok(trades),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// The database can't merge compressed buffers, so they are read (and locked),
+/// merged here and written back.
+fn save_last_trades_zip(
+    transaction: &mut Transaction,
+    last_trades: &[LastTrade],
+    block_data: &BlockData,
+) -> Result<u64, StorageError> {
+    let trades_by_mint = group_by_mint(last_trades);
+    let mints: Vec<&str> = trades_by_mint.iter().map(|(mint, _)| *mint).collect();
+
+    let select_query = "
+        SELECT mint, compressed_json, updated_slot
+        FROM last_trades_zip
+        WHERE mint = ANY($1)
+        FOR UPDATE;
+    ";
+
+    let rows = match transaction.query(select_query, &[&mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading last_trades_zip: {:?}", err);
+            return Err(err.into());
+        }
+    };
+
+    let mut current_trades_by_mint: HashMap<String, (Vec<u8>, i64)> = rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("mint"),
+                (row.get("compressed_json"), row.get("updated_slot")),
+            )
+        })
+        .collect();
+
+    let mut merged_trades_by_mint: Vec<(&str, Vec<u8>)> = Vec::new();
+    for (mint, trades) in trades_by_mint {
+        let current_trades = match current_trades_by_mint.remove(mint) {
+            Some((_, updated_slot)) if updated_slot > block_data.slot => continue,
+            Some((compressed_json, _)) => match decompress_trades(&compressed_json) {
+                Ok(current_trades) => current_trades,
+                Err(err) => {
+                    println!("Error decompressing last_trades_zip of {}: {:?}", mint, err);
+                    return Err(err.into());
+                }
+            },
+            None => Vec::new(),
+        };
+        let merged_trades = merge_last_trades(current_trades, trades);
+        merged_trades_by_mint.push((mint, compress_trades(&merged_trades)?));
+    }
+
+    if merged_trades_by_mint.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO last_trades_zip (
+            mint,
+            compressed_json,
+            updated_slot
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            compressed_json = EXCLUDED.compressed_json,
+            updated_slot = EXCLUDED.updated_slot;
+    ",
+        build_values_placeholders(merged_trades_by_mint.len(), &["", "", ""])
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(merged_trades_by_mint.len() * 3);
+    for (mint, compressed_json) in merged_trades_by_mint {
+        values.push(Box::new(mint.to_string()));
+        values.push(Box::new(compressed_json));
+        values.push(Box::new(block_data.slot));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating last_trades_zip: {:?}", err);
+            Err(err.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_trade(slot: i64, transaction_index: u32, instruction_index: u32) -> Value {
+        LastTrade {
+            mint: "mint".to_string(),
+            slot,
+            transaction_index,
+            instruction_index,
+            timestamp: 1739711240,
+            is_buy: true,
+            token_amount: 1000,
+            sol_amount: 10,
+            sender: "sender".to_string(),
+        }
+        .to_json()
+    }
+
+    #[test]
+    fn test_merge_last_trades() {
+        let legacy_trade = json!({ "timestamp": 1739711000, "isBuy": false });
+        let current_trades = vec![build_trade(10, 5, 0), build_trade(10, 2, 3), legacy_trade];
+
+        // a trade of the same block (already stored) and two new ones
+        let new_trades = vec![
+            build_trade(10, 2, 3),
+            build_trade(11, 0, 1),
+            build_trade(10, 7, 0),
+        ];
+        let merged_trades = merge_last_trades(current_trades, new_trades);
+
+        let positions: Vec<Option<(i64, i64, i64)>> =
+            merged_trades.iter().map(trade_position).collect();
+        assert_eq!(
+            positions,
+            vec![
+                Some((11, 0, 1)),
+                Some((10, 7, 0)),
+                Some((10, 5, 0)),
+                Some((10, 2, 3)),
+                None
+            ]
+        );
+
+        let many_trades: Vec<Value> = (0..80).map(|slot| build_trade(slot, 0, 0)).collect();
+        let merged_trades = merge_last_trades(merged_trades, many_trades);
+        assert_eq!(merged_trades.len(), LAST_TRADES_COUNT);
+        assert_eq!(trade_position(&merged_trades[0]), Some((79, 0, 0)));
+
+        let compressed_json = compress_trades(&merged_trades).unwrap();
+        assert_eq!(decompress_trades(&compressed_json).unwrap(), merged_trades);
+        assert!(decompress_trades(b"not gzip").is_err());
+
+        assert_eq!(
+            LastTradesEncoding::parse("compressed"),
+            Some(LastTradesEncoding::Compressed)
+        );
+        assert_eq!(LastTradesEncoding::parse("zip"), None);
+    }
+}
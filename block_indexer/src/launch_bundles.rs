@@ -0,0 +1,325 @@
+// Wallets that bought a token in the slot of its creation (bundled with the
+// creation transaction or sniped in the same block), and the ones among them
+// that were funded by the creator shortly before.
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::moonshot::{
+    MoonshotTokenMintFunctionCallEventMeta, MoonshotTradeFunctionCallEventMeta, MoonshotTradeType,
+};
+use crate::pumpfun::{PumpfunTokenMintFunctionCallEventMeta, PumpfunTradeFunctionCallEventMeta};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::{Event, GenericEventType};
+
+pub mod funding;
+use funding::FundingRegistry;
+
+#[derive(Debug, Clone)]
+pub struct Launch<'a> {
+    pub mint: &'a str,
+    pub creator: &'a str,
+    pub transaction_index: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct LaunchBuy<'a> {
+    pub mint: &'a str,
+    pub wallet: &'a str,
+    pub transaction_index: u32,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchBundle<'a> {
+    pub mint: &'a str,
+    pub creator: &'a str,
+    pub wallet: &'a str,
+    // bought in the creation transaction
+    pub in_creation_transaction: bool,
+    // sent by the creator before the first buy of the wallet
+    pub funding_lamports: u64,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub buy_count: i32,
+}
+
+/// Returns the executed token creations and buys of Pump.fun and Moonshot.
+pub fn read_launch_events(events: &[Event]) -> (Vec<Launch<'_>>, Vec<LaunchBuy<'_>>) {
+    let mut launches = Vec::new();
+    let mut buys = Vec::new();
+
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        let transaction_index = function_call_event.transaction_index;
+        let event_meta = function_call_event.event_meta.as_any();
+
+        match function_call_event.event_type {
+            GenericEventType::TokenMint => {
+                if let Some(event_meta) =
+                    event_meta.downcast_ref::<PumpfunTokenMintFunctionCallEventMeta>()
+                {
+                    if !event_meta.failed_transaction {
+                        launches.push(Launch {
+                            mint: &event_meta.event_log.mint,
+                            creator: &event_meta.event_log.user,
+                            transaction_index,
+                        });
+                    }
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<MoonshotTokenMintFunctionCallEventMeta>()
+                {
+                    if !event_meta.failed_transaction {
+                        launches.push(Launch {
+                            mint: &event_meta.mint,
+                            creator: &event_meta.sender,
+                            transaction_index,
+                        });
+                    }
+                }
+            }
+            GenericEventType::Trade => {
+                if let Some(event_meta) =
+                    event_meta.downcast_ref::<PumpfunTradeFunctionCallEventMeta>()
+                {
+                    if let Some(trade_event) = &event_meta.event_log {
+                        if trade_event.is_buy {
+                            buys.push(LaunchBuy {
+                                mint: &trade_event.mint,
+                                wallet: &trade_event.user,
+                                transaction_index,
+                                token_amount: trade_event.token_amount,
+                                sol_amount: trade_event.sol_amount,
+                            });
+                        }
+                    }
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<MoonshotTradeFunctionCallEventMeta>()
+                {
+                    if !event_meta.failed_transaction
+                        && event_meta.event_log.is_some()
+                        && matches!(event_meta.trade_type, MoonshotTradeType::Buy)
+                    {
+                        buys.push(LaunchBuy {
+                            mint: &event_meta.mint,
+                            wallet: &event_meta.sender,
+                            transaction_index,
+                            token_amount: event_meta.token_amount,
+                            sol_amount: event_meta.collateral_amount,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (launches, buys)
+}
+
+/// The buys of the creator itself are not part of the bundles.
+pub fn detect_launch_bundles<'a>(
+    launches: &[Launch<'a>],
+    buys: &[LaunchBuy<'a>],
+    funding: &FundingRegistry,
+    slot: i64,
+) -> Vec<LaunchBundle<'a>> {
+    let launches_by_mint: HashMap<&str, &Launch> = launches
+        .iter()
+        .map(|launch| (launch.mint, launch))
+        .collect();
+
+    let mut bundles: Vec<LaunchBundle> = Vec::new();
+    let mut index_by_pair: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for buy in buys {
+        let Some(launch) = launches_by_mint.get(buy.mint) else {
+            continue;
+        };
+        // a buy before the creation can't be of this token
+        if buy.wallet == launch.creator || buy.transaction_index < launch.transaction_index {
+            continue;
+        }
+
+        let index = *index_by_pair
+            .entry((buy.mint, buy.wallet))
+            .or_insert_with(|| {
+                bundles.push(LaunchBundle {
+                    mint: launch.mint,
+                    creator: launch.creator,
+                    wallet: buy.wallet,
+                    in_creation_transaction: false,
+                    funding_lamports: funding.funded_lamports(
+                        launch.creator,
+                        buy.wallet,
+                        (slot, buy.transaction_index),
+                    ),
+                    token_amount: 0,
+                    sol_amount: 0,
+                    buy_count: 0,
+                });
+                bundles.len() - 1
+            });
+
+        let bundle = &mut bundles[index];
+        bundle.in_creation_transaction |= buy.transaction_index == launch.transaction_index;
+        bundle.token_amount += buy.token_amount;
+        bundle.sol_amount += buy.sol_amount;
+        bundle.buy_count += 1;
+    }
+
+    bundles
+}
+
+/// `funding` must already have the transfers of the block.
+pub fn save_launch_bundles(
+    transaction: &mut Transaction,
+    events: &[Event],
+    block_data: &BlockData,
+    funding: &FundingRegistry,
+) -> Result<u64, postgres::Error> {
+    let (launches, buys) = read_launch_events(events);
+    if launches.is_empty() {
+        return Ok(0);
+    }
+
+    let bundles = detect_launch_bundles(&launches, &buys, funding, block_data.slot);
+    if bundles.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO launch_bundles (
+            mint,
+            mint_slot,
+            creator,
+            wallet,
+            in_creation_transaction,
+            funded_by_creator,
+            funding_lamports,
+            token_amount,
+            sol_amount,
+            buy_count,
+            created
+        ) VALUES {}
+        ON CONFLICT (mint, wallet) DO NOTHING;
+    ",
+        build_values_placeholders(
+            bundles.len(),
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(bundles.len() * 11);
+    for bundle in &bundles {
+        values.push(Box::new(bundle.mint.to_string()));
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(bundle.creator.to_string()));
+        values.push(Box::new(bundle.wallet.to_string()));
+        values.push(Box::new(bundle.in_creation_transaction));
+        values.push(Box::new(bundle.funding_lamports > 0));
+        values.push(Box::new(bundle.funding_lamports.to_string()));
+        values.push(Box::new(bundle.token_amount.to_string()));
+        values.push(Box::new(bundle.sol_amount.to_string()));
+        values.push(Box::new(bundle.buy_count));
+        values.push(Box::new(block_data.block_time as i32));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting launch_bundles: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::funding::SolTransfer;
+    use super::*;
+
+    fn build_buy<'a>(wallet: &'a str, transaction_index: u32) -> LaunchBuy<'a> {
+        LaunchBuy {
+            mint: "mint",
+            wallet,
+            transaction_index,
+            token_amount: 1000,
+            sol_amount: 10,
+        }
+    }
+
+    fn build_transfer(to: &str, transaction_index: u32, lamports: u64) -> SolTransfer {
+        SolTransfer {
+            from: "creator".to_string(),
+            to: to.to_string(),
+            transaction_index,
+            lamports,
+        }
+    }
+
+    #[test]
+    fn test_detect_launch_bundles() {
+        let mut funding = FundingRegistry::new();
+        funding.add_transfers(50, vec![build_transfer("expired", 0, 1)]);
+        funding.add_transfers(60, vec![build_transfer("funded", 0, 5_000_000)]);
+        funding.add_transfers(
+            100,
+            vec![
+                build_transfer("expired", 0, 1),
+                build_transfer("late", 7, 1_000),
+            ],
+        );
+
+        let launches = [Launch {
+            mint: "mint",
+            creator: "creator",
+            transaction_index: 3,
+        }];
+        let buys = [
+            build_buy("early", 1),
+            build_buy("creator", 3),
+            build_buy("bundled", 3),
+            build_buy("funded", 5),
+            build_buy("funded", 6),
+            build_buy("late", 6),
+            LaunchBuy {
+                mint: "other",
+                ..build_buy("sniper", 4)
+            },
+        ];
+
+        let bundles = detect_launch_bundles(&launches, &buys, &funding, 100);
+        assert_eq!(bundles.len(), 3);
+        assert_eq!(bundles[0].wallet, "bundled");
+        assert!(bundles[0].in_creation_transaction);
+        assert_eq!(bundles[0].funding_lamports, 0);
+
+        assert_eq!(bundles[1].wallet, "funded");
+        assert!(!bundles[1].in_creation_transaction);
+        assert_eq!(bundles[1].funding_lamports, 5_000_000);
+        assert_eq!(bundles[1].buy_count, 2);
+        assert_eq!(bundles[1].token_amount, 2000);
+
+        // funded after the buy
+        assert_eq!(bundles[2].wallet, "late");
+        assert_eq!(bundles[2].funding_lamports, 0);
+
+        // the transfer of slot 50 is out of the window
+        assert_eq!(funding.funded_lamports("creator", "expired", (100, 1)), 1);
+    }
+}
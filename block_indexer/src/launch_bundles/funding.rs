@@ -0,0 +1,186 @@
+// Recent SOL transfers, kept in memory to find the wallets funded by a creator
+// shortly before its launch. After a restart the first blocks have a shorter
+// window.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::solana::{SolTransferFunctionCallEventMeta, SolanaInstructionData};
+use crate::{Event, GenericEventType};
+
+// slots (~20 seconds) a transfer is kept
+pub const FUNDING_SLOT_WINDOW: i64 = 50;
+
+// slot and transaction index
+pub type ChainPosition = (i64, u32);
+
+#[derive(Debug, Clone)]
+pub struct SolTransfer {
+    pub from: String,
+    pub to: String,
+    pub transaction_index: u32,
+    pub lamports: u64,
+}
+
+pub struct FundingRegistry {
+    // (from, to) -> transfers, the oldest first
+    transfers: HashMap<(String, String), VecDeque<(ChainPosition, u64)>>,
+    // the pairs with transfers of every slot, the oldest slot first
+    pairs_by_slot: VecDeque<(i64, Vec<(String, String)>)>,
+    // transfers of the block being stored, added on commit
+    pending_transfers: Option<(i64, Vec<SolTransfer>)>,
+}
+
+impl FundingRegistry {
+    pub fn new() -> Self {
+        FundingRegistry {
+            transfers: HashMap::new(),
+            pairs_by_slot: VecDeque::new(),
+            pending_transfers: None,
+        }
+    }
+
+    /// The transfers of a slot are added together, after the ones of the
+    /// previous slots.
+    pub fn add_transfers(&mut self, slot: i64, transfers: Vec<SolTransfer>) {
+        let mut pairs = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            let pair = (transfer.from, transfer.to);
+            self.transfers
+                .entry(pair.clone())
+                .or_default()
+                .push_back(((slot, transfer.transaction_index), transfer.lamports));
+            pairs.push(pair);
+        }
+        self.pairs_by_slot.push_back((slot, pairs));
+
+        while let Some((oldest_slot, _)) = self.pairs_by_slot.front() {
+            if *oldest_slot > slot - FUNDING_SLOT_WINDOW {
+                break;
+            }
+            let (oldest_slot, pairs) = self.pairs_by_slot.pop_front().unwrap();
+            for pair in pairs {
+                let Some(transfers) = self.transfers.get_mut(&pair) else {
+                    continue;
+                };
+                while transfers
+                    .front()
+                    .is_some_and(|((transfer_slot, _), _)| *transfer_slot <= oldest_slot)
+                {
+                    transfers.pop_front();
+                }
+                if transfers.is_empty() {
+                    self.transfers.remove(&pair);
+                }
+            }
+        }
+    }
+
+    /// Stages the executed SOL transfers of a block, they are added by `commit`
+    /// once the block is stored.
+    pub fn add_transfer_events(&mut self, events: &[Event], slot: i64) {
+        let mut transfers = Vec::new();
+        for event in events {
+            let Event::FunctionCall(function_call_event) = event;
+            if function_call_event.event_type != GenericEventType::SolTransfer {
+                continue;
+            }
+            let Some(event_meta) = function_call_event
+                .event_meta
+                .as_any()
+                .downcast_ref::<SolTransferFunctionCallEventMeta>()
+            else {
+                continue;
+            };
+            let Some(SolanaInstructionData::SystemTransfer(values)) = function_call_event
+                .event_obj
+                .as_any()
+                .downcast_ref::<SolanaInstructionData>()
+            else {
+                continue;
+            };
+            if event_meta.failed_transaction {
+                continue;
+            }
+            transfers.push(SolTransfer {
+                from: event_meta.from_address.clone(),
+                to: event_meta.to_address.clone(),
+                transaction_index: function_call_event.transaction_index,
+                lamports: values.lamports,
+            });
+        }
+        self.pending_transfers = Some((slot, transfers));
+    }
+
+    pub fn commit(&mut self) {
+        if let Some((slot, transfers)) = self.pending_transfers.take() {
+            self.add_transfers(slot, transfers);
+        }
+    }
+
+    pub fn rollback(&mut self) {
+        self.pending_transfers = None;
+    }
+
+    /// Lamports sent by `from` to `to` in the window, before `position`. The
+    /// staged transfers of the block count too.
+    pub fn funded_lamports(&self, from: &str, to: &str, position: ChainPosition) -> u64 {
+        let stored_lamports: u64 = self
+            .transfers
+            .get(&(from.to_string(), to.to_string()))
+            .map(|transfers| {
+                transfers
+                    .iter()
+                    .filter(|(transfer_position, _)| *transfer_position < position)
+                    .map(|(_, lamports)| lamports)
+                    .sum()
+            })
+            .unwrap_or(0);
+        let pending_lamports: u64 = self
+            .pending_transfers
+            .iter()
+            .flat_map(|(slot, transfers)| {
+                transfers.iter().filter(move |transfer| {
+                    transfer.from == from
+                        && transfer.to == to
+                        && (*slot, transfer.transaction_index) < position
+                })
+            })
+            .map(|transfer| transfer.lamports)
+            .sum();
+        stored_lamports + pending_lamports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_transfers() {
+        let mut funding = FundingRegistry::new();
+        let transfer = SolTransfer {
+            from: "creator".to_string(),
+            to: "wallet".to_string(),
+            transaction_index: 2,
+            lamports: 1_000,
+        };
+
+        // the staged transfers of the block count before they are committed
+        funding.pending_transfers = Some((100, vec![transfer.clone()]));
+        assert_eq!(
+            funding.funded_lamports("creator", "wallet", (100, 3)),
+            1_000
+        );
+        assert_eq!(funding.funded_lamports("creator", "wallet", (100, 2)), 0);
+        funding.rollback();
+        assert_eq!(funding.funded_lamports("creator", "wallet", (100, 3)), 0);
+
+        funding.pending_transfers = Some((100, vec![transfer]));
+        funding.commit();
+        assert!(funding.pending_transfers.is_none());
+        assert_eq!(
+            funding.funded_lamports("creator", "wallet", (101, 0)),
+            1_000
+        );
+    }
+}
@@ -1,20 +1,40 @@
 // use as reference: js_src/src/fetching/block-indexer.ts
 
-// enable unused vars
-#![allow(unused)]
-
 use flate2::read::GzDecoder;
-use num_bigint::BigUint;
 use postgres::Client;
-use serde_json;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::any::Any;
+use std::io::Read;
 use std::time::Instant;
 
 mod blockchain_data;
+mod compute_budget;
+mod diamond_hands;
+mod early_trades;
+mod last_trades;
+mod launch_bundles;
+mod mint_cache;
 mod moonshot;
+mod parsing;
+mod positions;
+mod pumpfun;
+mod raydium;
+mod sandwiches;
+mod sol_price;
+mod solana;
+mod storage;
+mod time_series;
+mod token_lifecycle;
+mod transaction_log;
+use compute_budget::{read_transaction_fees, TransactionFees};
+use early_trades::early_trades_slot_window;
+use last_trades::LAST_TRADES_ENCODING;
 use moonshot::MoonshotParser;
+use pumpfun::PumpfunParser;
+use raydium::RaydiumParser;
+use solana::jito::read_jito_tip;
+use solana::SolanaParser;
+use storage::{build_events_report, save_events, BlockData, StorageCache};
+use transaction_log::parse_transaction_logs;
 
 fn main() {
     // // read tx.json
@@ -22,7 +42,7 @@ fn main() {
     // let reader = BufReader::new(file);
     // let data_obj = serde_json::from_reader(reader).unwrap();
 
-    // let events = parse_transaction(1, 1739711240, &data_obj);
+    // let events = parse_transaction(1739711240, &data_obj);
     // // println!("events: {:?}", events);
     //////////////////////////////////////////////////////////////////////////////////////////////////////
     let mut client = Client::connect(
@@ -31,6 +51,15 @@ fn main() {
     )
     .unwrap();
 
+    let mut cache = StorageCache::load(&mut client).unwrap();
+    println!("Raydium pools: {}", cache.raydium_pools.len());
+    println!("Last trades encoding: {:?}", *LAST_TRADES_ENCODING);
+    println!(
+        "Early trades slot windows: Pumpfun {:?}, Moonshot {:?}",
+        early_trades_slot_window(IndexerEventSource::Pumpfun),
+        early_trades_slot_window(IndexerEventSource::Moonshot)
+    );
+
     loop {
         let query = "SELECT slot, compressed_json FROM block_json where indexed IS NULL ORDER BY slot LIMIT 10;";
         let result = client.query(query, &[]);
@@ -67,9 +96,26 @@ fn main() {
 
             let save_begin = Instant::now();
 
-            let (_rows_count, events_by_source_and_type) = save_events(&events);
+            let block_data = BlockData {
+                slot: slot as i64,
+                block_time,
+                sol_price: cache
+                    .sol_price
+                    .update(&events, slot as i64, &cache.raydium_pools),
+            };
+
+            let result = save_events(&mut client, &block_data, &events, &mut cache);
 
-            let _report = build_events_report(&events_by_source_and_type);
+            if result.is_err() {
+                println!("Error saving events of slot {}: {:?}", slot, result.err());
+                std::process::exit(1);
+            }
+
+            let (rows_count, events_by_source_and_type) = result.unwrap();
+
+            let report = build_events_report(&events_by_source_and_type);
+            println!("Rows: {} ({})", rows_count, report);
+            println!("Cached mints: {}", cache.mints.len());
 
             let save_time = save_begin.elapsed();
             println!("Save time: {:?}", save_time);
@@ -91,23 +137,55 @@ pub struct FunctionCallEvent {
     pub source: IndexerEventSource,
     pub event_type: GenericEventType,
     pub slot: u64,
+    // position in the block, set after parsing
+    pub transaction_index: u32,
+    pub instruction_index: u32,
+    // fees of the transaction, set after parsing
+    pub fees: TransactionFees,
     pub signature: String,
     pub event_obj: Box<dyn FunctionCallInstructionData>,
     pub event_meta: Box<dyn FunctionCallEventMeta>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IndexerEventSource {
+    Pumpfun,
     Moonshot,
+    Raydium,
+    Solana,
 }
 
+impl IndexerEventSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IndexerEventSource::Pumpfun => "pumpfun",
+            IndexerEventSource::Moonshot => "moonshot",
+            IndexerEventSource::Raydium => "raydium",
+            IndexerEventSource::Solana => "solana",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GenericEventType {
     Trade,
     TokenMint,
+    Complete,
+    Info,
+    SplTokenBalanceChange,
+    SplTokenTransfer,
+    SolTransfer,
 }
 
-pub trait FunctionCallInstructionData {}
+// as_any allows the storage of each protocol to downcast its own data
 
-pub trait FunctionCallEventMeta {}
+pub trait FunctionCallInstructionData {
+    fn as_any(&self) -> &dyn Any;
+}
+
+pub trait FunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any;
+}
 
 fn parse_block(slot: i32, data_obj: &serde_json::Value) -> Vec<Event> {
     let mut events: Vec<Event> = Vec::new();
@@ -126,27 +204,18 @@ fn parse_block(slot: i32, data_obj: &serde_json::Value) -> Vec<Event> {
 
     let transactions = data_obj.get("transactions").unwrap();
 
-    for transaction_obj in transactions.as_array().unwrap() {
-        let meta = transaction_obj.get("meta").unwrap();
-        let compute_units_consumed = meta.get("computeUnitsConsumed").unwrap().as_i64().unwrap();
-        let err = meta.get("err").unwrap();
-        let fee = meta.get("fee").unwrap().as_i64().unwrap();
-        let inner_instructions = meta.get("innerInstructions").unwrap();
-        let loaded_addresses = meta.get("loadedAddresses").unwrap();
-        let log_messages = meta.get("logMessages").unwrap();
-        let post_balances = meta.get("postBalances").unwrap();
-        let pre_balances = meta.get("preBalances").unwrap();
-        let post_token_balances = meta.get("postTokenBalances").unwrap();
-        let pre_token_balances = meta.get("preTokenBalances").unwrap();
-        let rewards = meta.get("rewards").unwrap();
-        let status = meta.get("status").unwrap();
-
+    for (transaction_index, transaction_obj) in transactions.as_array().unwrap().iter().enumerate()
+    {
         let transaction: &serde_json::Map<String, serde_json::Value> =
             transaction_obj.as_object().unwrap();
 
-        parse_transaction(slot, block_time, transaction);
-        // .iter()
-        // .for_each(|event| events.push(event.clone()));
+        let mut transaction_events = parse_transaction(block_time, transaction);
+        for event in &mut transaction_events {
+            let Event::FunctionCall(function_call_event) = event;
+            function_call_event.slot = slot as u64;
+            function_call_event.transaction_index = transaction_index as u32;
+        }
+        events.append(&mut transaction_events);
     }
 
     events
@@ -158,12 +227,14 @@ fn parse_block(slot: i32, data_obj: &serde_json::Value) -> Vec<Event> {
 // }
 
 fn parse_transaction(
-    slot: i32,
     block_time: u64,
     transaction_obj: &serde_json::Map<String, serde_json::Value>,
 ) -> Vec<Event> {
     let mut events = Vec::new();
     let moonshot_parser = MoonshotParser::new();
+    let pumpfun_parser = PumpfunParser::new();
+    let raydium_parser = RaydiumParser::new();
+    let solana_parser = SolanaParser::new();
 
     let transaction = transaction_obj.get("transaction").unwrap();
     let meta = transaction_obj.get("meta").unwrap();
@@ -179,35 +250,159 @@ fn parse_transaction(
     //     .map(|v| (v.parse::<u64>().unwrap(), v.clone()))
     //     .collect();
 
+    let mut instructions_log_messages = parse_transaction_logs(transaction_obj);
+
+    let inner_instructions = meta
+        .get("innerInstructions")
+        .and_then(|inner_instructions| inner_instructions.as_array());
+
     let instructions = transaction
         .get("message")
         .and_then(|msg| msg.get("instructions"))
         .and_then(|instructions| instructions.as_array());
 
+    // outer and inner instructions are numbered in execution order
+    let mut instruction_position: u32 = 0;
+
     if let Some(instructions) = instructions {
-        for instruction in instructions {
+        for (instruction_index, instruction) in instructions.iter().enumerate() {
+            let instruction_events_start = events.len();
             let mut moonshot_events = moonshot_parser.parse_instruction(
                 transaction_obj,
                 instruction,
                 &addresses,
                 block_time,
+                instructions_log_messages.front(),
             );
             events.append(&mut moonshot_events);
+            let mut pumpfun_events = pumpfun_parser.parse_instruction(
+                transaction_obj,
+                instruction,
+                &addresses,
+                block_time,
+                instructions_log_messages.front(),
+            );
+            events.append(&mut pumpfun_events);
+            let mut raydium_events = raydium_parser.parse_instruction(
+                transaction_obj,
+                instruction,
+                &addresses,
+                block_time,
+                instructions_log_messages.front(),
+            );
+            events.append(&mut raydium_events);
+            let mut solana_events = solana_parser.parse_instruction(
+                transaction_obj,
+                instruction,
+                &addresses,
+                block_time,
+                instructions_log_messages.front(),
+            );
+            events.append(&mut solana_events);
+            set_instruction_index(
+                &mut events[instruction_events_start..],
+                instruction_position,
+            );
+            instruction_position += 1;
+
+            // remove log message reference after parsing
+            instructions_log_messages.pop_front();
+
+            // look for inner instructions
+            let inner_group = inner_instructions.and_then(|inner_instructions| {
+                inner_instructions.iter().find(|group| {
+                    group.get("index").and_then(|index| index.as_u64())
+                        == Some(instruction_index as u64)
+                })
+            });
+
+            if let Some(inner_group) = inner_group {
+                for inner_instruction in inner_group
+                    .get("instructions")
+                    .and_then(|instructions| instructions.as_array())
+                    .unwrap()
+                {
+                    let instruction_events_start = events.len();
+                    let mut moonshot_events = moonshot_parser.parse_instruction(
+                        transaction_obj,
+                        inner_instruction,
+                        &addresses,
+                        block_time,
+                        instructions_log_messages.front(),
+                    );
+                    events.append(&mut moonshot_events);
+                    let mut pumpfun_events = pumpfun_parser.parse_instruction(
+                        transaction_obj,
+                        inner_instruction,
+                        &addresses,
+                        block_time,
+                        instructions_log_messages.front(),
+                    );
+                    events.append(&mut pumpfun_events);
+                    let mut raydium_events = raydium_parser.parse_instruction(
+                        transaction_obj,
+                        inner_instruction,
+                        &addresses,
+                        block_time,
+                        instructions_log_messages.front(),
+                    );
+                    events.append(&mut raydium_events);
+                    let mut solana_events = solana_parser.parse_instruction(
+                        transaction_obj,
+                        inner_instruction,
+                        &addresses,
+                        block_time,
+                        instructions_log_messages.front(),
+                    );
+                    events.append(&mut solana_events);
+                    set_instruction_index(
+                        &mut events[instruction_events_start..],
+                        instruction_position,
+                    );
+                    instruction_position += 1;
+
+                    // remove log message reference after parsing
+                    instructions_log_messages.pop_front();
+                }
+            }
         }
     }
 
+    // the balance changes are the outcome of the whole transaction
+    let transaction_events_start = events.len();
+    events.append(&mut solana_parser.parse_transaction(transaction_obj, block_time));
+    set_instruction_index(
+        &mut events[transaction_events_start..],
+        instruction_position,
+    );
+
+    let fees = TransactionFees {
+        jito_tip: read_jito_tip(&events),
+        ..read_transaction_fees(transaction_obj, &addresses)
+    };
+    for event in &mut events {
+        let Event::FunctionCall(function_call_event) = event;
+        function_call_event.fees = fees;
+    }
+
     events
 }
 
+fn set_instruction_index(events: &mut [Event], instruction_index: u32) {
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        function_call_event.instruction_index = instruction_index;
+    }
+}
+
 fn get_addresses_vector(
     account_keys: Option<&serde_json::Value>,
     static_account_keys: Option<&serde_json::Value>,
     loaded_addresses: &serde_json::Value,
 ) -> Vec<String> {
     let mut addresses: Vec<String>;
-    if (account_keys.is_some()) {
+    if let Some(account_keys) = account_keys {
         addresses = account_keys
-            .unwrap()
             .as_array()
             .unwrap()
             .iter()
@@ -261,19 +456,10 @@ fn get_addresses_vector(
     addresses
 }
 
-fn save_events(_events: &Vec<Event>) -> (i32, HashMap<String, Vec<Event>>) {
-    let events_by_source_and_type = HashMap::new();
-    (0, events_by_source_and_type)
-}
-
-fn build_events_report(_events_by_source_and_type: &HashMap<String, Vec<Event>>) -> String {
-    let report = String::new();
-    report
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num_bigint::BigUint;
     use std::fs::File;
     use std::io::BufReader;
 
@@ -284,7 +470,45 @@ mod tests {
         let reader = BufReader::new(file);
         let data_obj = serde_json::from_reader(reader).unwrap();
 
-        let events = parse_transaction(1, 1739711240, &data_obj);
+        let events = parse_transaction(1739711240, &data_obj);
         // println!("events: {:?}", events);
+
+        let (moonshot_events, solana_events): (Vec<&Event>, Vec<&Event>) =
+            events.iter().partition(|event| {
+                let Event::FunctionCall(event) = event;
+                event.source == IndexerEventSource::Moonshot
+            });
+        assert_eq!(moonshot_events.len(), 1);
+        // the sender and the curve balances of the token changed
+        assert!(solana_events.iter().any(|event| {
+            let Event::FunctionCall(event) = event;
+            event.event_type == GenericEventType::SplTokenBalanceChange
+        }));
+
+        let Event::FunctionCall(event) = moonshot_events[0];
+        assert_eq!(event.event_type, GenericEventType::Trade);
+        let event_meta = event
+            .event_meta
+            .as_any()
+            .downcast_ref::<moonshot::MoonshotTradeFunctionCallEventMeta>()
+            .unwrap();
+        assert_eq!(
+            event_meta.sender,
+            "DnptbQ1vbCcHqY9f1ZbYg4XRr1AdTagpx5oNqbgYCej8"
+        );
+        assert_eq!(
+            event_meta.mint,
+            "CjBaQUySPyfUuKwoAPtmupC2wTmnR6ciRTRVvc2qmoon"
+        );
+        assert_eq!(
+            event_meta.bonding_curve_token_post_balance,
+            BigUint::from(247077052003106161u64)
+        );
+        assert_eq!(
+            event_meta.bonding_curve_sol_post_balance,
+            BigUint::from(70573271632u64)
+        );
+        assert_eq!(event_meta.sender_token_delta, 5000674344825);
+        assert!(event_meta.event_log.is_some());
     }
 }
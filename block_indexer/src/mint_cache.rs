@@ -0,0 +1,275 @@
+// use as reference: js_src/src/storage/protocol/solana/mint-cache.ts
+
+// Tokens launched in our protocols by mint, shared by the storages so the
+// balance changes don't query pump_data/moonshot_data for every block. The mints
+// that are not ours are remembered too (most of the balance changes are of them).
+
+use postgres::GenericClient;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::moonshot::MoonshotTokenMintFunctionCallEventMeta;
+use crate::pumpfun::PumpfunTokenMintFunctionCallEventMeta;
+use crate::{Event, GenericEventType, IndexerEventSource};
+
+pub const MINT_CACHE_CAPACITY: usize = 200_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintData {
+    pub mint: String,
+    pub protocol: IndexerEventSource,
+    pub bonding_curve: Option<String>,
+    pub developer: Option<String>,
+    pub create_event_slot: Option<i64>,
+}
+
+impl MintData {
+    pub fn is_bonding_curve(&self, wallet: &str) -> bool {
+        self.bonding_curve.as_deref() == Some(wallet)
+    }
+
+    pub fn is_developer(&self, wallet: &str) -> bool {
+        self.developer.as_deref() == Some(wallet)
+    }
+}
+
+struct CacheEntry {
+    // None when the mint is not one of ours
+    data: Option<MintData>,
+    last_used: u64,
+}
+
+/// LRU cache bounded by the number of mints (found and not found).
+pub struct MintCache {
+    capacity: usize,
+    entries: HashMap<String, CacheEntry>,
+    // last use -> mint, the first one is the next to evict
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+    // mints cached since the last commit, removed if the block is rolled back
+    block_mints: Vec<String>,
+}
+
+impl MintCache {
+    pub fn new(capacity: usize) -> Self {
+        MintCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            block_mints: Vec::new(),
+        }
+    }
+
+    /// None when the mint is not cached, Some(None) when it is known not to be ours.
+    pub fn get(&mut self, mint: &str) -> Option<Option<&MintData>> {
+        let entry = self.entries.get_mut(mint)?;
+        self.clock += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.clock, mint.to_string());
+        entry.last_used = self.clock;
+        Some(entry.data.as_ref())
+    }
+
+    pub fn insert(&mut self, data: MintData) {
+        self.put(data.mint.clone(), Some(data));
+    }
+
+    pub fn insert_not_found(&mut self, mint: &str) {
+        self.put(mint.to_string(), None);
+    }
+
+    fn put(&mut self, mint: String, data: Option<MintData>) {
+        if let Some(entry) = self.entries.remove(&mint) {
+            self.recency.remove(&entry.last_used);
+        }
+        self.block_mints.push(mint.clone());
+        self.clock += 1;
+        self.recency.insert(self.clock, mint.clone());
+        self.entries.insert(
+            mint,
+            CacheEntry {
+                data,
+                last_used: self.clock,
+            },
+        );
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest_mint)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest_mint);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Keeps the mints cached by the block, once it is stored.
+    pub fn commit(&mut self) {
+        self.block_mints.clear();
+    }
+
+    /// Forgets the mints cached by a block that was not stored, they are read
+    /// again from the database when needed.
+    pub fn rollback(&mut self) {
+        for mint in self.block_mints.drain(..) {
+            if let Some(entry) = self.entries.remove(&mint) {
+                self.recency.remove(&entry.last_used);
+            }
+        }
+    }
+
+    /// Adds the tokens created in the block, before they are looked up by the
+    /// rest of the events of the same block. They are dropped by `rollback`.
+    pub fn add_mint_events(&mut self, events: &[Event]) {
+        for event in events {
+            let Event::FunctionCall(function_call_event) = event;
+            if function_call_event.event_type != GenericEventType::TokenMint {
+                continue;
+            }
+            let event_meta = function_call_event.event_meta.as_any();
+            if let Some(event_meta) =
+                event_meta.downcast_ref::<PumpfunTokenMintFunctionCallEventMeta>()
+            {
+                let event_log = &event_meta.event_log;
+                self.insert(MintData {
+                    mint: event_log.mint.clone(),
+                    protocol: IndexerEventSource::Pumpfun,
+                    bonding_curve: Some(event_log.bonding_curve.clone()),
+                    developer: Some(event_log.user.clone()),
+                    create_event_slot: Some(function_call_event.slot as i64),
+                });
+            } else if let Some(event_meta) =
+                event_meta.downcast_ref::<MoonshotTokenMintFunctionCallEventMeta>()
+            {
+                // a failed mint didn't create the token
+                if event_meta.failed_transaction {
+                    continue;
+                }
+                self.insert(MintData {
+                    mint: event_meta.mint.clone(),
+                    protocol: IndexerEventSource::Moonshot,
+                    bonding_curve: Some(event_meta.curve_account.clone()),
+                    developer: Some(event_meta.sender.clone()),
+                    create_event_slot: Some(function_call_event.slot as i64),
+                });
+            }
+        }
+    }
+
+    /// Returns the data of the mints that are ours, the database is only read for
+    /// the mints that are not cached.
+    pub fn resolve(
+        &mut self,
+        client: &mut impl GenericClient,
+        mints: &[&str],
+    ) -> Result<HashMap<String, MintData>, postgres::Error> {
+        let unique_mints: HashSet<&str> = mints.iter().copied().collect();
+        let missing_mints: Vec<&str> = unique_mints
+            .iter()
+            .copied()
+            .filter(|mint| self.get(mint).is_none())
+            .collect();
+
+        if !missing_mints.is_empty() {
+            let select_query = "
+                SELECT mint, bonding_curve, user_public_key AS developer, create_event_slot,
+                    'Pumpfun' AS protocol
+                FROM pump_data
+                WHERE mint = ANY($1)
+                UNION
+                SELECT mint, curve_account AS bonding_curve, sender AS developer, create_event_slot,
+                    'Moonshot' AS protocol
+                FROM moonshot_data
+                WHERE mint = ANY($1);
+            ";
+
+            let rows = match client.query(select_query, &[&missing_mints]) {
+                Ok(rows) => rows,
+                Err(err) => {
+                    println!("Error reading mints data: {:?}", err);
+                    return Err(err);
+                }
+            };
+
+            for row in rows {
+                let protocol: &str = row.get("protocol");
+                self.insert(MintData {
+                    mint: row.get("mint"),
+                    protocol: if protocol == "Moonshot" {
+                        IndexerEventSource::Moonshot
+                    } else {
+                        IndexerEventSource::Pumpfun
+                    },
+                    bonding_curve: row.get("bonding_curve"),
+                    developer: row.get("developer"),
+                    create_event_slot: row.get("create_event_slot"),
+                });
+            }
+
+            for mint in missing_mints {
+                if self.get(mint).is_none() {
+                    self.insert_not_found(mint);
+                }
+            }
+        }
+
+        Ok(unique_mints
+            .into_iter()
+            .filter_map(|mint| {
+                let data = self.get(mint).flatten()?.clone();
+                Some((mint.to_string(), data))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_mint_data(mint: &str) -> MintData {
+        MintData {
+            mint: mint.to_string(),
+            protocol: IndexerEventSource::Pumpfun,
+            bonding_curve: Some("curve".to_string()),
+            developer: Some("dev".to_string()),
+            create_event_slot: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_mint_cache_eviction() {
+        let mut mint_cache = MintCache::new(2);
+        mint_cache.insert(build_mint_data("a"));
+        mint_cache.insert_not_found("b");
+        assert_eq!(mint_cache.get("b"), Some(None));
+
+        // "b" was used after "a", so "a" is evicted
+        mint_cache.insert(build_mint_data("c"));
+        assert_eq!(mint_cache.len(), 2);
+        assert!(mint_cache.get("a").is_none());
+        assert!(mint_cache.get("c").unwrap().unwrap().is_developer("dev"));
+
+        // a mint created after a miss replaces it
+        mint_cache.insert(build_mint_data("b"));
+        assert_eq!(mint_cache.get("b"), Some(Some(&build_mint_data("b"))));
+        assert_eq!(mint_cache.len(), 2);
+    }
+
+    #[test]
+    fn test_mint_cache_rollback() {
+        let mut mint_cache = MintCache::new(10);
+        mint_cache.insert(build_mint_data("a"));
+        mint_cache.commit();
+
+        mint_cache.insert(build_mint_data("b"));
+        mint_cache.insert_not_found("c");
+        mint_cache.rollback();
+        assert!(mint_cache.get("a").is_some());
+        assert!(mint_cache.get("b").is_none());
+        assert!(mint_cache.get("c").is_none());
+        assert_eq!(mint_cache.len(), 1);
+    }
+}
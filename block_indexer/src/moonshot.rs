@@ -1,41 +1,130 @@
-use sha256::digest;
 use std::sync::LazyLock;
-use std::{collections::HashMap, str::FromStr};
 
-use crate::{
-    Event, FunctionCallEvent, FunctionCallEventMeta, FunctionCallInstructionData, GenericEventType,
-    IndexerEventSource,
+use crate::parsing::{
+    deserialize_address, find_log_event_data, get_discriminator, get_sol_balance_delta,
+    get_sol_balances, get_token_balance_delta, get_token_balances, is_failed_transaction,
 };
+use crate::raydium::RAYDIUM_POOL_V4_PROGRAM_ID;
+use crate::transaction_log::InstructionLogs;
+use crate::{Event, FunctionCallEventMeta, FunctionCallInstructionData};
+use curve::{get_curve_state, MoonshotCurveParams, MoonshotCurveState};
 use num_bigint::BigUint;
-use num_traits::Num;
 use serde_json::Value;
 use solana_sdk::bs58;
+use std::any::Any;
 
+mod accounts;
+pub mod curve;
+pub mod curve_account;
 mod instruction;
+mod migration_event;
+pub mod storage;
+mod trade_event;
+use instruction::process_config_instruction;
+use instruction::process_migrate_funds_instruction;
 use instruction::process_token_mint_instruction;
 use instruction::process_trade_instruction;
+use migration_event::find_migration_event;
+pub use migration_event::MoonshotMigrationEventValues;
+use trade_event::find_trade_event;
+pub use trade_event::{MoonshotTradeEventValues, MoonshotTradeType};
+
+const MOONSHOT_PROGRAM_ID: &str = "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG";
+const METEORA_POOLS_PROGRAM_ID: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
+
+// MigrationTarget in instruction-data.ts
+pub const MIGRATION_TARGET_RAYDIUM: u8 = 0;
+pub const MIGRATION_TARGET_METEORA: u8 = 1;
 
 pub struct MoonshotParser;
 
+#[allow(dead_code)]
 pub struct MoonshotTradeFunctionCallEventMeta {
     pub block_time: u64,
     pub sender: String,
     pub mint: String,
+    pub curve_account: String,
+    pub curve_token_account: String,
     pub failed_transaction: bool,
+    pub trade_type: MoonshotTradeType,
+    // executed amounts (requested ones are in MoonshotTradeValues)
+    pub token_amount: u64,
+    pub collateral_amount: u64,
+    pub sender_token_delta: i128,
+    pub sender_sol_delta: i128,
     pub bonding_curve_token_post_balance: BigUint,
     pub bonding_curve_sol_post_balance: BigUint,
+    pub event_log: Option<MoonshotTradeEventValues>,
+}
+
+impl MoonshotTradeFunctionCallEventMeta {
+    /// Derived from the post balances, no need to fetch the curve account.
+    pub fn curve_state(&self, params: &MoonshotCurveParams) -> MoonshotCurveState {
+        get_curve_state(
+            params,
+            u64::try_from(&self.bonding_curve_token_post_balance).unwrap(),
+            u64::try_from(&self.bonding_curve_sol_post_balance).unwrap(),
+        )
+    }
 }
 
-impl FunctionCallEventMeta for MoonshotTradeFunctionCallEventMeta {}
+impl FunctionCallEventMeta for MoonshotTradeFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
+#[allow(dead_code)]
 pub struct MoonshotTokenMintFunctionCallEventMeta {
     pub block_time: u64,
     pub sender: String,
+    pub curve_account: String,
     pub mint: String,
+    pub mint_metadata: String,
+    pub curve_token_account: String,
+    pub config_account: String,
     pub failed_transaction: bool,
 }
 
-impl FunctionCallEventMeta for MoonshotTokenMintFunctionCallEventMeta {}
+impl FunctionCallEventMeta for MoonshotTokenMintFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct MoonshotCompleteFunctionCallEventMeta {
+    pub block_time: u64,
+    pub migration_authority: String,
+    pub curve_account: String,
+    pub curve_token_account: String,
+    pub mint: String,
+    // from the pool program invoked by the migration, None when the pool is
+    // created in another transaction (the storage falls back to the target of the mint)
+    pub migration_target: Option<u8>,
+    pub failed_transaction: bool,
+    pub event_log: Option<MoonshotMigrationEventValues>,
+}
+
+impl FunctionCallEventMeta for MoonshotCompleteFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct MoonshotConfigFunctionCallEventMeta {
+    pub block_time: u64,
+    pub config_authority: String,
+    pub config_account: String,
+    pub failed_transaction: bool,
+}
+
+impl FunctionCallEventMeta for MoonshotConfigFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
 impl MoonshotParser {
     pub fn new() -> Self {
@@ -46,25 +135,20 @@ impl MoonshotParser {
         &self,
         transaction_obj: &serde_json::Map<String, serde_json::Value>,
         instruction: &Value,
-        addresses: &Vec<String>,
+        addresses: &[String],
         block_time: u64,
+        instruction_logs: Option<&InstructionLogs>,
     ) -> Vec<Event> {
         let mut events: Vec<Event> = Vec::new();
 
-        // Aquí iría la lógica para parsear instrucciones específicas de Moonshot
-        // Por ahora retornamos un vector vacío
-        println!("instruction: {:?}", instruction);
         let instruction_data = instruction.get("data").unwrap();
-        println!("instruction_data: {:?}", instruction_data);
         let instruction_data_str = instruction_data.as_str().unwrap();
         let instruction_data_bytes = bs58::decode(instruction_data_str).into_vec().unwrap();
 
         let program_id_index = instruction.get("programIdIndex").unwrap().as_u64().unwrap();
         let program_id = addresses.get(program_id_index as usize).unwrap();
 
-        println!("program_id: {}", program_id);
-
-        if (program_id != "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG") {
+        if program_id != MOONSHOT_PROGRAM_ID {
             return events;
         }
 
@@ -73,14 +157,17 @@ impl MoonshotParser {
 
         match decoded_instruction {
             MoonshotInstructionData::Trade(_trade) => {
+                let event_log = get_instruction_logs(transaction_obj, instruction_logs)
+                    .and_then(find_trade_event);
                 let function_call_event = process_trade_instruction(
                     transaction_obj,
                     instruction,
                     addresses,
                     block_time,
                     decoded_instruction.clone(),
+                    event_log,
                 );
-                events.push(function_call_event);
+                events.extend(function_call_event);
             }
             MoonshotInstructionData::TokenMint(_token_mint) => {
                 let function_call_event = process_token_mint_instruction(
@@ -90,11 +177,37 @@ impl MoonshotParser {
                     block_time,
                     decoded_instruction_clone,
                 );
-                events.push(function_call_event);
+                events.extend(function_call_event);
+            }
+            MoonshotInstructionData::MigrateFunds(_migrate_funds) => {
+                if is_failed_transaction(transaction_obj) {
+                    return events;
+                }
+                let event_log = get_instruction_logs(transaction_obj, instruction_logs)
+                    .and_then(find_migration_event);
+                let function_call_event = process_migrate_funds_instruction(
+                    transaction_obj,
+                    instruction,
+                    addresses,
+                    block_time,
+                    decoded_instruction_clone,
+                    event_log,
+                );
+                events.extend(function_call_event);
+            }
+            MoonshotInstructionData::ConfigInit(_) | MoonshotInstructionData::ConfigUpdate(_) => {
+                if is_failed_transaction(transaction_obj) {
+                    return events;
+                }
+                let function_call_event = process_config_instruction(
+                    transaction_obj,
+                    instruction,
+                    addresses,
+                    block_time,
+                    decoded_instruction_clone,
+                );
+                events.extend(function_call_event);
             }
-            MoonshotInstructionData::MigrateFunds(_migrate_funds) => {}
-            MoonshotInstructionData::ConfigInit(_config_init) => {}
-            MoonshotInstructionData::ConfigUpdate(_config_update) => {}
             MoonshotInstructionData::Unknown => {
                 // TODO: Implement unknown event
             }
@@ -104,85 +217,34 @@ impl MoonshotParser {
     }
 }
 
-fn get_address_index(index: u64, instruction: &Value) -> u64 {
-    match instruction.get("accounts") {
-        Some(accounts) => {
-            let accounts_as_array = accounts.as_array().unwrap();
-            let account = accounts_as_array.get(index as usize).unwrap();
-            let account_index = account.as_u64().unwrap();
-            //let account_index = account_str.parse::<u64>().unwrap();
-            account_index
-        }
-        None => instruction
-            .get("accountKeyIndexes")
-            .unwrap()
-            .get(index as usize)
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .parse::<u64>()
-            .unwrap(),
+fn get_instruction_logs<'a>(
+    transaction_obj: &serde_json::Map<String, serde_json::Value>,
+    instruction_logs: Option<&'a InstructionLogs>,
+) -> Option<&'a InstructionLogs> {
+    // skip failed transactions log parsing
+    if is_failed_transaction(transaction_obj) {
+        return None;
     }
-}
 
-struct TokenBalances {
-    pre_balances: Vec<serde_json::Value>,
-    post_balances: Vec<serde_json::Value>,
-}
+    let instruction_logs = instruction_logs?;
 
-fn get_token_balances(
-    meta: &serde_json::Map<String, serde_json::Value>,
-    account_id: u64,
-) -> TokenBalances {
-    let pre_balances: Vec<serde_json::Value> = meta
-        .get("preTokenBalances")
-        .unwrap()
-        .as_array()
-        .unwrap()
-        .iter()
-        .filter(|balance| balance.get("accountIndex").unwrap().as_u64().unwrap() == account_id)
-        .map(|balance| balance.clone())
-        .collect();
-    let post_balances: Vec<Value> = meta
-        .get("postTokenBalances")
-        .unwrap()
-        .as_array()
-        .unwrap()
-        .iter()
-        .filter(|balance| balance.get("accountIndex").unwrap().as_u64().unwrap() == account_id)
-        .map(|balance| balance.clone())
-        .collect();
-    TokenBalances {
-        pre_balances,
-        post_balances,
-    }
+    // the logs of another program, the instruction has none
+    (instruction_logs.address == MOONSHOT_PROGRAM_ID).then_some(instruction_logs)
 }
 
-fn get_sol_balances(
-    meta: &serde_json::Map<String, serde_json::Value>,
-    account_id: u64,
-) -> TokenBalances {
-    let pre_balance = meta
-        .get("preBalances")
-        .unwrap()
-        .get(account_id as usize)
-        .unwrap();
-    println!("pre_balance: {}", pre_balance);
-    let post_balance = meta
-        .get("postBalances")
-        .unwrap()
-        .get(account_id as usize)
-        .unwrap();
-    println!("post_balance: {}", post_balance);
-    TokenBalances {
-        pre_balances: vec![pre_balance.clone()],
-        post_balances: vec![post_balance.clone()],
-    }
+// None when the data is malformed, Some(None) when the address is not set
+fn deserialize_optional_address(data: &mut &[u8]) -> Option<Option<String>> {
+    let bytes: Option<[u8; 32]> = borsh::BorshDeserialize::deserialize(data).ok()?;
+    Some(bytes.map(|bytes| bs58::encode(bytes).into_string()))
 }
 
-fn get_address_as_string(index: u64, addresses: &Vec<String>, instruction: &Value) -> String {
-    let address_index = get_address_index(index, instruction);
-    addresses.get(address_index as usize).unwrap().to_string()
+/// Target of a migration from the pool programs of the transaction.
+fn resolve_migration_target(addresses: &[String]) -> Option<u8> {
+    addresses.iter().find_map(|address| match address.as_str() {
+        RAYDIUM_POOL_V4_PROGRAM_ID => Some(MIGRATION_TARGET_RAYDIUM),
+        METEORA_POOLS_PROGRAM_ID => Some(MIGRATION_TARGET_METEORA),
+        _ => None,
+    })
 }
 
 enum MoonshotInstructionDiscriminator {
@@ -195,15 +257,6 @@ enum MoonshotInstructionDiscriminator {
     Unknown,
 }
 
-fn get_discriminator(discriminator: &str) -> BigUint {
-    let digest_result = digest(discriminator.as_bytes());
-    let digest_big_uint = BigUint::from_str_radix(&digest_result, 16).unwrap();
-    let digest_bytes = digest_big_uint.to_bytes_be();
-    let mut bytes = [0u8; 8];
-    bytes.copy_from_slice(&digest_bytes[0..8]);
-    BigUint::from_bytes_le(&bytes)
-}
-
 static MOONSHOT_BUY_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
     LazyLock::new(|| get_discriminator("global:buy"));
 static MOONSHOT_SELL_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
@@ -249,29 +302,50 @@ pub struct MoonshotTokenMintValues {
     pub migration_target: u8,
 }
 
+// migrate_funds has no arguments, everything is in the accounts and the MigrationEvent
 #[derive(Debug, Clone)]
 pub struct MoonshotMigrateFundsValues {}
 
+// every field is optional, ConfigUpdate only sends the ones being changed
 #[derive(Debug, Clone)]
-pub struct MoonshotConfigInitValues {}
-
-#[derive(Debug, Clone)]
-pub struct MoonshotConfigUpdateValues {}
+pub struct MoonshotConfigValues {
+    pub migration_authority: Option<String>,
+    pub backend_authority: Option<String>,
+    pub config_authority: Option<String>,
+    pub helio_fee: Option<String>,
+    pub dex_fee: Option<String>,
+    pub fee_bps: Option<u16>,
+    pub dex_fee_share: Option<u8>,
+    pub migration_fee: Option<u64>,
+    pub marketcap_threshold: Option<u64>,
+    pub marketcap_currency: Option<u8>,
+    pub min_supported_decimal_places: Option<u8>,
+    pub max_supported_decimal_places: Option<u8>,
+    pub min_supported_token_supply: Option<u64>,
+    pub max_supported_token_supply: Option<u64>,
+    pub coef_b: Option<u32>,
+}
 
 #[derive(Debug, Clone)]
 enum MoonshotInstructionData {
     Trade(MoonshotTradeValues),
     TokenMint(MoonshotTokenMintValues),
     MigrateFunds(MoonshotMigrateFundsValues),
-    ConfigInit(MoonshotConfigInitValues),
-    ConfigUpdate(MoonshotConfigUpdateValues),
+    ConfigInit(MoonshotConfigValues),
+    ConfigUpdate(MoonshotConfigValues),
     Unknown,
 }
 
-impl FunctionCallInstructionData for MoonshotInstructionData {}
+impl FunctionCallInstructionData for MoonshotInstructionData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub struct MoonshotTradeValues {
+    pub trade_type: MoonshotTradeType,
     pub token_amount: u64,
     pub collateral_amount: u64,
     pub fixed_side: u8,
@@ -279,23 +353,19 @@ pub struct MoonshotTradeValues {
 }
 
 fn decode_instruction_data(instruction_data: &[u8]) -> MoonshotInstructionData {
-    // el primer u64 es el discriminador
-    //let instruction_type = u64_bytes_to_big_int(instruction_data, 0);
-
-    println!("instruction_data: {:?}", &instruction_data[0..8]);
-
+    // the first u64 is the discriminator
+    if instruction_data.len() < 8 {
+        return MoonshotInstructionData::Unknown;
+    }
     let instruction_type = BigUint::from_bytes_le(&instruction_data[0..8]);
 
-    let instruction_type_string = instruction_type.to_string();
-    println!("instruction_type_string: {}", instruction_type_string);
-
     match MoonshotInstructionDiscriminator::from_big_uint(instruction_type) {
         MoonshotInstructionDiscriminator::Buy => {
-            let decoded_trade = decode_trade(instruction_data);
+            let decoded_trade = decode_trade(instruction_data, MoonshotTradeType::Buy);
             MoonshotInstructionData::Trade(decoded_trade)
         }
         MoonshotInstructionDiscriminator::Sell => {
-            let decoded_trade = decode_trade(instruction_data);
+            let decoded_trade = decode_trade(instruction_data, MoonshotTradeType::Sell);
             MoonshotInstructionData::Trade(decoded_trade)
         }
         MoonshotInstructionDiscriminator::TokenMint => {
@@ -306,25 +376,28 @@ fn decode_instruction_data(instruction_data: &[u8]) -> MoonshotInstructionData {
             let decoded_migrate_funds = decode_migrate_funds(instruction_data);
             MoonshotInstructionData::MigrateFunds(decoded_migrate_funds)
         }
-        MoonshotInstructionDiscriminator::ConfigInit => {
-            let decoded_config_init = decode_config_init(instruction_data);
-            MoonshotInstructionData::ConfigInit(decoded_config_init)
-        }
-        MoonshotInstructionDiscriminator::ConfigUpdate => {
-            let decoded_config_update = decode_config_update(instruction_data);
-            MoonshotInstructionData::ConfigUpdate(decoded_config_update)
-        }
-        MoonshotInstructionDiscriminator::Unknown => todo!(),
+        MoonshotInstructionDiscriminator::ConfigInit => match decode_config(instruction_data) {
+            Some(decoded_config_init) => MoonshotInstructionData::ConfigInit(decoded_config_init),
+            None => MoonshotInstructionData::Unknown,
+        },
+        MoonshotInstructionDiscriminator::ConfigUpdate => match decode_config(instruction_data) {
+            Some(decoded_config_update) => {
+                MoonshotInstructionData::ConfigUpdate(decoded_config_update)
+            }
+            None => MoonshotInstructionData::Unknown,
+        },
+        MoonshotInstructionDiscriminator::Unknown => MoonshotInstructionData::Unknown,
     }
 }
 
-fn decode_trade(instruction_data: &[u8]) -> MoonshotTradeValues {
+fn decode_trade(instruction_data: &[u8], trade_type: MoonshotTradeType) -> MoonshotTradeValues {
     let token_amount = u64::from_le_bytes(instruction_data[8..16].try_into().unwrap());
     let collateral_amount = u64::from_le_bytes(instruction_data[16..24].try_into().unwrap());
     let fixed_side = u8::from_le_bytes(instruction_data[24..25].try_into().unwrap());
     let slippage_bps = u64::from_le_bytes(instruction_data[25..33].try_into().unwrap());
 
     MoonshotTradeValues {
+        trade_type,
         token_amount,
         collateral_amount,
         fixed_side,
@@ -367,21 +440,59 @@ fn decode_token_mint(instruction_data: &[u8]) -> MoonshotTokenMintValues {
     }
 }
 
-fn decode_migrate_funds(instruction_data: &[u8]) -> MoonshotMigrateFundsValues {
+fn decode_migrate_funds(_instruction_data: &[u8]) -> MoonshotMigrateFundsValues {
     MoonshotMigrateFundsValues {}
 }
 
-fn decode_config_init(instruction_data: &[u8]) -> MoonshotConfigInitValues {
-    MoonshotConfigInitValues {}
-}
-
-fn decode_config_update(instruction_data: &[u8]) -> MoonshotConfigUpdateValues {
-    MoonshotConfigUpdateValues {}
+fn decode_config(instruction_data: &[u8]) -> Option<MoonshotConfigValues> {
+    let mut instruction_data_mut = instruction_data;
+    let _discriminator: u64 =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let migration_authority = deserialize_optional_address(&mut instruction_data_mut)?;
+    let backend_authority = deserialize_optional_address(&mut instruction_data_mut)?;
+    let config_authority = deserialize_optional_address(&mut instruction_data_mut)?;
+    let helio_fee = deserialize_optional_address(&mut instruction_data_mut)?;
+    let dex_fee = deserialize_optional_address(&mut instruction_data_mut)?;
+    let fee_bps = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let dex_fee_share = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let migration_fee = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let marketcap_threshold =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let marketcap_currency =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let min_supported_decimal_places =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let max_supported_decimal_places =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let min_supported_token_supply =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let max_supported_token_supply =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let coef_b = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(MoonshotConfigValues {
+        migration_authority,
+        backend_authority,
+        config_authority,
+        helio_fee,
+        dex_fee,
+        fee_bps,
+        dex_fee_share,
+        migration_fee,
+        marketcap_threshold,
+        marketcap_currency,
+        min_supported_decimal_places,
+        max_supported_decimal_places,
+        min_supported_token_supply,
+        max_supported_token_supply,
+        coef_b,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use base64::prelude::*;
     use dotenv::dotenv;
     use solana_client::rpc_client::RpcClient;
     use solana_sdk::signature::Signature;
@@ -418,4 +529,84 @@ mod tests {
 
         // assert!(events.is_empty());
     }
+
+    #[test]
+    fn test_find_trade_event() {
+        let instruction_logs = InstructionLogs {
+            address: MOONSHOT_PROGRAM_ID.to_string(),
+            log_messages: vec![
+                "Program MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG invoke [1]".to_string(),
+                "Program log: Instruction: Buy".to_string(),
+                "Program data: vdt/007mYe55/2pPjAQAAIj5FwAAAAAA0yQAAAAAAACNGAAAAAAAAI9CmJpu63IK25nPV3qRLv6O8VeQBXGbK5fIsxw1IhrbKEOOGpVqSHQGm4hX/quBhPtof2NGGMA12sQ53BrrO1WYoPAAAAAAAb4Jh0TiGk7x+Wa8+lpiQH9ed1RVAs5KljPIAEHli7VHAAUAAAB0cmFkZQ==".to_string(),
+                "Program MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG success".to_string(),
+            ],
+        };
+
+        let trade_event = find_trade_event(&instruction_logs).unwrap();
+
+        assert_eq!(trade_event.amount, 5000674344825);
+        assert_eq!(trade_event.collateral_amount, 1571208);
+        assert_eq!(trade_event.dex_fee, 9427);
+        assert_eq!(trade_event.helio_fee, 6285);
+        assert_eq!(
+            trade_event.sender,
+            "DnptbQ1vbCcHqY9f1ZbYg4XRr1AdTagpx5oNqbgYCej8"
+        );
+        assert_eq!(trade_event.trade_type, MoonshotTradeType::Buy);
+        assert_eq!(trade_event.label, "trade");
+
+        // unknown trade type and truncated data
+        let event_data = BASE64_STANDARD
+            .decode(instruction_logs.log_messages[2].trim_start_matches("Program data: "))
+            .unwrap();
+        let mut unknown_type = event_data.clone();
+        unknown_type[8 + 8 * 5 + 32 * 3] = 2;
+        assert!(trade_event::decode_trade_event(&unknown_type).is_none());
+        assert!(trade_event::decode_trade_event(&event_data[..100]).is_none());
+    }
+
+    #[test]
+    fn test_decode_config_update() {
+        let mut instruction_data = MOONSHOT_CONFIG_UPDATE_INSTRUCTION_DISCRIMINATOR.to_bytes_le();
+        instruction_data.resize(8, 0);
+        instruction_data.extend([0, 0, 0, 0, 0]); // no authorities nor fee accounts
+        instruction_data.extend([1, 100, 0]); // fee_bps: 100
+        instruction_data.extend([0, 0, 0, 0, 0, 0, 0, 0]);
+        instruction_data.extend([1, 7, 0, 0, 0]); // coef_b: 7
+
+        match decode_instruction_data(&instruction_data) {
+            MoonshotInstructionData::ConfigUpdate(config) => {
+                assert_eq!(config.fee_bps, Some(100));
+                assert_eq!(config.dex_fee_share, None);
+                assert_eq!(config.migration_authority, None);
+                assert_eq!(config.coef_b, Some(7));
+            }
+            _ => panic!("ConfigUpdate expected"),
+        }
+
+        // truncated data is not decoded
+        instruction_data.truncate(20);
+        assert!(matches!(
+            decode_instruction_data(&instruction_data),
+            MoonshotInstructionData::Unknown
+        ));
+        instruction_data.truncate(5);
+        assert!(matches!(
+            decode_instruction_data(&instruction_data),
+            MoonshotInstructionData::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_resolve_migration_target() {
+        let addresses = vec![
+            MOONSHOT_PROGRAM_ID.to_string(),
+            RAYDIUM_POOL_V4_PROGRAM_ID.to_string(),
+        ];
+        assert_eq!(
+            resolve_migration_target(&addresses),
+            Some(MIGRATION_TARGET_RAYDIUM)
+        );
+        assert_eq!(resolve_migration_target(&addresses[..1]), None);
+    }
 }
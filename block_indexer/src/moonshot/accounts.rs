@@ -0,0 +1,144 @@
+// Account layouts of the Moonshot instructions (positions as in the program IDL).
+// The instruction "accounts" hold indexes into the transaction addresses, the
+// structs below resolve them to the account keys.
+
+use serde_json::Value;
+
+use crate::parsing::{get_accounts_count, get_address_as_string};
+
+// Buy and Sell share the same layout
+#[allow(dead_code)]
+pub struct MoonshotTradeAccounts {
+    pub sender: String,
+    pub sender_token_account: String,
+    pub curve_account: String,
+    pub curve_token_account: String,
+    pub dex_fee: String,
+    pub helio_fee: String,
+    pub mint: String,
+    pub config_account: String,
+    pub token_program: String,
+    pub associated_token_program: String,
+    pub system_program: String,
+}
+
+impl MoonshotTradeAccounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 11 {
+            return None;
+        }
+
+        Some(MoonshotTradeAccounts {
+            sender: get_address_as_string(0, addresses, instruction),
+            sender_token_account: get_address_as_string(1, addresses, instruction),
+            curve_account: get_address_as_string(2, addresses, instruction),
+            curve_token_account: get_address_as_string(3, addresses, instruction),
+            dex_fee: get_address_as_string(4, addresses, instruction),
+            helio_fee: get_address_as_string(5, addresses, instruction),
+            mint: get_address_as_string(6, addresses, instruction),
+            config_account: get_address_as_string(7, addresses, instruction),
+            token_program: get_address_as_string(8, addresses, instruction),
+            associated_token_program: get_address_as_string(9, addresses, instruction),
+            system_program: get_address_as_string(10, addresses, instruction),
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub struct MoonshotTokenMintAccounts {
+    pub sender: String,
+    pub backend_authority: String,
+    pub curve_account: String,
+    pub mint: String,
+    pub mint_metadata: String,
+    pub curve_token_account: String,
+    pub config_account: String,
+    pub token_program: String,
+    pub associated_token_program: String,
+    pub mpl_token_metadata: String,
+    pub system_program: String,
+}
+
+impl MoonshotTokenMintAccounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 11 {
+            return None;
+        }
+
+        Some(MoonshotTokenMintAccounts {
+            sender: get_address_as_string(0, addresses, instruction),
+            backend_authority: get_address_as_string(1, addresses, instruction),
+            curve_account: get_address_as_string(2, addresses, instruction),
+            mint: get_address_as_string(3, addresses, instruction),
+            mint_metadata: get_address_as_string(4, addresses, instruction),
+            curve_token_account: get_address_as_string(5, addresses, instruction),
+            config_account: get_address_as_string(6, addresses, instruction),
+            token_program: get_address_as_string(7, addresses, instruction),
+            associated_token_program: get_address_as_string(8, addresses, instruction),
+            mpl_token_metadata: get_address_as_string(9, addresses, instruction),
+            system_program: get_address_as_string(10, addresses, instruction),
+        })
+    }
+}
+
+#[allow(dead_code)]
+pub struct MoonshotMigrateFundsAccounts {
+    pub backend_authority: String,
+    pub migration_authority: String,
+    pub curve_account: String,
+    pub curve_token_account: String,
+    pub migration_authority_token_account: String,
+    pub mint: String,
+    pub dex_fee_account: String,
+    pub helio_fee_account: String,
+    pub config_account: String,
+    pub system_program: String,
+    pub token_program: String,
+    pub associated_token_program: String,
+}
+
+impl MoonshotMigrateFundsAccounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 12 {
+            return None;
+        }
+
+        Some(MoonshotMigrateFundsAccounts {
+            backend_authority: get_address_as_string(0, addresses, instruction),
+            migration_authority: get_address_as_string(1, addresses, instruction),
+            curve_account: get_address_as_string(2, addresses, instruction),
+            curve_token_account: get_address_as_string(3, addresses, instruction),
+            migration_authority_token_account: get_address_as_string(4, addresses, instruction),
+            mint: get_address_as_string(5, addresses, instruction),
+            dex_fee_account: get_address_as_string(6, addresses, instruction),
+            helio_fee_account: get_address_as_string(7, addresses, instruction),
+            config_account: get_address_as_string(8, addresses, instruction),
+            system_program: get_address_as_string(9, addresses, instruction),
+            token_program: get_address_as_string(10, addresses, instruction),
+            associated_token_program: get_address_as_string(11, addresses, instruction),
+        })
+    }
+}
+
+// ConfigInit also has the system program as third account
+pub struct MoonshotConfigAccounts {
+    pub config_authority: String,
+    pub config_account: String,
+}
+
+impl MoonshotConfigAccounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 2 {
+            return None;
+        }
+
+        Some(MoonshotConfigAccounts {
+            config_authority: get_address_as_string(0, addresses, instruction),
+            config_account: get_address_as_string(1, addresses, instruction),
+        })
+    }
+}
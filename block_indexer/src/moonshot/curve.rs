@@ -0,0 +1,129 @@
+// use as reference: js_src/src/fetching/protocol/moonshot/bonding.fetcher.ts
+// and js_src/src/auxiliar/moonshot.ts
+
+const INITIAL_VIRTUAL_TOKEN_RESERVES: u128 = 1_073_000_000_000_000_000;
+const INITIAL_VIRTUAL_COLLATERAL_RESERVES: u128 = 30_000_000_000;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Token parameters of the curve, from the CurveAccount (see curve_account.rs)
+/// or from the TokenMint instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonshotCurveParams {
+    pub total_supply: u64,
+    pub decimals: u8,
+}
+
+// the launch parameters (1_000_000_000 tokens with 9 decimals), only used for
+// the tokens without a stored curve account or mint
+impl Default for MoonshotCurveParams {
+    fn default() -> Self {
+        MoonshotCurveParams {
+            total_supply: 1_000_000_000_000_000_000,
+            decimals: 9,
+        }
+    }
+}
+
+impl MoonshotCurveParams {
+    fn token_units(&self) -> f64 {
+        10f64.powi(self.decimals as i32)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct MoonshotCurveState {
+    // real balances of the curve (token account and curve account)
+    pub curve_token_amount: u64,
+    pub curve_sol_amount: u64,
+    pub virtual_token_reserves: u128,
+    pub virtual_collateral_reserves: u128,
+    pub price: f64,
+    pub marketcap: f64,
+    pub percentage: f64,
+    pub liquidity: f64,
+}
+
+pub fn get_curve_percentage(price_in_sol: f64) -> f64 {
+    let coefficients = [
+        1.64495936e62,
+        -3.59104058e56,
+        3.36157604e50,
+        -1.76514162e44,
+        5.7136059e37,
+        -1.18124449e31,
+        1.56700631e24,
+        -1.3130554e17,
+        6.80386291e9,
+        -1.15101449e2,
+    ];
+
+    coefficients.iter().fold(0.0, |percentage, coefficient| {
+        percentage * price_in_sol + coefficient
+    })
+}
+
+/// Builds the curve state from the tokens still held by the curve.
+/// `curve_position` (tokens sold by the curve) is total_supply - curve_amount.
+pub fn get_curve_state(
+    params: &MoonshotCurveParams,
+    curve_token_amount: u64,
+    curve_sol_amount: u64,
+) -> MoonshotCurveState {
+    let token_units = params.token_units();
+    let curve_position = params.total_supply.saturating_sub(curve_token_amount) as u128;
+    let constant_product = INITIAL_VIRTUAL_TOKEN_RESERVES * INITIAL_VIRTUAL_COLLATERAL_RESERVES;
+    let virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES
+        .saturating_sub(curve_position)
+        .max(1);
+    let virtual_collateral_reserves = constant_product / virtual_token_reserves;
+
+    let price = (virtual_collateral_reserves as f64 / LAMPORTS_PER_SOL)
+        / (virtual_token_reserves as f64 / token_units);
+    let marketcap = price * (params.total_supply as f64 / token_units);
+    let percentage = get_curve_percentage(price).clamp(0.0, 100.0);
+    let liquidity = virtual_collateral_reserves as f64 / LAMPORTS_PER_SOL
+        + (virtual_token_reserves as f64 / token_units) * price;
+
+    MoonshotCurveState {
+        curve_token_amount,
+        curve_sol_amount,
+        virtual_token_reserves,
+        virtual_collateral_reserves,
+        price,
+        marketcap,
+        percentage,
+        liquidity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_curve_state() {
+        // post balances of the curve in data/moon_tx.json
+        let params = MoonshotCurveParams::default();
+        let state = get_curve_state(&params, 247077052003106161, 70573271632);
+
+        assert_eq!(state.virtual_token_reserves, 320077052003106161);
+        // the real collateral is the virtual one minus the initial virtual reserves (the curve
+        // account balance also has the rent exempt lamports)
+        let real_collateral =
+            state.virtual_collateral_reserves - INITIAL_VIRTUAL_COLLATERAL_RESERVES;
+        assert!(real_collateral.abs_diff(70573271632) < 10_000_000);
+        // the trade in the same transaction paid 1571208 lamports for 5000674344825 units
+        assert!((state.price - 1571208.0 / 5000674344825.0).abs() < 1e-9);
+        assert!(state.percentage >= 0.0 && state.percentage <= 100.0);
+
+        // with 6 decimals the same raw reserves are 1000 times more tokens
+        let params_6_decimals = MoonshotCurveParams {
+            total_supply: params.total_supply,
+            decimals: 6,
+        };
+        let state_6_decimals = get_curve_state(&params_6_decimals, 247077052003106161, 70573271632);
+        assert!((state.price / state_6_decimals.price - 1000.0).abs() < 1e-6);
+    }
+}
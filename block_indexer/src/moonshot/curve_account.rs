@@ -0,0 +1,91 @@
+// CurveAccount as returned by moonshotToken.getCurveAccount() in
+// js_src/src/fetching/protocol/moonshot/bonding.fetcher.ts
+
+use num_bigint::BigUint;
+use std::sync::LazyLock;
+
+use super::curve::MoonshotCurveParams;
+use super::{deserialize_address, get_discriminator};
+
+// discriminator + fields
+const MOONSHOT_CURVE_ACCOUNT_MIN_SIZE: usize = 8 + 74;
+
+static MOONSHOT_CURVE_ACCOUNT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("account:CurveAccount"));
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct MoonshotCurveAccount {
+    pub total_supply: u64,
+    pub curve_amount: u64,
+    pub mint: String,
+    pub decimals: u8,
+    pub collateral_currency: u8,
+    pub curve_type: u8,
+    pub marketcap_threshold: u64,
+    pub marketcap_currency: u8,
+    pub migration_fee: u64,
+    pub coef_b: u32,
+    pub bump: u8,
+    pub migration_target: u8,
+}
+
+impl MoonshotCurveAccount {
+    pub fn curve_params(&self) -> MoonshotCurveParams {
+        MoonshotCurveParams {
+            total_supply: self.total_supply,
+            decimals: self.decimals,
+        }
+    }
+}
+
+// totalSupply: u64
+// curveAmount: u64
+// mint: publicKey
+// decimals: u8
+// collateralCurrency: Currency
+// curveType: CurveType
+// marketcapThreshold: u64
+// marketcapCurrency: Currency
+// migrationFee: u64
+// coefB: u32
+// bump: u8
+// migrationTarget: MigrationTarget
+
+/// Returns None when the data isn't a CurveAccount.
+pub fn decode_curve_account(account_data: &[u8]) -> Option<MoonshotCurveAccount> {
+    if account_data.len() < MOONSHOT_CURVE_ACCOUNT_MIN_SIZE
+        || BigUint::from_bytes_le(&account_data[0..8]) != *MOONSHOT_CURVE_ACCOUNT_DISCRIMINATOR
+    {
+        return None;
+    }
+
+    let mut account_data_mut = &account_data[8..];
+    let total_supply = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let curve_amount = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let mint = deserialize_address(&mut account_data_mut);
+    let decimals = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let collateral_currency = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let curve_type = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let marketcap_threshold = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let marketcap_currency = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let migration_fee = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let coef_b = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let bump = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+    let migration_target = borsh::BorshDeserialize::deserialize(&mut account_data_mut).ok()?;
+
+    Some(MoonshotCurveAccount {
+        total_supply,
+        curve_amount,
+        mint,
+        decimals,
+        collateral_currency,
+        curve_type,
+        marketcap_threshold,
+        marketcap_currency,
+        migration_fee,
+        coef_b,
+        bump,
+        migration_target,
+    })
+}
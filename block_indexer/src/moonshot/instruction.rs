@@ -1,23 +1,29 @@
-use crate::{
-    Event, FunctionCallEvent, FunctionCallEventMeta, FunctionCallInstructionData, GenericEventType,
-    IndexerEventSource,
-};
+use crate::compute_budget::TransactionFees;
+use crate::{Event, FunctionCallEvent, GenericEventType, IndexerEventSource};
 use num_bigint::BigUint;
 use serde_json::Value;
 use std::str::FromStr;
 
+use super::accounts::{
+    MoonshotConfigAccounts, MoonshotMigrateFundsAccounts, MoonshotTokenMintAccounts,
+    MoonshotTradeAccounts,
+};
 use super::{
-    get_address_as_string, get_sol_balances, get_token_balances, MoonshotInstructionData,
-    MoonshotTokenMintFunctionCallEventMeta, MoonshotTradeFunctionCallEventMeta,
+    get_sol_balance_delta, get_sol_balances, get_token_balance_delta, get_token_balances,
+    resolve_migration_target, MoonshotCompleteFunctionCallEventMeta,
+    MoonshotConfigFunctionCallEventMeta, MoonshotInstructionData, MoonshotMigrationEventValues,
+    MoonshotTokenMintFunctionCallEventMeta, MoonshotTradeEventValues,
+    MoonshotTradeFunctionCallEventMeta,
 };
 
 pub fn process_trade_instruction(
     transaction_obj: &serde_json::Map<String, serde_json::Value>,
     instruction: &Value,
-    addresses: &Vec<String>,
+    addresses: &[String],
     block_time: u64,
     decoded_instruction: MoonshotInstructionData,
-) -> Event {
+    event_log: Option<MoonshotTradeEventValues>,
+) -> Option<Event> {
     let meta = transaction_obj.get("meta").unwrap().as_object().unwrap();
     let failed_transaction = meta.get("err").unwrap().is_object();
     let signature = transaction_obj
@@ -30,53 +36,89 @@ pub fn process_trade_instruction(
         .as_str()
         .unwrap()
         .to_string();
-    let sender = get_address_as_string(1, addresses, instruction);
-    let mint = get_address_as_string(2, addresses, instruction);
+    let accounts = MoonshotTradeAccounts::resolve(instruction, addresses)?;
 
-    let bonding_curve_token_balances = get_token_balances(meta, 3);
-    let bonding_curve_sol_balances = get_sol_balances(meta, 4);
+    let trade_values = match &decoded_instruction {
+        MoonshotInstructionData::Trade(trade_values) => *trade_values,
+        _ => unreachable!(),
+    };
 
-    let bonding_curve_token_post_balance = BigUint::from_str(
-        bonding_curve_token_balances.post_balances[0]
-            .get("uiTokenAmount")
-            .unwrap()
-            .get("amount")
+    let sender_token_delta = get_token_balance_delta(meta, &accounts.sender, &accounts.mint);
+    let sender_sol_delta = get_sol_balance_delta(meta, addresses, &accounts.sender);
+
+    // the trade event has the executed amounts, the balance deltas are the fallback (they
+    // include the fees paid by the sender)
+    let (token_amount, collateral_amount) = match (&event_log, failed_transaction) {
+        (_, true) => (0, 0),
+        (Some(event_log), false) => (event_log.amount, event_log.collateral_amount),
+        (None, false) => (
+            sender_token_delta.unsigned_abs() as u64,
+            sender_sol_delta.unsigned_abs() as u64,
+        ),
+    };
+
+    let bonding_curve_token_balances =
+        get_token_balances(meta, addresses, &accounts.curve_token_account);
+    let bonding_curve_sol_balances = get_sol_balances(meta, addresses, &accounts.curve_account);
+
+    // the curve token account has no post balance if it was closed
+    let bonding_curve_token_post_balance = bonding_curve_token_balances
+        .post_balances
+        .first()
+        .map(|balance| {
+            BigUint::from_str(
+                balance
+                    .get("uiTokenAmount")
+                    .unwrap()
+                    .get("amount")
+                    .unwrap()
+                    .as_str()
+                    .unwrap(),
+            )
             .unwrap()
-            .as_str()
-            .unwrap(),
-    )
-    .unwrap();
-    let bonding_curve_sol_post_balance = BigUint::from_str(
+        })
+        .unwrap_or_default();
+    let bonding_curve_sol_post_balance = BigUint::from(
         bonding_curve_sol_balances.post_balances[0]
-            .as_str()
+            .as_u64()
             .unwrap(),
-    )
-    .unwrap();
+    );
 
-    Event::FunctionCall(FunctionCallEvent {
+    Some(Event::FunctionCall(FunctionCallEvent {
         source: IndexerEventSource::Moonshot,
         event_type: GenericEventType::Trade,
         slot: 0,
+        transaction_index: 0,
+        instruction_index: 0,
+        fees: TransactionFees::default(),
         signature,
         event_obj: Box::new(decoded_instruction),
         event_meta: Box::new(MoonshotTradeFunctionCallEventMeta {
             block_time,
-            sender,
-            mint,
+            sender: accounts.sender,
+            mint: accounts.mint,
+            curve_account: accounts.curve_account,
+            curve_token_account: accounts.curve_token_account,
             failed_transaction,
+            trade_type: trade_values.trade_type,
+            token_amount,
+            collateral_amount,
+            sender_token_delta,
+            sender_sol_delta,
             bonding_curve_token_post_balance,
             bonding_curve_sol_post_balance,
+            event_log,
         }),
-    })
+    }))
 }
 
 pub fn process_token_mint_instruction(
     transaction_obj: &serde_json::Map<String, serde_json::Value>,
     instruction: &Value,
-    addresses: &Vec<String>,
+    addresses: &[String],
     block_time: u64,
     decoded_instruction: MoonshotInstructionData,
-) -> Event {
+) -> Option<Event> {
     let meta = transaction_obj.get("meta").unwrap().as_object().unwrap();
     let failed_transaction = meta.get("err").unwrap().is_object();
     let signature = transaction_obj
@@ -89,20 +131,109 @@ pub fn process_token_mint_instruction(
         .as_str()
         .unwrap()
         .to_string();
-    let sender = get_address_as_string(1, addresses, instruction);
-    let mint = get_address_as_string(2, addresses, instruction);
+    let accounts = MoonshotTokenMintAccounts::resolve(instruction, addresses)?;
 
-    Event::FunctionCall(FunctionCallEvent {
+    Some(Event::FunctionCall(FunctionCallEvent {
         source: IndexerEventSource::Moonshot,
         event_type: GenericEventType::TokenMint,
         slot: 0,
+        transaction_index: 0,
+        instruction_index: 0,
+        fees: TransactionFees::default(),
         signature,
         event_obj: Box::new(decoded_instruction),
         event_meta: Box::new(MoonshotTokenMintFunctionCallEventMeta {
             block_time,
-            sender,
-            mint,
+            sender: accounts.sender,
+            curve_account: accounts.curve_account,
+            mint: accounts.mint,
+            mint_metadata: accounts.mint_metadata,
+            curve_token_account: accounts.curve_token_account,
+            config_account: accounts.config_account,
+            failed_transaction,
+        }),
+    }))
+}
+
+pub fn process_migrate_funds_instruction(
+    transaction_obj: &serde_json::Map<String, serde_json::Value>,
+    instruction: &Value,
+    addresses: &[String],
+    block_time: u64,
+    decoded_instruction: MoonshotInstructionData,
+    event_log: Option<MoonshotMigrationEventValues>,
+) -> Option<Event> {
+    let meta = transaction_obj.get("meta").unwrap().as_object().unwrap();
+    let failed_transaction = meta.get("err").unwrap().is_object();
+    let signature = transaction_obj
+        .get("transaction")
+        .unwrap()
+        .get("signatures")
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_string();
+    let accounts = MoonshotMigrateFundsAccounts::resolve(instruction, addresses)?;
+
+    Some(Event::FunctionCall(FunctionCallEvent {
+        source: IndexerEventSource::Moonshot,
+        event_type: GenericEventType::Complete,
+        slot: 0,
+        transaction_index: 0,
+        instruction_index: 0,
+        fees: TransactionFees::default(),
+        signature,
+        event_obj: Box::new(decoded_instruction),
+        event_meta: Box::new(MoonshotCompleteFunctionCallEventMeta {
+            block_time,
+            migration_authority: accounts.migration_authority,
+            curve_account: accounts.curve_account,
+            curve_token_account: accounts.curve_token_account,
+            mint: accounts.mint,
+            migration_target: resolve_migration_target(addresses),
+            failed_transaction,
+            event_log,
+        }),
+    }))
+}
+
+pub fn process_config_instruction(
+    transaction_obj: &serde_json::Map<String, serde_json::Value>,
+    instruction: &Value,
+    addresses: &[String],
+    block_time: u64,
+    decoded_instruction: MoonshotInstructionData,
+) -> Option<Event> {
+    let meta = transaction_obj.get("meta").unwrap().as_object().unwrap();
+    let failed_transaction = meta.get("err").unwrap().is_object();
+    let signature = transaction_obj
+        .get("transaction")
+        .unwrap()
+        .get("signatures")
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_string();
+    let accounts = MoonshotConfigAccounts::resolve(instruction, addresses)?;
+
+    Some(Event::FunctionCall(FunctionCallEvent {
+        source: IndexerEventSource::Moonshot,
+        event_type: GenericEventType::Info,
+        slot: 0,
+        transaction_index: 0,
+        instruction_index: 0,
+        fees: TransactionFees::default(),
+        signature,
+        event_obj: Box::new(decoded_instruction),
+        event_meta: Box::new(MoonshotConfigFunctionCallEventMeta {
+            block_time,
+            config_authority: accounts.config_authority,
+            config_account: accounts.config_account,
             failed_transaction,
         }),
-    })
+    }))
 }
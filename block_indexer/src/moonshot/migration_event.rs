@@ -0,0 +1,50 @@
+// use as reference: MoonshotMigrationEventValues in js_src/src/parsing/protocol/moonshot/instruction-data.ts
+
+use num_bigint::BigUint;
+use std::sync::LazyLock;
+
+use super::{find_log_event_data, get_discriminator};
+use crate::transaction_log::InstructionLogs;
+
+static MOONSHOT_MIGRATION_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:MigrationEvent"));
+
+#[derive(Debug, Clone)]
+pub struct MoonshotMigrationEventValues {
+    pub tokens_migrated: u64,
+    pub tokens_burned: u64,
+    pub collateral_migrated: u64,
+    pub fee: u64,
+    pub label: String,
+}
+
+// tokensMigrated: u64
+// tokensBurned: u64
+// collateralMigrated: u64
+// fee: u64
+// label: string
+
+pub fn decode_migration_event(event_data: &[u8]) -> Option<MoonshotMigrationEventValues> {
+    let mut event_data_mut = event_data;
+    let _discriminator: u64 = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let tokens_migrated = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let tokens_burned = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let collateral_migrated = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let fee = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let label = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+
+    Some(MoonshotMigrationEventValues {
+        tokens_migrated,
+        tokens_burned,
+        collateral_migrated,
+        fee,
+        label,
+    })
+}
+
+pub fn find_migration_event(
+    instruction_logs: &InstructionLogs,
+) -> Option<MoonshotMigrationEventValues> {
+    find_log_event_data(instruction_logs, &MOONSHOT_MIGRATION_EVENT_DISCRIMINATOR)
+        .and_then(|event_data| decode_migration_event(&event_data))
+}
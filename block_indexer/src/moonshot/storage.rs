@@ -0,0 +1,53 @@
+// use as reference: js_src/src/storage/protocol/moonshot/storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::mint_cache::MintCache;
+use crate::storage::{arrange_events_by_type, BlockData, ProtocolSaveResult, StorageError};
+use crate::{Event, GenericEventType};
+
+mod config;
+pub mod curve;
+mod migration;
+mod mint;
+mod time_series;
+mod trade;
+use config::save_config_events;
+use migration::save_migration_events;
+use mint::save_mint_events;
+use trade::save_trade_events;
+
+pub fn save_protocol_events(
+    transaction: &mut Transaction,
+    events: &[&Event],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<ProtocolSaveResult, StorageError> {
+    let mut rows_count = 0;
+    let mut events_by_type = HashMap::new();
+
+    for (event_type, events) in arrange_events_by_type(events) {
+        events_by_type.insert(event_type, events.len());
+        match event_type {
+            GenericEventType::TokenMint => {
+                rows_count += save_mint_events(transaction, &events, block_data)?;
+            }
+            GenericEventType::Trade => {
+                rows_count += save_trade_events(transaction, &events, block_data, mint_cache)?;
+            }
+            GenericEventType::Complete => {
+                rows_count += save_migration_events(transaction, &events, block_data)?;
+            }
+            GenericEventType::Info => {
+                rows_count += save_config_events(transaction, &events, block_data)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ProtocolSaveResult {
+        rows_count,
+        events_by_type,
+    })
+}
@@ -0,0 +1,108 @@
+use postgres::Transaction;
+
+use crate::moonshot::{MoonshotConfigFunctionCallEventMeta, MoonshotInstructionData};
+use crate::storage::BlockData;
+use crate::Event;
+
+pub fn save_config_events(
+    transaction: &mut Transaction,
+    config_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let insert_query = "
+        INSERT INTO moonshot_config_history (
+            slot,
+            transaction_id,
+            instruction,
+            signer,
+            config_account,
+            migration_authority,
+            backend_authority,
+            config_authority,
+            helio_fee,
+            dex_fee,
+            fee_bps,
+            dex_fee_share,
+            migration_fee,
+            marketcap_threshold,
+            marketcap_currency,
+            min_supported_decimal_places,
+            max_supported_decimal_places,
+            min_supported_token_supply,
+            max_supported_token_supply,
+            coef_b,
+            timestamp
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13::text::numeric,
+            $14::text::numeric, $15, $16, $17, $18::text::numeric, $19::text::numeric, $20, $21
+        );
+    ";
+
+    let mut rows_count = 0;
+
+    for event in config_events {
+        let Event::FunctionCall(function_call_event) = event;
+
+        let event_meta = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<MoonshotConfigFunctionCallEventMeta>()
+            .unwrap();
+
+        let (instruction, config) = match function_call_event
+            .event_obj
+            .as_any()
+            .downcast_ref::<MoonshotInstructionData>()
+            .unwrap()
+        {
+            MoonshotInstructionData::ConfigInit(config) => ("ConfigInit", config),
+            MoonshotInstructionData::ConfigUpdate(config) => ("ConfigUpdate", config),
+            _ => continue,
+        };
+
+        let result = transaction.execute(
+            insert_query,
+            &[
+                &block_data.slot,
+                &function_call_event.signature,
+                &instruction,
+                &event_meta.config_authority,
+                &event_meta.config_account,
+                &config.migration_authority,
+                &config.backend_authority,
+                &config.config_authority,
+                &config.helio_fee,
+                &config.dex_fee,
+                &config.fee_bps.map(|value| value as i32),
+                &config.dex_fee_share.map(|value| value as i32),
+                &config.migration_fee.map(|value| value.to_string()),
+                &config.marketcap_threshold.map(|value| value.to_string()),
+                &config.marketcap_currency.map(|value| value as i32),
+                &config
+                    .min_supported_decimal_places
+                    .map(|value| value as i32),
+                &config
+                    .max_supported_decimal_places
+                    .map(|value| value as i32),
+                &config
+                    .min_supported_token_supply
+                    .map(|value| value.to_string()),
+                &config
+                    .max_supported_token_supply
+                    .map(|value| value.to_string()),
+                &config.coef_b.map(|value| value as i64),
+                &(event_meta.block_time as i32),
+            ],
+        );
+
+        match result {
+            Ok(count) => rows_count += count,
+            Err(err) => {
+                println!("Error inserting moonshot_config_history: {:?}", err);
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(rows_count)
+}
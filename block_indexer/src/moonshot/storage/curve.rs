@@ -0,0 +1,111 @@
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::moonshot::curve::MoonshotCurveParams;
+use crate::moonshot::curve_account::decode_curve_account;
+
+// the CurveAccount snapshot first, then the TokenMint values stored by the mint
+fn read_curve_params_row(
+    curve_account_data: Option<&[u8]>,
+    amount: Option<&str>,
+    decimals: Option<i32>,
+) -> Option<MoonshotCurveParams> {
+    if let Some(curve_account) = curve_account_data.and_then(decode_curve_account) {
+        return Some(curve_account.curve_params());
+    }
+    Some(MoonshotCurveParams {
+        total_supply: amount?.parse().ok()?,
+        decimals: u8::try_from(decimals?).ok()?,
+    })
+}
+
+/// Curve params of the mints, the mints saved in this transaction are included.
+/// The mints without stored params are not returned.
+pub fn read_curve_params(
+    transaction: &mut Transaction,
+    mints: &[&str],
+) -> Result<HashMap<String, MoonshotCurveParams>, postgres::Error> {
+    let select_query = "
+        SELECT mint, curve_account_data, amount::text AS amount, decimals
+        FROM moonshot_data
+        WHERE mint = ANY($1);
+    ";
+
+    let rows = match transaction.query(select_query, &[&mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading moonshot curve params: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let curve_account_data: Option<Vec<u8>> = row.get("curve_account_data");
+            let params = read_curve_params_row(
+                curve_account_data.as_deref(),
+                row.get("amount"),
+                row.get("decimals"),
+            )?;
+            Some((row.get("mint"), params))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::get_discriminator;
+    use solana_sdk::bs58;
+
+    fn build_curve_account_data(total_supply: u64, decimals: u8) -> Vec<u8> {
+        let mut data = get_discriminator("account:CurveAccount").to_bytes_le();
+        data.resize(8, 0);
+        data.extend_from_slice(&total_supply.to_le_bytes());
+        data.extend_from_slice(&800_000_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(
+            &bs58::decode("CjBaQUySPyfUuKwoAPtmupC2wTmnR6ciRTRVvc2qmoon")
+                .into_vec()
+                .unwrap(),
+        );
+        data.push(decimals);
+        // collateral currency, curve type
+        data.extend_from_slice(&[0, 1]);
+        data.extend_from_slice(&500_000_000_000u64.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        // bump, migration target
+        data.extend_from_slice(&[255, 0]);
+        data
+    }
+
+    #[test]
+    fn test_read_curve_params_row() {
+        let curve_account_data = build_curve_account_data(2_000_000_000_000_000, 6);
+        let curve_account = decode_curve_account(&curve_account_data).unwrap();
+        assert_eq!(
+            curve_account.mint,
+            "CjBaQUySPyfUuKwoAPtmupC2wTmnR6ciRTRVvc2qmoon"
+        );
+        assert_eq!(curve_account.curve_amount, 800_000_000_000_000_000);
+
+        // the curve account takes precedence over the mint values
+        assert_eq!(
+            read_curve_params_row(Some(&curve_account_data), Some("1000"), Some(9)),
+            Some(MoonshotCurveParams {
+                total_supply: 2_000_000_000_000_000,
+                decimals: 6,
+            })
+        );
+        assert_eq!(
+            read_curve_params_row(None, Some("1000000000000000000"), Some(9)),
+            Some(MoonshotCurveParams {
+                total_supply: 1_000_000_000_000_000_000,
+                decimals: 9,
+            })
+        );
+        assert_eq!(read_curve_params_row(Some(&[0; 8]), None, Some(9)), None);
+    }
+}
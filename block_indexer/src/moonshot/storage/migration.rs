@@ -0,0 +1,115 @@
+use postgres::Transaction;
+
+use crate::moonshot::MoonshotCompleteFunctionCallEventMeta;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+/// The migration target not found in the transaction is the one of the token
+/// mint (moonshot_data).
+pub fn save_migration_events(
+    transaction: &mut Transaction,
+    complete_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let migrations: Vec<(&str, &MoonshotCompleteFunctionCallEventMeta)> = complete_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let event_meta = function_call_event
+                .event_meta
+                .as_any()
+                .downcast_ref::<MoonshotCompleteFunctionCallEventMeta>()?;
+            (!event_meta.failed_transaction)
+                .then_some((function_call_event.signature.as_str(), event_meta))
+        })
+        .collect();
+
+    if migrations.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO moonshot_migrations (
+            slot,
+            transaction_id,
+            mint,
+            curve_account,
+            migration_authority,
+            migration_target,
+            tokens_migrated,
+            tokens_burned,
+            collateral_migrated,
+            fee,
+            label,
+            created
+        )
+        SELECT
+            tmp.slot,
+            tmp.transaction_id,
+            tmp.mint,
+            tmp.curve_account,
+            tmp.migration_authority,
+            COALESCE(tmp.migration_target, moonshot_data.migration_target),
+            tmp.tokens_migrated,
+            tmp.tokens_burned,
+            tmp.collateral_migrated,
+            tmp.fee,
+            tmp.label,
+            tmp.created
+        FROM (
+            VALUES {}
+        ) AS tmp(slot, transaction_id, mint, curve_account, migration_authority, migration_target, tokens_migrated, tokens_burned, collateral_migrated, fee, label, created)
+        LEFT JOIN moonshot_data ON moonshot_data.mint = tmp.mint
+        ON CONFLICT (transaction_id, mint) DO NOTHING;
+    ",
+        build_values_placeholders(
+            migrations.len(),
+            &[
+                "::bigint",
+                "::text",
+                "::text",
+                "::text",
+                "::text",
+                "::int",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text",
+                "::int",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(migrations.len() * 12);
+    for (signature, event_meta) in &migrations {
+        let event_log = event_meta.event_log.as_ref();
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(signature.to_string()));
+        values.push(Box::new(event_meta.mint.clone()));
+        values.push(Box::new(event_meta.curve_account.clone()));
+        values.push(Box::new(event_meta.migration_authority.clone()));
+        values.push(Box::new(
+            event_meta.migration_target.map(|target| target as i32),
+        ));
+        values.push(Box::new(
+            event_log.map(|log| log.tokens_migrated.to_string()),
+        ));
+        values.push(Box::new(event_log.map(|log| log.tokens_burned.to_string())));
+        values.push(Box::new(
+            event_log.map(|log| log.collateral_migrated.to_string()),
+        ));
+        values.push(Box::new(event_log.map(|log| log.fee.to_string())));
+        values.push(Box::new(event_log.map(|log| log.label.clone())));
+        values.push(Box::new(block_data.block_time as i32));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting moonshot_migrations: {:?}", err);
+            Err(err)
+        }
+    }
+}
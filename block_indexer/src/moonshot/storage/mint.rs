@@ -0,0 +1,125 @@
+// use as reference: js_src/src/storage/protocol/moonshot/mint.storage.ts
+
+use postgres::Transaction;
+
+use crate::moonshot::{MoonshotInstructionData, MoonshotTokenMintFunctionCallEventMeta};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+fn clean_string(value: &str) -> String {
+    value.replace('\0', "")
+}
+
+pub fn save_mint_events(
+    transaction: &mut Transaction,
+    mint_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let mut values: Vec<SqlValue> = Vec::with_capacity(mint_events.len() * 13);
+    let mut row_count = 0;
+
+    for event in mint_events {
+        let Event::FunctionCall(function_call_event) = event;
+
+        let event_meta = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<MoonshotTokenMintFunctionCallEventMeta>()
+            .unwrap();
+
+        // a failed mint didn't create the token
+        if event_meta.failed_transaction {
+            continue;
+        }
+
+        let MoonshotInstructionData::TokenMint(token_mint) = function_call_event
+            .event_obj
+            .as_any()
+            .downcast_ref::<MoonshotInstructionData>()
+            .unwrap()
+        else {
+            continue;
+        };
+
+        values.push(Box::new(event_meta.mint.clone()));
+        values.push(Box::new(clean_string(&token_mint.name)));
+        values.push(Box::new(clean_string(&token_mint.symbol)));
+        values.push(Box::new(token_mint.uri.clone()));
+        values.push(Box::new(event_meta.curve_account.clone()));
+        values.push(Box::new(event_meta.sender.clone()));
+        values.push(Box::new(token_mint.amount.to_string()));
+        values.push(Box::new(token_mint.collateral_currency as i32));
+        values.push(Box::new(token_mint.curve_type as i32));
+        values.push(Box::new(token_mint.decimals as i32));
+        values.push(Box::new(token_mint.migration_target as i32));
+        values.push(Box::new(block_data.block_time as i32));
+        values.push(Box::new(block_data.slot));
+        row_count += 1;
+    }
+
+    if row_count == 0 {
+        return Ok(0);
+    }
+
+    // the row can already exist if the block was re-indexed or a trade was stored first,
+    // the trade fields (price, counts, ...) are kept
+    let insert_query = format!(
+        "
+        INSERT INTO moonshot_data (
+            mint,
+            name,
+            symbol,
+            uri,
+            curve_account,
+            sender,
+            amount,
+            collateral_currency,
+            curve_type,
+            decimals,
+            migration_target,
+            created,
+            create_event_slot
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            name = EXCLUDED.name,
+            symbol = EXCLUDED.symbol,
+            uri = EXCLUDED.uri,
+            curve_account = EXCLUDED.curve_account,
+            sender = EXCLUDED.sender,
+            amount = EXCLUDED.amount,
+            collateral_currency = EXCLUDED.collateral_currency,
+            curve_type = EXCLUDED.curve_type,
+            decimals = EXCLUDED.decimals,
+            migration_target = EXCLUDED.migration_target,
+            created = EXCLUDED.created,
+            create_event_slot = EXCLUDED.create_event_slot;
+    ",
+        build_values_placeholders(
+            row_count,
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+                "",
+                ""
+            ],
+        )
+    );
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting moonshot_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
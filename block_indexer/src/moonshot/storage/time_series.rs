@@ -0,0 +1,112 @@
+// use as reference: js_src/src/storage/protocol/moonshot/time-series.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::moonshot::curve::MoonshotCurveParams;
+use crate::moonshot::{MoonshotTradeFunctionCallEventMeta, MoonshotTradeType};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::time_series::{Bar, BarAggregator};
+use crate::Event;
+
+/// Bars of every timeframe for the executed trades (non failed and with the
+/// TradeEvent in the logs). The price is the one of the curve after the trade.
+pub fn build_bars(
+    trade_events: &[&Event],
+    curve_params: &HashMap<String, MoonshotCurveParams>,
+) -> Vec<Bar> {
+    let mut aggregator = BarAggregator::new();
+
+    for event in trade_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let event_meta = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<MoonshotTradeFunctionCallEventMeta>()
+            .unwrap();
+
+        if event_meta.failed_transaction || event_meta.event_log.is_none() {
+            continue;
+        }
+
+        let params = curve_params
+            .get(&event_meta.mint)
+            .copied()
+            .unwrap_or_default();
+        aggregator.add_trade(
+            &event_meta.mint,
+            event_meta.block_time as i64,
+            event_meta.curve_state(&params).price,
+            event_meta.collateral_amount,
+            event_meta.trade_type == MoonshotTradeType::Buy,
+        );
+    }
+
+    aggregator.into_bars()
+}
+
+pub fn save_bars(
+    transaction: &mut Transaction,
+    bars: &[Bar],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if bars.is_empty() {
+        return Ok(0);
+    }
+
+    // open is only set by the first insert of the bar
+    let insert_query = format!(
+        "
+        INSERT INTO moonshot_price_bar (
+            timeframe, mint, timestamp, open, high, low, close, volume, buy_count, sell_count, created
+        ) VALUES {}
+        ON CONFLICT (timeframe, mint, timestamp)
+        DO UPDATE SET
+            high = GREATEST(EXCLUDED.high, moonshot_price_bar.high),
+            low = LEAST(EXCLUDED.low, moonshot_price_bar.low),
+            close = EXCLUDED.close,
+            volume = moonshot_price_bar.volume + EXCLUDED.volume,
+            buy_count = moonshot_price_bar.buy_count + EXCLUDED.buy_count,
+            sell_count = moonshot_price_bar.sell_count + EXCLUDED.sell_count;
+    ",
+        build_values_placeholders(
+            bars.len(),
+            &[
+                "",
+                "",
+                "",
+                "::float8",
+                "::float8",
+                "::float8",
+                "::float8",
+                "::text::numeric",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(bars.len() * 11);
+    for bar in bars {
+        values.push(Box::new(bar.timeframe.seconds()));
+        values.push(Box::new(bar.mint.clone()));
+        values.push(Box::new(bar.timestamp));
+        values.push(Box::new(bar.open));
+        values.push(Box::new(bar.high));
+        values.push(Box::new(bar.low));
+        values.push(Box::new(bar.close));
+        values.push(Box::new(bar.volume.to_string()));
+        values.push(Box::new(bar.buy_count));
+        values.push(Box::new(bar.sell_count));
+        values.push(Box::new(block_data.block_time));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating moonshot_price_bar: {:?}", err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,393 @@
+// use as reference: js_src/src/storage/protocol/moonshot/trade.storage.ts
+// and js_src/src/storage/protocol/moonshot/trade-history.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use super::curve::read_curve_params;
+use super::time_series::{build_bars, save_bars};
+use crate::diamond_hands::storage::save_diamond_hands;
+use crate::early_trades::{save_early_trades, EarlyTrade};
+use crate::last_trades::{save_last_trades, LastTrade};
+use crate::mint_cache::MintCache;
+use crate::moonshot::curve::{MoonshotCurveParams, MoonshotCurveState};
+use crate::moonshot::{MoonshotTradeFunctionCallEventMeta, MoonshotTradeType};
+use crate::positions::storage::save_positions;
+use crate::sol_price::{lamports_to_usd, sol_to_usd};
+use crate::storage::{
+    build_values_placeholders, execute_with_values, BlockData, SqlValue, StorageError,
+};
+use crate::Event;
+
+/// Per mint summary of the trades of a block, the curve values are the ones
+/// after the last trade.
+pub struct ConsolidatedMoonshotTrades<'a> {
+    pub mint: &'a str,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    pub volume: u64,
+    pub curve_state: MoonshotCurveState,
+}
+
+fn get_trade_meta(event: &Event) -> &MoonshotTradeFunctionCallEventMeta {
+    let Event::FunctionCall(function_call_event) = event;
+    function_call_event
+        .event_meta
+        .as_any()
+        .downcast_ref::<MoonshotTradeFunctionCallEventMeta>()
+        .unwrap()
+}
+
+/// Only the executed trades count (non failed and with the TradeEvent in the logs).
+/// The mints without curve params use the launch ones.
+pub fn consolidate_trade_events<'a>(
+    trade_events: &[&'a Event],
+    curve_params: &HashMap<String, MoonshotCurveParams>,
+) -> Vec<ConsolidatedMoonshotTrades<'a>> {
+    let mut consolidated_trades: Vec<ConsolidatedMoonshotTrades> = Vec::new();
+    let mut index_by_mint: HashMap<&str, usize> = HashMap::new();
+
+    for event in trade_events {
+        let event_meta = get_trade_meta(event);
+        if event_meta.failed_transaction || event_meta.event_log.is_none() {
+            continue;
+        }
+
+        let params = curve_params
+            .get(&event_meta.mint)
+            .copied()
+            .unwrap_or_default();
+        let curve_state = event_meta.curve_state(&params);
+
+        let index = *index_by_mint
+            .entry(event_meta.mint.as_str())
+            .or_insert_with(|| {
+                consolidated_trades.push(ConsolidatedMoonshotTrades {
+                    mint: &event_meta.mint,
+                    buy_count: 0,
+                    sell_count: 0,
+                    buy_volume: 0,
+                    sell_volume: 0,
+                    volume: 0,
+                    curve_state: curve_state.clone(),
+                });
+                consolidated_trades.len() - 1
+            });
+
+        let consolidated = &mut consolidated_trades[index];
+        match event_meta.trade_type {
+            MoonshotTradeType::Buy => {
+                consolidated.buy_count += 1;
+                consolidated.buy_volume += event_meta.collateral_amount;
+            }
+            MoonshotTradeType::Sell => {
+                consolidated.sell_count += 1;
+                consolidated.sell_volume += event_meta.collateral_amount;
+            }
+        }
+        consolidated.volume += event_meta.collateral_amount;
+        consolidated.curve_state = curve_state;
+    }
+
+    consolidated_trades
+}
+
+// this includes failed transactions
+fn save_trade_history(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if trade_events.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO moonshot_trade (
+            signer,
+            mint,
+            transaction_id,
+            allocation,
+            amount,
+            collateral_amount,
+            cost_token,
+            curve,
+            dex_fee,
+            helio_fee,
+            type,
+            is_buy,
+            timestamp,
+            created,
+            failed_transaction,
+            priority_fee,
+            compute_unit_limit,
+            compute_units_consumed,
+            base_fee,
+            jito_tip
+        ) VALUES {};
+    ",
+        build_values_placeholders(
+            trade_events.len(),
+            &[
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(trade_events.len() * 20);
+    for event in trade_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let event_meta = get_trade_meta(event);
+        let event_log = event_meta.event_log.as_ref();
+
+        values.push(Box::new(event_meta.sender.clone()));
+        values.push(Box::new(event_meta.mint.clone()));
+        values.push(Box::new(function_call_event.signature.clone()));
+        values.push(Box::new(event_log.map(|log| log.allocation.to_string())));
+        values.push(Box::new(event_log.map(|log| log.amount.to_string())));
+        values.push(Box::new(
+            event_log.map(|log| log.collateral_amount.to_string()),
+        ));
+        values.push(Box::new(event_log.map(|log| log.cost_token.clone())));
+        values.push(Box::new(event_log.map(|log| log.curve.clone())));
+        values.push(Box::new(event_log.map(|log| log.dex_fee.to_string())));
+        values.push(Box::new(event_log.map(|log| log.helio_fee.to_string())));
+        values.push(Box::new(event_log.map(|log| log.trade_type.as_u8() as i32)));
+        values.push(Box::new(event_meta.trade_type == MoonshotTradeType::Buy));
+        values.push(Box::new(event_meta.block_time as i32));
+        values.push(Box::new(block_data.block_time as i32));
+        values.push(Box::new(event_meta.failed_transaction));
+        values.push(Box::new(function_call_event.fees.priority_fee as i64));
+        values.push(Box::new(function_call_event.fees.compute_unit_limit as i32));
+        values.push(Box::new(
+            function_call_event.fees.compute_units_consumed as i32,
+        ));
+        values.push(Box::new(function_call_event.fees.base_fee as i64));
+        values.push(Box::new(function_call_event.fees.jito_tip as i64));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting moonshot_trade: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn save_prices(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    curve_params: &HashMap<String, MoonshotCurveParams>,
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let consolidated_trades = consolidate_trade_events(trade_events, curve_params);
+    if consolidated_trades.is_empty() {
+        return Ok(0);
+    }
+
+    // buy_count and sell_count have no default, a row created by the mint has them as NULL
+    let insert_query = format!(
+        "
+        INSERT INTO moonshot_data (
+            mint,
+            price,
+            marketcap,
+            percentage,
+            liquidity,
+            updated,
+            buy_count,
+            sell_count,
+            buy_volume,
+            sell_volume,
+            volume,
+            curve_token_amount,
+            curve_sol_amount,
+            curve_liquidity,
+            marketcap_usd,
+            volume_usd
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            price = EXCLUDED.price,
+            marketcap = EXCLUDED.marketcap,
+            percentage = EXCLUDED.percentage,
+            liquidity = EXCLUDED.liquidity,
+            updated = EXCLUDED.updated,
+            buy_count = EXCLUDED.buy_count + COALESCE(moonshot_data.buy_count, 0),
+            sell_count = EXCLUDED.sell_count + COALESCE(moonshot_data.sell_count, 0),
+            buy_volume = EXCLUDED.buy_volume + COALESCE(moonshot_data.buy_volume, 0),
+            sell_volume = EXCLUDED.sell_volume + COALESCE(moonshot_data.sell_volume, 0),
+            volume = EXCLUDED.volume + COALESCE(moonshot_data.volume, 0),
+            curve_token_amount = EXCLUDED.curve_token_amount,
+            curve_sol_amount = EXCLUDED.curve_sol_amount,
+            curve_liquidity = EXCLUDED.curve_liquidity,
+            marketcap_usd = EXCLUDED.marketcap_usd,
+            volume_usd = COALESCE(EXCLUDED.volume_usd, 0) + COALESCE(moonshot_data.volume_usd, 0);
+    ",
+        build_values_placeholders(
+            consolidated_trades.len(),
+            &[
+                "",
+                "::float8",
+                "::float8",
+                "::float8",
+                "::float8",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::float8",
+                "::float8",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(consolidated_trades.len() * 16);
+    for consolidated in &consolidated_trades {
+        let curve_state = &consolidated.curve_state;
+        // lamports of the curve plus the tokens of the curve valued at the current price
+        let curve_liquidity = (curve_state.curve_sol_amount as f64
+            + curve_state.curve_token_amount as f64 * curve_state.price)
+            .floor() as u64;
+
+        values.push(Box::new(consolidated.mint.to_string()));
+        values.push(Box::new(curve_state.price));
+        values.push(Box::new(curve_state.marketcap));
+        values.push(Box::new(curve_state.percentage));
+        values.push(Box::new(curve_state.liquidity));
+        values.push(Box::new(block_data.block_time as i32));
+        values.push(Box::new(consolidated.buy_count));
+        values.push(Box::new(consolidated.sell_count));
+        values.push(Box::new(consolidated.buy_volume.to_string()));
+        values.push(Box::new(consolidated.sell_volume.to_string()));
+        values.push(Box::new(consolidated.volume.to_string()));
+        values.push(Box::new(curve_state.curve_token_amount.to_string()));
+        values.push(Box::new(curve_state.curve_sol_amount.to_string()));
+        values.push(Box::new(curve_liquidity.to_string()));
+        values.push(Box::new(sol_to_usd(
+            curve_state.marketcap,
+            block_data.sol_price,
+        )));
+        values.push(Box::new(lamports_to_usd(
+            consolidated.volume,
+            block_data.sol_price,
+        )));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting moonshot_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn build_last_trades(trade_events: &[&Event], block_data: &BlockData) -> Vec<LastTrade> {
+    trade_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let event_meta = get_trade_meta(event);
+            if event_meta.failed_transaction || event_meta.event_log.is_none() {
+                return None;
+            }
+            Some(LastTrade {
+                mint: event_meta.mint.clone(),
+                slot: block_data.slot,
+                transaction_index: function_call_event.transaction_index,
+                instruction_index: function_call_event.instruction_index,
+                timestamp: event_meta.block_time as i64,
+                is_buy: matches!(event_meta.trade_type, MoonshotTradeType::Buy),
+                token_amount: event_meta.token_amount,
+                sol_amount: event_meta.collateral_amount,
+                sender: event_meta.sender.clone(),
+            })
+        })
+        .collect()
+}
+
+fn build_early_trades(trade_events: &[&Event]) -> Vec<EarlyTrade> {
+    trade_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let event_meta = get_trade_meta(event);
+            if event_meta.failed_transaction || event_meta.event_log.is_none() {
+                return None;
+            }
+            Some(EarlyTrade {
+                mint: event_meta.mint.clone(),
+                wallet: event_meta.sender.clone(),
+                transaction_id: function_call_event.signature.clone(),
+                token_amount: event_meta.token_amount,
+                is_buy: matches!(event_meta.trade_type, MoonshotTradeType::Buy),
+            })
+        })
+        .collect()
+}
+
+pub fn save_trade_events(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<u64, StorageError> {
+    let mut rows_count = 0;
+
+    let mut mints: Vec<&str> = trade_events
+        .iter()
+        .map(|event| get_trade_meta(event).mint.as_str())
+        .collect();
+    mints.sort();
+    mints.dedup();
+    let curve_params = read_curve_params(transaction, &mints)?;
+
+    rows_count += save_prices(transaction, trade_events, &curve_params, block_data)?;
+    rows_count += save_bars(
+        transaction,
+        &build_bars(trade_events, &curve_params),
+        block_data,
+    )?;
+    rows_count += save_trade_history(transaction, trade_events, block_data)?;
+    let last_trades = build_last_trades(trade_events, block_data);
+    rows_count += save_last_trades(transaction, &last_trades, block_data)?;
+    rows_count += save_diamond_hands(transaction, &last_trades, block_data)?;
+    rows_count += save_positions(transaction, &last_trades, block_data)?;
+    rows_count += save_early_trades(
+        transaction,
+        &build_early_trades(trade_events),
+        block_data,
+        mint_cache,
+    )?;
+
+    Ok(rows_count)
+}
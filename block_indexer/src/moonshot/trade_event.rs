@@ -0,0 +1,96 @@
+// use as reference: js_src/src/parsing/protocol/moonshot/trade-event.decoder.ts
+
+use num_bigint::BigUint;
+use std::sync::LazyLock;
+
+use super::{deserialize_address, find_log_event_data, get_discriminator};
+use crate::transaction_log::InstructionLogs;
+
+static MOONSHOT_TRADE_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:TradeEvent"));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoonshotTradeType {
+    Buy,
+    Sell,
+}
+
+impl MoonshotTradeType {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MoonshotTradeType::Buy),
+            1 => Some(MoonshotTradeType::Sell),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            MoonshotTradeType::Buy => 0,
+            MoonshotTradeType::Sell => 1,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct MoonshotTradeEventValues {
+    pub amount: u64,
+    pub collateral_amount: u64,
+    pub dex_fee: u64,
+    pub helio_fee: u64,
+    pub allocation: u64,
+    pub curve: String,
+    pub cost_token: String,
+    pub sender: String,
+    pub trade_type: MoonshotTradeType,
+    pub label: String,
+}
+
+// amount: u64
+// collateralAmount: u64
+// dexFee: u64
+// helioFee: u64
+// allocation: u64
+// curve: publicKey
+// costToken: publicKey
+// sender: publicKey
+// type: TradeType
+// label: string
+
+/// Returns None for malformed data or an unknown trade type.
+pub fn decode_trade_event(event_data: &[u8]) -> Option<MoonshotTradeEventValues> {
+    let mut event_data_mut = event_data;
+    let _discriminator: u64 = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let amount = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let collateral_amount = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let dex_fee = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let helio_fee = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let allocation = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    if event_data_mut.len() < 32 * 3 {
+        return None;
+    }
+    let curve = deserialize_address(&mut event_data_mut);
+    let cost_token = deserialize_address(&mut event_data_mut);
+    let sender = deserialize_address(&mut event_data_mut);
+    let trade_type: u8 = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+    let label = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+
+    Some(MoonshotTradeEventValues {
+        amount,
+        collateral_amount,
+        dex_fee,
+        helio_fee,
+        allocation,
+        curve,
+        cost_token,
+        sender,
+        trade_type: MoonshotTradeType::from_u8(trade_type)?,
+        label,
+    })
+}
+
+pub fn find_trade_event(instruction_logs: &InstructionLogs) -> Option<MoonshotTradeEventValues> {
+    find_log_event_data(instruction_logs, &MOONSHOT_TRADE_EVENT_DISCRIMINATOR)
+        .and_then(|event_data| decode_trade_event(&event_data))
+}
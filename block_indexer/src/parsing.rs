@@ -0,0 +1,206 @@
+// use as reference: js_src/src/parsing/auxiliar/parsing.ts
+// helpers shared by the protocol parsers
+
+use base64::prelude::*;
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde_json::Value;
+use sha256::digest;
+use solana_sdk::bs58;
+
+use crate::transaction_log::InstructionLogs;
+
+// Anchor events emitted with emit!
+pub const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+// wrapped SOL, the quote of most of the pools
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+pub fn is_failed_transaction(transaction_obj: &serde_json::Map<String, serde_json::Value>) -> bool {
+    transaction_obj
+        .get("meta")
+        .unwrap()
+        .get("err")
+        .unwrap()
+        .is_object()
+}
+
+/// Looks for an Anchor event emitted by the instruction ("Program data: <base64>")
+/// and returns its data (discriminator included).
+pub fn find_log_event_data(
+    instruction_logs: &InstructionLogs,
+    discriminator: &BigUint,
+) -> Option<Vec<u8>> {
+    for log_message in &instruction_logs.log_messages {
+        let Some(encoded_data) = log_message.strip_prefix(PROGRAM_DATA_LOG_PREFIX) else {
+            continue;
+        };
+        let Ok(event_data) = BASE64_STANDARD.decode(encoded_data) else {
+            continue;
+        };
+        if event_data.len() < 8 {
+            continue;
+        }
+        if BigUint::from_bytes_le(&event_data[0..8]) == *discriminator {
+            return Some(event_data);
+        }
+    }
+    None
+}
+
+pub fn deserialize_address(data: &mut &[u8]) -> String {
+    let bytes: [u8; 32] = borsh::BorshDeserialize::deserialize(data).unwrap();
+    bs58::encode(bytes).into_string()
+}
+
+pub fn get_address_index(index: u64, instruction: &Value) -> u64 {
+    match instruction.get("accounts") {
+        Some(accounts) => {
+            let accounts_as_array = accounts.as_array().unwrap();
+            let account = accounts_as_array.get(index as usize).unwrap();
+            let account_index = account.as_u64().unwrap();
+            //let account_index = account_str.parse::<u64>().unwrap();
+            account_index
+        }
+        None => instruction
+            .get("accountKeyIndexes")
+            .unwrap()
+            .get(index as usize)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .parse::<u64>()
+            .unwrap(),
+    }
+}
+
+// position of the account key in the transaction addresses (the index used by the balances)
+pub fn get_account_index(account: &str, addresses: &[String]) -> u64 {
+    addresses
+        .iter()
+        .position(|address| address == account)
+        .unwrap() as u64
+}
+
+pub struct TokenBalances {
+    pub pre_balances: Vec<serde_json::Value>,
+    pub post_balances: Vec<serde_json::Value>,
+}
+
+pub fn get_token_balances(
+    meta: &serde_json::Map<String, serde_json::Value>,
+    addresses: &[String],
+    account: &str,
+) -> TokenBalances {
+    let account_id = get_account_index(account, addresses);
+    let pre_balances: Vec<serde_json::Value> = meta
+        .get("preTokenBalances")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|balance| balance.get("accountIndex").unwrap().as_u64().unwrap() == account_id)
+        .cloned()
+        .collect();
+    let post_balances: Vec<Value> = meta
+        .get("postTokenBalances")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|balance| balance.get("accountIndex").unwrap().as_u64().unwrap() == account_id)
+        .cloned()
+        .collect();
+    TokenBalances {
+        pre_balances,
+        post_balances,
+    }
+}
+
+pub fn get_sol_balances(
+    meta: &serde_json::Map<String, serde_json::Value>,
+    addresses: &[String],
+    account: &str,
+) -> TokenBalances {
+    let account_id = get_account_index(account, addresses);
+    let pre_balance = meta
+        .get("preBalances")
+        .unwrap()
+        .get(account_id as usize)
+        .unwrap();
+    let post_balance = meta
+        .get("postBalances")
+        .unwrap()
+        .get(account_id as usize)
+        .unwrap();
+    TokenBalances {
+        pre_balances: vec![pre_balance.clone()],
+        post_balances: vec![post_balance.clone()],
+    }
+}
+
+// post - pre of every token account of the owner for the given mint
+pub fn get_token_balance_delta(
+    meta: &serde_json::Map<String, serde_json::Value>,
+    owner: &str,
+    mint: &str,
+) -> i128 {
+    let sum_balances = |key: &str| -> i128 {
+        meta.get(key)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|balance| {
+                balance.get("owner").and_then(|owner| owner.as_str()) == Some(owner)
+                    && balance.get("mint").and_then(|mint| mint.as_str()) == Some(mint)
+            })
+            .map(|balance| {
+                balance
+                    .get("uiTokenAmount")
+                    .unwrap()
+                    .get("amount")
+                    .unwrap()
+                    .as_str()
+                    .unwrap()
+                    .parse::<i128>()
+                    .unwrap()
+            })
+            .sum()
+    };
+    sum_balances("postTokenBalances") - sum_balances("preTokenBalances")
+}
+
+pub fn get_sol_balance_delta(
+    meta: &serde_json::Map<String, serde_json::Value>,
+    addresses: &[String],
+    account: &str,
+) -> i128 {
+    let sol_balances = get_sol_balances(meta, addresses, account);
+    let pre_balance = sol_balances.pre_balances[0].as_u64().unwrap() as i128;
+    let post_balance = sol_balances.post_balances[0].as_u64().unwrap() as i128;
+    post_balance - pre_balance
+}
+
+pub fn get_address_as_string(index: u64, addresses: &[String], instruction: &Value) -> String {
+    let address_index = get_address_index(index, instruction);
+    addresses.get(address_index as usize).unwrap().to_string()
+}
+
+pub fn get_accounts_count(instruction: &Value) -> usize {
+    instruction
+        .get("accounts")
+        .or_else(|| instruction.get("accountKeyIndexes"))
+        .and_then(|accounts| accounts.as_array())
+        .map(|accounts| accounts.len())
+        .unwrap_or(0)
+}
+
+pub fn get_discriminator(discriminator: &str) -> BigUint {
+    let digest_result = digest(discriminator.as_bytes());
+    let digest_big_uint = BigUint::from_str_radix(&digest_result, 16).unwrap();
+    let digest_bytes = digest_big_uint.to_bytes_be();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest_bytes[0..8]);
+    BigUint::from_bytes_le(&bytes)
+}
@@ -0,0 +1,102 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/positions-full.storage.ts
+// and js_src/src/storage/protocol/pumpfun/positions.storage.ts
+
+// Position of every (wallet, mint), fed by the executed trades of all the
+// protocols. The cost of the held tokens is tracked with the average cost
+// method, in lamports.
+
+pub mod storage;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Position {
+    pub token_received: u128,
+    pub token_sent: u128,
+    pub sol_received: u128,
+    pub sol_sent: u128,
+    // tokens bought and not sold yet
+    pub token_quantity: u128,
+    // cost of token_quantity
+    pub cost_basis: u128,
+    pub realized_pnl: i128,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub last_buy: Option<i64>,
+    // sol and token amounts of the last trade, the current price
+    pub last_trade_amounts: Option<(u64, u64)>,
+}
+
+impl Position {
+    /// The trades must be applied in the order they were executed. `slot` is
+    /// the last buy when the trade is a buy.
+    pub fn apply_trade(&mut self, is_buy: bool, token_amount: u64, sol_amount: u64, slot: i64) {
+        if is_buy {
+            self.token_received += token_amount as u128;
+            self.sol_sent += sol_amount as u128;
+            self.token_quantity += token_amount as u128;
+            self.cost_basis += sol_amount as u128;
+            self.buy_count += 1;
+            self.last_buy = Some(slot);
+        } else {
+            self.token_sent += token_amount as u128;
+            self.sol_received += sol_amount as u128;
+            self.sell_count += 1;
+
+            // the tokens sold beyond the quantity (received by transfer or bought
+            // before they were tracked) have an unknown cost, they don't realize
+            // profit
+            let sell_quantity = self.token_quantity.min(token_amount as u128);
+            if sell_quantity > 0 {
+                let sell_cost = self.cost_basis * sell_quantity / self.token_quantity;
+                let proceeds = sol_amount as u128 * sell_quantity / token_amount as u128;
+                self.realized_pnl += proceeds as i128 - sell_cost as i128;
+                self.token_quantity -= sell_quantity;
+                self.cost_basis -= sell_cost;
+            }
+        }
+
+        if token_amount > 0 {
+            self.last_trade_amounts = Some((sol_amount, token_amount));
+        }
+    }
+
+    /// Profit of the held tokens at the price of the last trade.
+    pub fn unrealized_pnl(&self) -> i128 {
+        let Some((sol_amount, token_amount)) = self.last_trade_amounts else {
+            return 0;
+        };
+        let value = self.token_quantity * sol_amount as u128 / token_amount as u128;
+        value as i128 - self.cost_basis as i128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_trades() {
+        let mut position = Position::default();
+        position.apply_trade(true, 1000, 100, 10);
+        position.apply_trade(true, 1000, 300, 11);
+        assert_eq!(position.token_quantity, 2000);
+        assert_eq!(position.cost_basis, 400);
+        assert_eq!(position.unrealized_pnl(), 2000 * 300 / 1000 - 400);
+
+        // the average cost is 0.2 per token
+        position.apply_trade(false, 500, 150, 12);
+        assert_eq!(position.realized_pnl, 150 - 100);
+        assert_eq!(position.token_quantity, 1500);
+        assert_eq!(position.cost_basis, 300);
+        assert_eq!(position.last_buy, Some(11));
+
+        // only the 1500 known tokens realize profit
+        position.apply_trade(false, 3000, 600, 13);
+        assert_eq!(position.realized_pnl, 50 + 300 - 300);
+        assert_eq!(position.token_quantity, 0);
+        assert_eq!(position.cost_basis, 0);
+        assert_eq!(position.token_sent, 3500);
+        assert_eq!(position.sol_received, 750);
+        assert_eq!(position.sell_count, 2);
+        assert_eq!(position.unrealized_pnl(), 0);
+    }
+}
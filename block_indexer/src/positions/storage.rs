@@ -0,0 +1,464 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/positions-full.storage.ts
+// and js_src/src/storage/protocol/pumpfun/positions.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use super::Position;
+use crate::last_trades::LastTrade;
+use crate::storage::{
+    build_values_placeholders, execute_with_values, parse_numeric, BlockData, SqlValue,
+};
+
+// decimals of the numeric(38, 10) prices
+const PRICE_DECIMALS: u32 = 10;
+
+/// `numerator / denominator` with PRICE_DECIMALS decimals (truncated).
+fn format_price(numerator: u128, denominator: u128) -> String {
+    if denominator == 0 {
+        return "0".to_string();
+    }
+    let scale = 10u128.pow(PRICE_DECIMALS);
+    let scaled = numerator * scale / denominator;
+    format!(
+        "{}.{:0>width$}",
+        scaled / scale,
+        scaled % scale,
+        width = PRICE_DECIMALS as usize
+    )
+}
+
+struct WalletPosition<'a> {
+    wallet: &'a str,
+    mint: &'a str,
+    // trades of the block only
+    block_position: Position,
+    // None when the wallet never bought the mint
+    position: Option<Position>,
+}
+
+fn read_current_wallet_positions(
+    transaction: &mut Transaction,
+    wallets: &[&str],
+    mints: &[&str],
+) -> Result<HashMap<(String, String), Position>, postgres::Error> {
+    let select_query = "
+        SELECT
+            wallet,
+            token,
+            COALESCE(sol_received, 0)::text AS sol_received,
+            COALESCE(sol_sent, 0)::text AS sol_sent,
+            token_received::text,
+            token_sent::text,
+            token_quantity::text,
+            cost_basis::text,
+            realized_pnl::text,
+            last_buy
+        FROM wallet_positions
+        WHERE (wallet, token) IN (SELECT * FROM unnest($1::varchar[], $2::varchar[]))
+        FOR UPDATE;
+    ";
+
+    let rows = match transaction.query(select_query, &[&wallets, &mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error fetching wallet_positions: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let position = Position {
+                token_received: parse_numeric(row.get("token_received")) as u128,
+                token_sent: parse_numeric(row.get("token_sent")) as u128,
+                sol_received: parse_numeric(row.get("sol_received")) as u128,
+                sol_sent: parse_numeric(row.get("sol_sent")) as u128,
+                token_quantity: parse_numeric(row.get("token_quantity")) as u128,
+                cost_basis: parse_numeric(row.get("cost_basis")) as u128,
+                realized_pnl: parse_numeric(row.get("realized_pnl")),
+                last_buy: Some(row.get("last_buy")),
+                ..Position::default()
+            };
+            ((row.get("wallet"), row.get("token")), position)
+        })
+        .collect())
+}
+
+fn build_wallet_positions<'a>(
+    trades: &'a [LastTrade],
+    mut current_positions: HashMap<(String, String), Position>,
+) -> Vec<WalletPosition<'a>> {
+    let mut wallet_positions: Vec<WalletPosition> = Vec::new();
+    let mut index_by_pair: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for trade in trades {
+        let pair = (trade.sender.as_str(), trade.mint.as_str());
+        let index = *index_by_pair.entry(pair).or_insert_with(|| {
+            wallet_positions.push(WalletPosition {
+                wallet: pair.0,
+                mint: pair.1,
+                block_position: Position::default(),
+                position: current_positions.remove(&(pair.0.to_string(), pair.1.to_string())),
+            });
+            wallet_positions.len() - 1
+        });
+
+        let wallet_position = &mut wallet_positions[index];
+        wallet_position.block_position.apply_trade(
+            trade.is_buy,
+            trade.token_amount,
+            trade.sol_amount,
+            trade.slot,
+        );
+        // a position is opened by a buy, the cost of the tokens sold before is unknown
+        if wallet_position.position.is_none() && trade.is_buy {
+            wallet_position.position = Some(Position::default());
+        }
+        if let Some(position) = &mut wallet_position.position {
+            position.apply_trade(
+                trade.is_buy,
+                trade.token_amount,
+                trade.sol_amount,
+                trade.slot,
+            );
+        }
+    }
+
+    wallet_positions
+}
+
+// every trade is counted, the block deltas are added to the current values
+fn save_positions_full(
+    transaction: &mut Transaction,
+    wallet_positions: &[WalletPosition],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let insert_query = format!(
+        "
+        INSERT INTO positions_full (
+            user_wallet,
+            token_mint,
+            token_received,
+            token_sent,
+            sol_received,
+            sol_sent,
+            updated_slot,
+            buy_count,
+            sell_count
+        ) VALUES {}
+        ON CONFLICT (user_wallet, token_mint) DO UPDATE
+        SET
+            token_received = positions_full.token_received + EXCLUDED.token_received,
+            token_sent = positions_full.token_sent + EXCLUDED.token_sent,
+            sol_received = positions_full.sol_received + EXCLUDED.sol_received,
+            sol_sent = positions_full.sol_sent + EXCLUDED.sol_sent,
+            updated_slot = EXCLUDED.updated_slot,
+            buy_count = COALESCE(positions_full.buy_count, 0) + EXCLUDED.buy_count,
+            sell_count = COALESCE(positions_full.sell_count, 0) + EXCLUDED.sell_count;
+    ",
+        build_values_placeholders(
+            wallet_positions.len(),
+            &[
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(wallet_positions.len() * 9);
+    for wallet_position in wallet_positions {
+        let block_position = &wallet_position.block_position;
+
+        values.push(Box::new(wallet_position.wallet.to_string()));
+        values.push(Box::new(wallet_position.mint.to_string()));
+        values.push(Box::new(block_position.token_received.to_string()));
+        values.push(Box::new(block_position.token_sent.to_string()));
+        values.push(Box::new(block_position.sol_received.to_string()));
+        values.push(Box::new(block_position.sol_sent.to_string()));
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(block_position.buy_count));
+        values.push(Box::new(block_position.sell_count));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting positions_full: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+// the values are the ones after the block (not deltas)
+fn save_wallet_positions(
+    transaction: &mut Transaction,
+    positions: &[(&WalletPosition, &Position)],
+) -> Result<u64, postgres::Error> {
+    let insert_query = format!(
+        "
+        INSERT INTO wallet_positions (
+            wallet,
+            token,
+            sol_received,
+            sol_sent,
+            token_received,
+            token_sent,
+            token_quantity,
+            average_price,
+            realized_pnl,
+            cost_basis,
+            last_buy
+        ) VALUES {}
+        ON CONFLICT (wallet, token) DO UPDATE
+        SET
+            sol_received = EXCLUDED.sol_received,
+            sol_sent = EXCLUDED.sol_sent,
+            token_received = EXCLUDED.token_received,
+            token_sent = EXCLUDED.token_sent,
+            token_quantity = EXCLUDED.token_quantity,
+            average_price = EXCLUDED.average_price,
+            realized_pnl = EXCLUDED.realized_pnl,
+            cost_basis = EXCLUDED.cost_basis,
+            last_buy = EXCLUDED.last_buy;
+    ",
+        build_values_placeholders(
+            positions.len(),
+            &[
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(positions.len() * 11);
+    for (wallet_position, position) in positions {
+        values.push(Box::new(wallet_position.wallet.to_string()));
+        values.push(Box::new(wallet_position.mint.to_string()));
+        values.push(Box::new(position.sol_received.to_string()));
+        values.push(Box::new(position.sol_sent.to_string()));
+        values.push(Box::new(position.token_received.to_string()));
+        values.push(Box::new(position.token_sent.to_string()));
+        values.push(Box::new(position.token_quantity.to_string()));
+        values.push(Box::new(format_price(
+            position.cost_basis,
+            position.token_quantity,
+        )));
+        values.push(Box::new(position.realized_pnl.to_string()));
+        values.push(Box::new(position.cost_basis.to_string()));
+        values.push(Box::new(position.last_buy.unwrap()));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating wallet_positions: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+// TODO: remove with the positions table, wallet_positions has the same values.
+// last_buy is the block time here
+fn save_legacy_positions(
+    transaction: &mut Transaction,
+    positions: &[(&WalletPosition, &Position)],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let insert_query = format!(
+        "
+        INSERT INTO positions (
+            user_wallet,
+            token_mint,
+            token_received,
+            sol_received,
+            token_sent,
+            sol_sent,
+            net_position,
+            cost_basis,
+            realized_pnl,
+            unrealized_pnl,
+            current_price,
+            last_buy
+        ) VALUES {}
+        ON CONFLICT (user_wallet, token_mint) DO UPDATE
+        SET
+            token_received = EXCLUDED.token_received,
+            sol_received = EXCLUDED.sol_received,
+            token_sent = EXCLUDED.token_sent,
+            sol_sent = EXCLUDED.sol_sent,
+            net_position = EXCLUDED.net_position,
+            cost_basis = EXCLUDED.cost_basis,
+            realized_pnl = EXCLUDED.realized_pnl,
+            unrealized_pnl = EXCLUDED.unrealized_pnl,
+            current_price = EXCLUDED.current_price,
+            last_buy = COALESCE(EXCLUDED.last_buy, positions.last_buy),
+            updated_at = CURRENT_TIMESTAMP;
+    ",
+        build_values_placeholders(
+            positions.len(),
+            &[
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::bigint",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(positions.len() * 12);
+    for (wallet_position, position) in positions {
+        let current_price = match position.last_trade_amounts {
+            Some((sol_amount, token_amount)) => {
+                format_price(sol_amount as u128, token_amount as u128)
+            }
+            None => "0".to_string(),
+        };
+        let last_buy = wallet_position
+            .block_position
+            .last_buy
+            .map(|_| block_data.block_time);
+
+        values.push(Box::new(wallet_position.wallet.to_string()));
+        values.push(Box::new(wallet_position.mint.to_string()));
+        values.push(Box::new(position.token_received.to_string()));
+        values.push(Box::new(position.sol_received.to_string()));
+        values.push(Box::new(position.token_sent.to_string()));
+        values.push(Box::new(position.sol_sent.to_string()));
+        values.push(Box::new(position.token_quantity.to_string()));
+        values.push(Box::new(position.cost_basis.to_string()));
+        values.push(Box::new(position.realized_pnl.to_string()));
+        values.push(Box::new(position.unrealized_pnl().to_string()));
+        values.push(Box::new(current_price));
+        values.push(Box::new(last_buy));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating positions: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+/// Only the executed trades are expected, in the order of the block.
+pub fn save_positions(
+    transaction: &mut Transaction,
+    trades: &[LastTrade],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if trades.is_empty() {
+        return Ok(0);
+    }
+
+    let mut pairs: Vec<(&str, &str)> = trades
+        .iter()
+        .map(|trade| (trade.sender.as_str(), trade.mint.as_str()))
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+    let wallets: Vec<&str> = pairs.iter().map(|(wallet, _)| *wallet).collect();
+    let mints: Vec<&str> = pairs.iter().map(|(_, mint)| *mint).collect();
+
+    let current_positions = read_current_wallet_positions(transaction, &wallets, &mints)?;
+    let wallet_positions = build_wallet_positions(trades, current_positions);
+
+    let mut rows_count = save_positions_full(transaction, &wallet_positions, block_data)?;
+
+    let positions: Vec<(&WalletPosition, &Position)> = wallet_positions
+        .iter()
+        .filter_map(|wallet_position| Some((wallet_position, wallet_position.position.as_ref()?)))
+        .collect();
+    if !positions.is_empty() {
+        rows_count += save_wallet_positions(transaction, &positions)?;
+        rows_count += save_legacy_positions(transaction, &positions, block_data)?;
+    }
+
+    Ok(rows_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_trade(sender: &str, is_buy: bool, token_amount: u64, sol_amount: u64) -> LastTrade {
+        LastTrade {
+            mint: "mint".to_string(),
+            slot: 100,
+            transaction_index: 0,
+            instruction_index: 0,
+            timestamp: 1739711240,
+            is_buy,
+            token_amount,
+            sol_amount,
+            sender: sender.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_wallet_positions() {
+        let trades = [
+            build_trade("transferred", false, 100, 10),
+            build_trade("transferred", true, 200, 30),
+            build_trade("holder", false, 300, 60),
+        ];
+        let current_positions = HashMap::from([(
+            ("holder".to_string(), "mint".to_string()),
+            Position {
+                token_received: 1000,
+                sol_received: 0,
+                sol_sent: 100,
+                token_quantity: 1000,
+                cost_basis: 100,
+                last_buy: Some(50),
+                ..Position::default()
+            },
+        )]);
+
+        let wallet_positions = build_wallet_positions(&trades, current_positions);
+        assert_eq!(wallet_positions.len(), 2);
+
+        // the sell before the first buy is only in the block deltas
+        let transferred = &wallet_positions[0];
+        assert_eq!(transferred.block_position.token_sent, 100);
+        assert_eq!(transferred.block_position.buy_count, 1);
+        let position = transferred.position.as_ref().unwrap();
+        assert_eq!(position.token_sent, 0);
+        assert_eq!(position.token_quantity, 200);
+        assert_eq!(position.last_buy, Some(100));
+
+        let holder = wallet_positions[1].position.as_ref().unwrap();
+        assert_eq!(holder.token_quantity, 700);
+        assert_eq!(holder.realized_pnl, 60 - 30);
+        assert_eq!(holder.last_buy, Some(50));
+
+        assert_eq!(format_price(300, 2000), "0.1500000000");
+        assert_eq!(format_price(7, 3), "2.3333333333");
+    }
+}
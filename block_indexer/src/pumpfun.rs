@@ -0,0 +1,529 @@
+// use as reference: js_src/src/parsing/protocol/pumpfun/pumpfun.parser.ts
+// and js_src/src/parsing/protocol/pumpfun/pumpfun.decoder.ts
+
+use num_bigint::BigUint;
+use serde_json::Value;
+use solana_sdk::bs58;
+use std::any::Any;
+use std::sync::LazyLock;
+
+use crate::compute_budget::TransactionFees;
+use crate::parsing::{deserialize_address, get_discriminator, is_failed_transaction};
+use crate::transaction_log::InstructionLogs;
+use crate::{
+    Event, FunctionCallEvent, FunctionCallEventMeta, FunctionCallInstructionData, GenericEventType,
+    IndexerEventSource,
+};
+
+mod accounts;
+pub mod curve;
+mod events;
+pub mod storage;
+use accounts::PumpfunTradeAccounts;
+use events::{decode_event, find_events, is_event_logged};
+pub use events::{
+    PumpfunCompleteEventValues, PumpfunCreateEventValues, PumpfunEvent,
+    PumpfunSetParamsEventValues, PumpfunTradeEventValues,
+};
+
+pub const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+pub struct PumpfunParser;
+
+#[allow(dead_code)]
+pub struct PumpfunTradeFunctionCallEventMeta {
+    pub block_time: u64,
+    pub sender: String,
+    pub mint: String,
+    pub is_buy: bool,
+    pub failed_transaction: bool,
+    // None for failed transactions (there are no logs)
+    pub event_log: Option<PumpfunTradeEventValues>,
+}
+
+impl FunctionCallEventMeta for PumpfunTradeFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct PumpfunTokenMintFunctionCallEventMeta {
+    pub block_time: u64,
+    pub failed_transaction: bool,
+    pub event_log: PumpfunCreateEventValues,
+}
+
+impl FunctionCallEventMeta for PumpfunTokenMintFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct PumpfunCompleteFunctionCallEventMeta {
+    pub block_time: u64,
+    pub failed_transaction: bool,
+    pub event_log: PumpfunCompleteEventValues,
+}
+
+impl FunctionCallEventMeta for PumpfunCompleteFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct PumpfunSetParamsFunctionCallEventMeta {
+    pub block_time: u64,
+    pub failed_transaction: bool,
+    pub event_log: PumpfunSetParamsEventValues,
+}
+
+impl FunctionCallEventMeta for PumpfunSetParamsFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl PumpfunParser {
+    pub fn new() -> Self {
+        PumpfunParser
+    }
+
+    /// The events come from the logs of the instruction ("Program data: ...") or,
+    /// for the program versions that use emit_cpi!, from a self-CPI inner instruction.
+    /// A self-CPI event also found in the logs is skipped, it is read from the logs.
+    pub fn parse_instruction(
+        &self,
+        transaction_obj: &serde_json::Map<String, serde_json::Value>,
+        instruction: &Value,
+        addresses: &[String],
+        block_time: u64,
+        instruction_logs: Option<&InstructionLogs>,
+    ) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+
+        let program_id_index = instruction.get("programIdIndex").unwrap().as_u64().unwrap();
+        let program_id = addresses.get(program_id_index as usize).unwrap();
+
+        if program_id != PUMPFUN_PROGRAM_ID {
+            return events;
+        }
+
+        let instruction_data_str = instruction.get("data").unwrap().as_str().unwrap();
+        let instruction_data_bytes = bs58::decode(instruction_data_str).into_vec().unwrap();
+
+        let decoded_instruction = decode_instruction_data(&instruction_data_bytes);
+        let signature = transaction_obj
+            .get("transaction")
+            .unwrap()
+            .get("signatures")
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // failed transactions have no events, only the trade attempt is kept
+        if is_failed_transaction(transaction_obj) {
+            if let PumpfunInstructionData::Buy(_) | PumpfunInstructionData::Sell(_) =
+                decoded_instruction
+            {
+                let Some(accounts) = PumpfunTradeAccounts::resolve(instruction, addresses) else {
+                    return events;
+                };
+                let is_buy = matches!(decoded_instruction, PumpfunInstructionData::Buy(_));
+                events.push(Event::FunctionCall(FunctionCallEvent {
+                    source: IndexerEventSource::Pumpfun,
+                    event_type: GenericEventType::Trade,
+                    slot: 0,
+                    transaction_index: 0,
+                    instruction_index: 0,
+                    fees: TransactionFees::default(),
+                    signature,
+                    event_obj: Box::new(decoded_instruction),
+                    event_meta: Box::new(PumpfunTradeFunctionCallEventMeta {
+                        block_time,
+                        sender: accounts.user,
+                        mint: accounts.mint,
+                        is_buy,
+                        failed_transaction: true,
+                        event_log: None,
+                    }),
+                }));
+            }
+            return events;
+        }
+
+        let pumpfun_events = match &decoded_instruction {
+            PumpfunInstructionData::EventCpi(event) => {
+                if is_event_logged(transaction_obj, &instruction_data_bytes[8..]) {
+                    vec![]
+                } else {
+                    vec![event.clone()]
+                }
+            }
+            _ => instruction_logs
+                .filter(|instruction_logs| instruction_logs.address == PUMPFUN_PROGRAM_ID)
+                .map(find_events)
+                .unwrap_or_default(),
+        };
+
+        for pumpfun_event in pumpfun_events {
+            let (event_type, event_meta): (GenericEventType, Box<dyn FunctionCallEventMeta>) =
+                match pumpfun_event {
+                    PumpfunEvent::Trade(event_log) => (
+                        GenericEventType::Trade,
+                        Box::new(PumpfunTradeFunctionCallEventMeta {
+                            block_time,
+                            sender: event_log.user.clone(),
+                            mint: event_log.mint.clone(),
+                            is_buy: event_log.is_buy,
+                            failed_transaction: false,
+                            event_log: Some(event_log),
+                        }),
+                    ),
+                    PumpfunEvent::Create(event_log) => (
+                        GenericEventType::TokenMint,
+                        Box::new(PumpfunTokenMintFunctionCallEventMeta {
+                            block_time,
+                            failed_transaction: false,
+                            event_log,
+                        }),
+                    ),
+                    PumpfunEvent::Complete(event_log) => (
+                        GenericEventType::Complete,
+                        Box::new(PumpfunCompleteFunctionCallEventMeta {
+                            block_time,
+                            failed_transaction: false,
+                            event_log,
+                        }),
+                    ),
+                    PumpfunEvent::SetParams(event_log) => (
+                        GenericEventType::Info,
+                        Box::new(PumpfunSetParamsFunctionCallEventMeta {
+                            block_time,
+                            failed_transaction: false,
+                            event_log,
+                        }),
+                    ),
+                };
+
+            events.push(Event::FunctionCall(FunctionCallEvent {
+                source: IndexerEventSource::Pumpfun,
+                event_type,
+                slot: 0,
+                transaction_index: 0,
+                instruction_index: 0,
+                fees: TransactionFees::default(),
+                signature: signature.clone(),
+                event_obj: Box::new(decoded_instruction.clone()),
+                event_meta,
+            }));
+        }
+
+        events
+    }
+}
+
+enum PumpfunInstructionDiscriminator {
+    Initialize,
+    SetParams,
+    Create,
+    Buy,
+    Sell,
+    Withdraw,
+    EventCpi,
+    Unknown,
+}
+
+static PUMPFUN_INITIALIZE_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:initialize"));
+// the program was built with the camelCase name
+static PUMPFUN_SET_PARAMS_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:setParams"));
+static PUMPFUN_CREATE_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:create"));
+static PUMPFUN_BUY_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:buy"));
+static PUMPFUN_SELL_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:sell"));
+static PUMPFUN_WITHDRAW_INSTRUCTION_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("global:withdraw"));
+// EVENT_IX_TAG of Anchor (0x1d9acb512ea545e4 as little-endian bytes), prefix of
+// the self-CPI instructions used by emit_cpi! (followed by the event data)
+const PUMPFUN_EVENT_CPI_PREFIX: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+static PUMPFUN_EVENT_CPI_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| BigUint::from_bytes_le(&PUMPFUN_EVENT_CPI_PREFIX));
+
+impl PumpfunInstructionDiscriminator {
+    fn from_big_uint(value: BigUint) -> Self {
+        if value == *PUMPFUN_BUY_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::Buy
+        } else if value == *PUMPFUN_SELL_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::Sell
+        } else if value == *PUMPFUN_CREATE_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::Create
+        } else if value == *PUMPFUN_EVENT_CPI_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::EventCpi
+        } else if value == *PUMPFUN_SET_PARAMS_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::SetParams
+        } else if value == *PUMPFUN_INITIALIZE_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::Initialize
+        } else if value == *PUMPFUN_WITHDRAW_INSTRUCTION_DISCRIMINATOR {
+            PumpfunInstructionDiscriminator::Withdraw
+        } else {
+            PumpfunInstructionDiscriminator::Unknown
+        }
+    }
+}
+
+// amount: u64
+// maxSolCost: u64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpfunBuyValues {
+    pub amount: u64,
+    pub max_sol_cost: u64,
+}
+
+// amount: u64
+// minSolOutput: u64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpfunSellValues {
+    pub amount: u64,
+    pub min_sol_output: u64,
+}
+
+// name: string
+// symbol: string
+// uri: string
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpfunCreateValues {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+// feeRecipient: pubkey
+// initialVirtualTokenReserves: u64
+// initialVirtualSolReserves: u64
+// initialRealTokenReserves: u64
+// tokenTotalSupply: u64
+// feeBasisPoints: u64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpfunSetParamsValues {
+    pub fee_recipient: String,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+}
+
+// malformed instruction data (the program rejects it) is decoded as None
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum PumpfunInstructionData {
+    Initialize,
+    SetParams(Option<PumpfunSetParamsValues>),
+    Create(Option<PumpfunCreateValues>),
+    Buy(Option<PumpfunBuyValues>),
+    Sell(Option<PumpfunSellValues>),
+    Withdraw,
+    EventCpi(PumpfunEvent),
+    Unknown,
+}
+
+impl FunctionCallInstructionData for PumpfunInstructionData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn decode_instruction_data(instruction_data: &[u8]) -> PumpfunInstructionData {
+    if instruction_data.len() < 8 {
+        return PumpfunInstructionData::Unknown;
+    }
+
+    let instruction_type = BigUint::from_bytes_le(&instruction_data[0..8]);
+
+    match PumpfunInstructionDiscriminator::from_big_uint(instruction_type) {
+        PumpfunInstructionDiscriminator::Initialize => PumpfunInstructionData::Initialize,
+        PumpfunInstructionDiscriminator::SetParams => {
+            PumpfunInstructionData::SetParams(decode_set_params(instruction_data))
+        }
+        PumpfunInstructionDiscriminator::Create => {
+            PumpfunInstructionData::Create(decode_create(instruction_data))
+        }
+        PumpfunInstructionDiscriminator::Buy => {
+            PumpfunInstructionData::Buy(decode_buy(instruction_data))
+        }
+        PumpfunInstructionDiscriminator::Sell => {
+            PumpfunInstructionData::Sell(decode_sell(instruction_data))
+        }
+        PumpfunInstructionDiscriminator::Withdraw => PumpfunInstructionData::Withdraw,
+        PumpfunInstructionDiscriminator::EventCpi => match decode_event(&instruction_data[8..]) {
+            Some(event) => PumpfunInstructionData::EventCpi(event),
+            None => PumpfunInstructionData::Unknown,
+        },
+        PumpfunInstructionDiscriminator::Unknown => PumpfunInstructionData::Unknown,
+    }
+}
+
+fn decode_buy(instruction_data: &[u8]) -> Option<PumpfunBuyValues> {
+    let mut instruction_data_mut = &instruction_data[8..];
+    let amount = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let max_sol_cost = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(PumpfunBuyValues {
+        amount,
+        max_sol_cost,
+    })
+}
+
+fn decode_sell(instruction_data: &[u8]) -> Option<PumpfunSellValues> {
+    let mut instruction_data_mut = &instruction_data[8..];
+    let amount = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let min_sol_output = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(PumpfunSellValues {
+        amount,
+        min_sol_output,
+    })
+}
+
+fn decode_create(instruction_data: &[u8]) -> Option<PumpfunCreateValues> {
+    let mut instruction_data_mut = &instruction_data[8..];
+    let name = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let symbol = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let uri = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(PumpfunCreateValues { name, symbol, uri })
+}
+
+fn decode_set_params(instruction_data: &[u8]) -> Option<PumpfunSetParamsValues> {
+    let mut instruction_data_mut = &instruction_data[8..];
+    if instruction_data_mut.len() < 32 + 8 * 5 {
+        return None;
+    }
+    let fee_recipient = deserialize_address(&mut instruction_data_mut);
+    let initial_virtual_token_reserves =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let initial_virtual_sol_reserves =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let initial_real_token_reserves =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let token_total_supply =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let fee_basis_points = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(PumpfunSetParamsValues {
+        fee_recipient,
+        initial_virtual_token_reserves,
+        initial_virtual_sol_reserves,
+        initial_real_token_reserves,
+        token_total_supply,
+        fee_basis_points,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::prelude::*;
+
+    #[test]
+    fn test_discriminators() {
+        // values of PumpfunInstructionDiscriminator in instruction-data.ts
+        assert_eq!(
+            PUMPFUN_BUY_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "16927863322537952870"
+        );
+        assert_eq!(
+            PUMPFUN_SELL_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "12502976635542562355"
+        );
+        assert_eq!(
+            PUMPFUN_CREATE_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "8576854823835016728"
+        );
+        assert_eq!(
+            PUMPFUN_SET_PARAMS_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "18411476951383809957"
+        );
+        assert_eq!(
+            PUMPFUN_INITIALIZE_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "17121445590508351407"
+        );
+        assert_eq!(
+            PUMPFUN_WITHDRAW_INSTRUCTION_DISCRIMINATOR.to_string(),
+            "2495396153584390839"
+        );
+    }
+
+    #[test]
+    fn test_decode_trade_event() {
+        // TradeEvent discriminator (bddb7fd34ee661ee in pumpfun.decoder.ts) + fields
+        let mut event_data = vec![0xbd, 0xdb, 0x7f, 0xd3, 0x4e, 0xe6, 0x61, 0xee];
+        event_data.extend_from_slice(&[1u8; 32]);
+        event_data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+        event_data.extend_from_slice(&35_000_000_000_000u64.to_le_bytes());
+        event_data.push(1);
+        event_data.extend_from_slice(&[2u8; 32]);
+        event_data.extend_from_slice(&1_739_711_240i64.to_le_bytes());
+        event_data.extend_from_slice(&31_500_000_000u64.to_le_bytes());
+        event_data.extend_from_slice(&1_038_000_000_000_000u64.to_le_bytes());
+        event_data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+        event_data.extend_from_slice(&758_000_000_000_000u64.to_le_bytes());
+
+        let instruction_logs = InstructionLogs {
+            address: PUMPFUN_PROGRAM_ID.to_string(),
+            log_messages: vec![
+                "Program log: Instruction: Buy".to_string(),
+                format!("Program data: {}", BASE64_STANDARD.encode(&event_data)),
+            ],
+        };
+
+        let events = find_events(&instruction_logs);
+        assert_eq!(events.len(), 1);
+        let PumpfunEvent::Trade(trade_event) = &events[0] else {
+            panic!("expected a TradeEvent");
+        };
+        assert_eq!(trade_event.mint, bs58::encode([1u8; 32]).into_string());
+        assert_eq!(trade_event.user, bs58::encode([2u8; 32]).into_string());
+        assert_eq!(trade_event.sol_amount, 1_500_000_000);
+        assert_eq!(trade_event.token_amount, 35_000_000_000_000);
+        assert!(trade_event.is_buy);
+        assert_eq!(trade_event.timestamp, 1_739_711_240);
+        assert_eq!(trade_event.real_token_reserves, 758_000_000_000_000);
+
+        // the same event as self-CPI instruction data, the tag is the first 8 bytes
+        // of the Pump.fun self-CPI instructions on chain
+        let mut cpi_data = vec![0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+        cpi_data.extend_from_slice(&event_data);
+        assert!(matches!(
+            decode_instruction_data(&cpi_data),
+            PumpfunInstructionData::EventCpi(PumpfunEvent::Trade(_))
+        ));
+
+        // the event is read once, from the logs when they have it
+        let transaction_obj = serde_json::json!({
+            "meta": { "logMessages": instruction_logs.log_messages },
+        });
+        assert!(is_event_logged(
+            transaction_obj.as_object().unwrap(),
+            &cpi_data[8..]
+        ));
+        let transaction_obj = serde_json::json!({ "meta": { "logMessages": [] } });
+        assert!(!is_event_logged(
+            transaction_obj.as_object().unwrap(),
+            &cpi_data[8..]
+        ));
+    }
+}
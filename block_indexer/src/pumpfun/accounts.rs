@@ -0,0 +1,57 @@
+// Account layouts of the Pump.fun instructions (positions as in the program IDL).
+// Only the trade layout is needed, the rest of the data comes in the events.
+
+use serde_json::Value;
+
+use crate::parsing::{get_accounts_count, get_address_as_string};
+
+// Buy and Sell share the first 7 accounts
+#[allow(dead_code)]
+pub struct PumpfunTradeAccounts {
+    pub global: String,
+    pub fee_recipient: String,
+    pub mint: String,
+    pub bonding_curve: String,
+    pub associated_bonding_curve: String,
+    pub associated_user: String,
+    pub user: String,
+}
+
+impl PumpfunTradeAccounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 7 {
+            return None;
+        }
+
+        Some(PumpfunTradeAccounts {
+            global: get_address_as_string(0, addresses, instruction),
+            fee_recipient: get_address_as_string(1, addresses, instruction),
+            mint: get_address_as_string(2, addresses, instruction),
+            bonding_curve: get_address_as_string(3, addresses, instruction),
+            associated_bonding_curve: get_address_as_string(4, addresses, instruction),
+            associated_user: get_address_as_string(5, addresses, instruction),
+            user: get_address_as_string(6, addresses, instruction),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_trade_accounts() {
+        let addresses: Vec<String> = (0..7).map(|index| format!("account{}", index)).collect();
+
+        let instruction = json!({ "accounts": [0, 1, 2, 3, 4, 5, 6] });
+        let accounts = PumpfunTradeAccounts::resolve(&instruction, &addresses).unwrap();
+        assert_eq!(accounts.mint, "account2");
+        assert_eq!(accounts.user, "account6");
+
+        // a failed trade can have a truncated accounts list
+        let instruction = json!({ "accounts": [0, 1, 2] });
+        assert!(PumpfunTradeAccounts::resolve(&instruction, &addresses).is_none());
+    }
+}
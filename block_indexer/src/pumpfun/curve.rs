@@ -0,0 +1,28 @@
+// values of the Pump.fun Global account (set with setParams), every token
+// starts with the same bonding curve
+
+pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000;
+pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000;
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+// discriminator of the BondingCurve account (as stored in pump_data)
+pub const BONDING_CURVE_ACCOUNT_DISCRIMINATOR: i64 = 6966180631402821399;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const PUMP_CURVE_TOKEN_UNITS: f64 = 1_000_000.0;
+
+/// Price of a token in SOL:
+/// (virtualSolReserves / LAMPORTS_PER_SOL) / (virtualTokenReserves / 10 ** PUMP_CURVE_TOKEN_DECIMALS)
+pub fn get_price(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> f64 {
+    if virtual_token_reserves == 0 {
+        return 0.0;
+    }
+    (virtual_sol_reserves as f64 / LAMPORTS_PER_SOL)
+        / (virtual_token_reserves as f64 / PUMP_CURVE_TOKEN_UNITS)
+}
+
+/// Marketcap in SOL of the whole supply at the given price.
+pub fn get_marketcap(price: f64, token_total_supply: u64) -> f64 {
+    price * (token_total_supply as f64 / PUMP_CURVE_TOKEN_UNITS)
+}
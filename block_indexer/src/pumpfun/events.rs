@@ -0,0 +1,232 @@
+// use as reference: js_src/src/parsing/protocol/pumpfun/pumpfun.decoder.ts
+// and the *-event.decoder.ts files next to it
+
+use num_bigint::BigUint;
+use std::sync::LazyLock;
+
+use crate::parsing::{deserialize_address, get_discriminator, PROGRAM_DATA_LOG_PREFIX};
+use crate::transaction_log::InstructionLogs;
+use base64::prelude::*;
+
+static PUMPFUN_CREATE_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:CreateEvent"));
+static PUMPFUN_TRADE_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:TradeEvent"));
+static PUMPFUN_COMPLETE_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:CompleteEvent"));
+static PUMPFUN_SET_PARAMS_EVENT_DISCRIMINATOR: LazyLock<BigUint> =
+    LazyLock::new(|| get_discriminator("event:SetParamsEvent"));
+
+// name: string
+// symbol: string
+// uri: string
+// mint: pubkey
+// bondingCurve: pubkey
+// user: pubkey
+#[derive(Debug, Clone)]
+pub struct PumpfunCreateEventValues {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: String,
+    pub bonding_curve: String,
+    pub user: String,
+}
+
+// mint: pubkey
+// solAmount: u64
+// tokenAmount: u64
+// isBuy: bool
+// user: pubkey
+// timestamp: i64
+// virtualSolReserves: u64
+// virtualTokenReserves: u64
+// realSolReserves: u64
+// realTokenReserves: u64
+#[derive(Debug, Clone)]
+pub struct PumpfunTradeEventValues {
+    pub mint: String,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: String,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+// user: pubkey
+// mint: pubkey
+// bondingCurve: pubkey
+// timestamp: i64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct PumpfunCompleteEventValues {
+    pub user: String,
+    pub mint: String,
+    pub bonding_curve: String,
+    pub timestamp: i64,
+}
+
+// feeRecipient: pubkey
+// initialVirtualTokenReserves: u64
+// initialVirtualSolReserves: u64
+// initialRealTokenReserves: u64
+// tokenTotalSupply: u64
+// feeBasisPoints: u64
+#[derive(Debug, Clone)]
+pub struct PumpfunSetParamsEventValues {
+    pub fee_recipient: String,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum PumpfunEvent {
+    Create(PumpfunCreateEventValues),
+    Trade(PumpfunTradeEventValues),
+    Complete(PumpfunCompleteEventValues),
+    SetParams(PumpfunSetParamsEventValues),
+}
+
+/// `event_data` starts with the event discriminator. Returns None for other
+/// events or malformed data. Newer program versions append fields to the
+/// events, the trailing bytes are ignored.
+pub fn decode_event(event_data: &[u8]) -> Option<PumpfunEvent> {
+    if event_data.len() < 8 {
+        return None;
+    }
+
+    let discriminator = BigUint::from_bytes_le(&event_data[0..8]);
+    let mut event_data_mut = &event_data[8..];
+
+    if discriminator == *PUMPFUN_TRADE_EVENT_DISCRIMINATOR {
+        // mint + 2 u64 + bool + user + i64 + 4 u64
+        if event_data_mut.len() < 32 + 16 + 1 + 32 + 8 + 32 {
+            return None;
+        }
+        let mint = deserialize_address(&mut event_data_mut);
+        let sol_amount = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let token_amount = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let is_buy = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let user = deserialize_address(&mut event_data_mut);
+        let timestamp = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let virtual_sol_reserves =
+            borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let virtual_token_reserves =
+            borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let real_sol_reserves = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let real_token_reserves = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+
+        Some(PumpfunEvent::Trade(PumpfunTradeEventValues {
+            mint,
+            sol_amount,
+            token_amount,
+            is_buy,
+            user,
+            timestamp,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+        }))
+    } else if discriminator == *PUMPFUN_CREATE_EVENT_DISCRIMINATOR {
+        let name = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let symbol = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let uri = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        if event_data_mut.len() < 32 * 3 {
+            return None;
+        }
+        let mint = deserialize_address(&mut event_data_mut);
+        let bonding_curve = deserialize_address(&mut event_data_mut);
+        let user = deserialize_address(&mut event_data_mut);
+
+        Some(PumpfunEvent::Create(PumpfunCreateEventValues {
+            name,
+            symbol,
+            uri,
+            mint,
+            bonding_curve,
+            user,
+        }))
+    } else if discriminator == *PUMPFUN_COMPLETE_EVENT_DISCRIMINATOR {
+        if event_data_mut.len() < 32 * 3 + 8 {
+            return None;
+        }
+        let user = deserialize_address(&mut event_data_mut);
+        let mint = deserialize_address(&mut event_data_mut);
+        let bonding_curve = deserialize_address(&mut event_data_mut);
+        let timestamp = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+
+        Some(PumpfunEvent::Complete(PumpfunCompleteEventValues {
+            user,
+            mint,
+            bonding_curve,
+            timestamp,
+        }))
+    } else if discriminator == *PUMPFUN_SET_PARAMS_EVENT_DISCRIMINATOR {
+        if event_data_mut.len() < 32 + 8 * 5 {
+            return None;
+        }
+        let fee_recipient = deserialize_address(&mut event_data_mut);
+        let initial_virtual_token_reserves =
+            borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let initial_virtual_sol_reserves =
+            borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let initial_real_token_reserves =
+            borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let token_total_supply = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+        let fee_basis_points = borsh::BorshDeserialize::deserialize(&mut event_data_mut).ok()?;
+
+        Some(PumpfunEvent::SetParams(PumpfunSetParamsEventValues {
+            fee_recipient,
+            initial_virtual_token_reserves,
+            initial_virtual_sol_reserves,
+            initial_real_token_reserves,
+            token_total_supply,
+            fee_basis_points,
+        }))
+    } else {
+        None
+    }
+}
+
+/// Every Pump.fun event logged by the instruction ("Program data: <base64>"), in order.
+pub fn find_events(instruction_logs: &InstructionLogs) -> Vec<PumpfunEvent> {
+    instruction_logs
+        .log_messages
+        .iter()
+        .filter_map(|log_message| log_message.strip_prefix(PROGRAM_DATA_LOG_PREFIX))
+        .filter_map(|encoded_data| BASE64_STANDARD.decode(encoded_data).ok())
+        .filter_map(|event_data| decode_event(&event_data))
+        .collect()
+}
+
+/// Whether the event data is also in a "Program data: <base64>" log of the
+/// transaction. The log messages are truncated for the large transactions.
+pub fn is_event_logged(
+    transaction_obj: &serde_json::Map<String, serde_json::Value>,
+    event_data: &[u8],
+) -> bool {
+    let Some(log_messages) = transaction_obj
+        .get("meta")
+        .and_then(|meta| meta.get("logMessages"))
+        .and_then(|log_messages| log_messages.as_array())
+    else {
+        return false;
+    };
+    let event_log = format!(
+        "{}{}",
+        PROGRAM_DATA_LOG_PREFIX,
+        BASE64_STANDARD.encode(event_data)
+    );
+
+    log_messages
+        .iter()
+        .any(|log_message| log_message.as_str() == Some(event_log.as_str()))
+}
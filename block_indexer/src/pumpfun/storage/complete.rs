@@ -0,0 +1,61 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/complete.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::pumpfun::PumpfunCompleteFunctionCallEventMeta;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+pub fn save_complete_events(
+    transaction: &mut Transaction,
+    complete_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    // a mint can't be twice in the same upsert
+    let mut completed_by_mint: HashMap<&str, i64> = HashMap::new();
+    for event in complete_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let complete_event = &function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<PumpfunCompleteFunctionCallEventMeta>()
+            .unwrap()
+            .event_log;
+        completed_by_mint.insert(&complete_event.mint, complete_event.timestamp);
+    }
+
+    if completed_by_mint.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO pump_data (
+            mint, complete, updated, completed
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            complete = EXCLUDED.complete,
+            updated = EXCLUDED.updated,
+            completed = EXCLUDED.completed;
+    ",
+        build_values_placeholders(completed_by_mint.len(), &["", "", "", ""])
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(completed_by_mint.len() * 4);
+    for (mint, completed) in completed_by_mint {
+        values.push(Box::new(mint.to_string()));
+        values.push(Box::new(true));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(completed));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating pump_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/mint.storage.ts
+
+use postgres::Transaction;
+
+use crate::pumpfun::curve::{
+    get_marketcap, get_price, BONDING_CURVE_ACCOUNT_DISCRIMINATOR, INITIAL_REAL_TOKEN_RESERVES,
+    INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES, TOKEN_TOTAL_SUPPLY,
+};
+use crate::pumpfun::PumpfunTokenMintFunctionCallEventMeta;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+fn clean_string(value: &str) -> String {
+    value.replace('\0', "")
+}
+
+pub fn save_mint_events(
+    transaction: &mut Transaction,
+    mint_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if mint_events.is_empty() {
+        return Ok(0);
+    }
+
+    // the curve reserves are the initial ones, a trade in the same block updates them later
+    let insert_query = format!(
+        "
+        INSERT INTO pump_data (
+            mint,
+            name,
+            symbol,
+            uri,
+            bonding_curve,
+            user_public_key,
+            discriminator,
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves,
+            token_total_supply,
+            price,
+            marketcap,
+            created,
+            create_event_slot
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            name = EXCLUDED.name,
+            symbol = EXCLUDED.symbol,
+            uri = EXCLUDED.uri,
+            bonding_curve = EXCLUDED.bonding_curve,
+            user_public_key = EXCLUDED.user_public_key,
+            created = EXCLUDED.created,
+            create_event_slot = EXCLUDED.create_event_slot;
+    ",
+        build_values_placeholders(
+            mint_events.len(),
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::float8",
+                "::float8",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let price = get_price(INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES);
+    let marketcap = get_marketcap(price, TOKEN_TOTAL_SUPPLY);
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(mint_events.len() * 16);
+    for event in mint_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let create_event = &function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<PumpfunTokenMintFunctionCallEventMeta>()
+            .unwrap()
+            .event_log;
+
+        values.push(Box::new(create_event.mint.clone()));
+        values.push(Box::new(clean_string(&create_event.name)));
+        values.push(Box::new(clean_string(&create_event.symbol)));
+        values.push(Box::new(clean_string(&create_event.uri)));
+        values.push(Box::new(create_event.bonding_curve.clone()));
+        values.push(Box::new(create_event.user.clone()));
+        values.push(Box::new(BONDING_CURVE_ACCOUNT_DISCRIMINATOR));
+        values.push(Box::new(INITIAL_VIRTUAL_TOKEN_RESERVES.to_string()));
+        values.push(Box::new(INITIAL_VIRTUAL_SOL_RESERVES.to_string()));
+        values.push(Box::new(INITIAL_REAL_TOKEN_RESERVES.to_string()));
+        values.push(Box::new(0.to_string()));
+        values.push(Box::new(TOKEN_TOTAL_SUPPLY.to_string()));
+        values.push(Box::new(price));
+        values.push(Box::new(marketcap));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(block_data.slot));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting pump_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use postgres::Transaction;
+
+use crate::pumpfun::PumpfunSetParamsFunctionCallEventMeta;
+use crate::storage::BlockData;
+use crate::Event;
+
+pub fn save_params_events(
+    transaction: &mut Transaction,
+    params_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let insert_query = "
+        INSERT INTO pump_params_history (
+            slot,
+            transaction_id,
+            fee_recipient,
+            initial_virtual_token_reserves,
+            initial_virtual_sol_reserves,
+            initial_real_token_reserves,
+            token_total_supply,
+            fee_basis_points,
+            timestamp
+        ) VALUES (
+            $1, $2, $3, $4::text::numeric, $5::text::numeric, $6::text::numeric,
+            $7::text::numeric, $8, $9
+        );
+    ";
+
+    let mut rows_count = 0;
+
+    for event in params_events {
+        let Event::FunctionCall(function_call_event) = event;
+
+        let event_meta = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<PumpfunSetParamsFunctionCallEventMeta>()
+            .unwrap();
+        let params = &event_meta.event_log;
+
+        let result = transaction.execute(
+            insert_query,
+            &[
+                &block_data.slot,
+                &function_call_event.signature,
+                &params.fee_recipient,
+                &params.initial_virtual_token_reserves.to_string(),
+                &params.initial_virtual_sol_reserves.to_string(),
+                &params.initial_real_token_reserves.to_string(),
+                &params.token_total_supply.to_string(),
+                &(params.fee_basis_points as i64),
+                &(event_meta.block_time as i32),
+            ],
+        );
+
+        match result {
+            Ok(count) => rows_count += count,
+            Err(err) => {
+                println!("Error inserting pump_params_history: {:?}", err);
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(rows_count)
+}
@@ -0,0 +1,363 @@
+// use as reference: js_src/src/storage/protocol/pumpfun/trade.storage.ts
+// and js_src/src/storage/protocol/pumpfun/trade-history.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::diamond_hands::storage::save_diamond_hands;
+use crate::early_trades::{save_early_trades, EarlyTrade};
+use crate::last_trades::{save_last_trades, LastTrade};
+use crate::mint_cache::MintCache;
+use crate::positions::storage::save_positions;
+use crate::pumpfun::curve::{
+    get_marketcap, get_price, BONDING_CURVE_ACCOUNT_DISCRIMINATOR, TOKEN_TOTAL_SUPPLY,
+};
+use crate::pumpfun::{PumpfunTradeEventValues, PumpfunTradeFunctionCallEventMeta};
+use crate::sol_price::{lamports_to_usd, sol_to_usd};
+use crate::storage::{
+    build_values_placeholders, execute_with_values, BlockData, SqlValue, StorageError,
+};
+use crate::Event;
+
+/// Per mint summary of the trades of a block, the reserves are the ones after
+/// the last trade.
+pub struct ConsolidatedPumpfunTrades<'a> {
+    pub mint: &'a str,
+    pub buy_count: i32,
+    pub sell_count: i32,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    pub volume: u64,
+    pub last_trade: &'a PumpfunTradeEventValues,
+}
+
+fn get_trade_meta(event: &Event) -> &PumpfunTradeFunctionCallEventMeta {
+    let Event::FunctionCall(function_call_event) = event;
+    function_call_event
+        .event_meta
+        .as_any()
+        .downcast_ref::<PumpfunTradeFunctionCallEventMeta>()
+        .unwrap()
+}
+
+/// Only the executed trades count (the ones with a TradeEvent).
+pub fn consolidate_trade_events<'a>(
+    trade_events: &[&'a Event],
+) -> Vec<ConsolidatedPumpfunTrades<'a>> {
+    let mut consolidated_trades: Vec<ConsolidatedPumpfunTrades> = Vec::new();
+    let mut index_by_mint: HashMap<&str, usize> = HashMap::new();
+
+    for event in trade_events {
+        let event_meta = get_trade_meta(event);
+        let Some(trade_event) = &event_meta.event_log else {
+            continue;
+        };
+
+        let index = *index_by_mint
+            .entry(trade_event.mint.as_str())
+            .or_insert_with(|| {
+                consolidated_trades.push(ConsolidatedPumpfunTrades {
+                    mint: &trade_event.mint,
+                    buy_count: 0,
+                    sell_count: 0,
+                    buy_volume: 0,
+                    sell_volume: 0,
+                    volume: 0,
+                    last_trade: trade_event,
+                });
+                consolidated_trades.len() - 1
+            });
+
+        let consolidated = &mut consolidated_trades[index];
+        if trade_event.is_buy {
+            consolidated.buy_count += 1;
+            consolidated.buy_volume += trade_event.sol_amount;
+        } else {
+            consolidated.sell_count += 1;
+            consolidated.sell_volume += trade_event.sol_amount;
+        }
+        consolidated.volume += trade_event.sol_amount;
+        consolidated.last_trade = trade_event;
+    }
+
+    consolidated_trades
+}
+
+// this includes failed transactions
+fn save_trade_history(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if trade_events.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO pump_trade (
+            signer,
+            mint,
+            transaction_id,
+            sol_amount,
+            token_amount,
+            is_buy,
+            timestamp,
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves,
+            created,
+            failed_transaction,
+            priority_fee,
+            compute_unit_limit,
+            compute_units_consumed,
+            base_fee,
+            jito_tip
+        ) VALUES {};
+    ",
+        build_values_placeholders(
+            trade_events.len(),
+            &[
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(trade_events.len() * 18);
+    for event in trade_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let event_meta = get_trade_meta(event);
+        let event_log = event_meta.event_log.as_ref();
+
+        values.push(Box::new(event_meta.sender.clone()));
+        values.push(Box::new(event_meta.mint.clone()));
+        values.push(Box::new(function_call_event.signature.clone()));
+        values.push(Box::new(event_log.map(|log| log.sol_amount.to_string())));
+        values.push(Box::new(event_log.map(|log| log.token_amount.to_string())));
+        values.push(Box::new(event_meta.is_buy));
+        values.push(Box::new(event_log.map(|log| log.timestamp as i32)));
+        values.push(Box::new(
+            event_log.map(|log| log.virtual_token_reserves.to_string()),
+        ));
+        values.push(Box::new(
+            event_log.map(|log| log.virtual_sol_reserves.to_string()),
+        ));
+        values.push(Box::new(
+            event_log.map(|log| log.real_token_reserves.to_string()),
+        ));
+        values.push(Box::new(
+            event_log.map(|log| log.real_sol_reserves.to_string()),
+        ));
+        values.push(Box::new(block_data.block_time as i32));
+        values.push(Box::new(event_meta.failed_transaction));
+        values.push(Box::new(function_call_event.fees.priority_fee as i64));
+        values.push(Box::new(function_call_event.fees.compute_unit_limit as i32));
+        values.push(Box::new(
+            function_call_event.fees.compute_units_consumed as i32,
+        ));
+        values.push(Box::new(function_call_event.fees.base_fee as i64));
+        values.push(Box::new(function_call_event.fees.jito_tip as i64));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting pump_trade: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn save_prices_and_bonding_curves(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let consolidated_trades = consolidate_trade_events(trade_events);
+    if consolidated_trades.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO pump_data (
+            mint,
+            discriminator,
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            real_token_reserves,
+            real_sol_reserves,
+            token_total_supply,
+            price,
+            marketcap,
+            buy_count,
+            sell_count,
+            buy_volume,
+            sell_volume,
+            volume,
+            updated,
+            marketcap_usd,
+            volume_usd
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            discriminator = EXCLUDED.discriminator,
+            virtual_token_reserves = EXCLUDED.virtual_token_reserves,
+            virtual_sol_reserves = EXCLUDED.virtual_sol_reserves,
+            real_token_reserves = EXCLUDED.real_token_reserves,
+            real_sol_reserves = EXCLUDED.real_sol_reserves,
+            token_total_supply = EXCLUDED.token_total_supply,
+            price = EXCLUDED.price,
+            marketcap = EXCLUDED.marketcap,
+            buy_count = EXCLUDED.buy_count + COALESCE(pump_data.buy_count, 0),
+            sell_count = EXCLUDED.sell_count + COALESCE(pump_data.sell_count, 0),
+            buy_volume = EXCLUDED.buy_volume + COALESCE(pump_data.buy_volume, 0),
+            sell_volume = EXCLUDED.sell_volume + COALESCE(pump_data.sell_volume, 0),
+            volume = EXCLUDED.volume + COALESCE(pump_data.volume, 0),
+            updated = EXCLUDED.updated,
+            marketcap_usd = EXCLUDED.marketcap_usd,
+            volume_usd = COALESCE(EXCLUDED.volume_usd, 0) + COALESCE(pump_data.volume_usd, 0);
+    ",
+        build_values_placeholders(
+            consolidated_trades.len(),
+            &[
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::float8",
+                "::float8",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "::float8",
+                "::float8",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(consolidated_trades.len() * 17);
+    for consolidated in &consolidated_trades {
+        let last_trade = consolidated.last_trade;
+        let price = get_price(
+            last_trade.virtual_sol_reserves,
+            last_trade.virtual_token_reserves,
+        );
+
+        values.push(Box::new(consolidated.mint.to_string()));
+        values.push(Box::new(BONDING_CURVE_ACCOUNT_DISCRIMINATOR));
+        values.push(Box::new(last_trade.virtual_token_reserves.to_string()));
+        values.push(Box::new(last_trade.virtual_sol_reserves.to_string()));
+        values.push(Box::new(last_trade.real_token_reserves.to_string()));
+        values.push(Box::new(last_trade.real_sol_reserves.to_string()));
+        values.push(Box::new(TOKEN_TOTAL_SUPPLY.to_string()));
+        values.push(Box::new(price));
+        let marketcap = get_marketcap(price, TOKEN_TOTAL_SUPPLY);
+        values.push(Box::new(marketcap));
+        values.push(Box::new(consolidated.buy_count));
+        values.push(Box::new(consolidated.sell_count));
+        values.push(Box::new(consolidated.buy_volume.to_string()));
+        values.push(Box::new(consolidated.sell_volume.to_string()));
+        values.push(Box::new(consolidated.volume.to_string()));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(sol_to_usd(marketcap, block_data.sol_price)));
+        values.push(Box::new(lamports_to_usd(
+            consolidated.volume,
+            block_data.sol_price,
+        )));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting pump_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn build_last_trades(trade_events: &[&Event], block_data: &BlockData) -> Vec<LastTrade> {
+    trade_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let trade_event = get_trade_meta(event).event_log.as_ref()?;
+            Some(LastTrade {
+                mint: trade_event.mint.clone(),
+                slot: block_data.slot,
+                transaction_index: function_call_event.transaction_index,
+                instruction_index: function_call_event.instruction_index,
+                timestamp: trade_event.timestamp,
+                is_buy: trade_event.is_buy,
+                token_amount: trade_event.token_amount,
+                sol_amount: trade_event.sol_amount,
+                sender: trade_event.user.clone(),
+            })
+        })
+        .collect()
+}
+
+fn build_early_trades(trade_events: &[&Event]) -> Vec<EarlyTrade> {
+    trade_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let trade_event = get_trade_meta(event).event_log.as_ref()?;
+            Some(EarlyTrade {
+                mint: trade_event.mint.clone(),
+                wallet: trade_event.user.clone(),
+                transaction_id: function_call_event.signature.clone(),
+                token_amount: trade_event.token_amount,
+                is_buy: trade_event.is_buy,
+            })
+        })
+        .collect()
+}
+
+pub fn save_trade_events(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<u64, StorageError> {
+    let mut rows_count = 0;
+
+    rows_count += save_prices_and_bonding_curves(transaction, trade_events, block_data)?;
+    rows_count += save_trade_history(transaction, trade_events, block_data)?;
+    let last_trades = build_last_trades(trade_events, block_data);
+    rows_count += save_last_trades(transaction, &last_trades, block_data)?;
+    rows_count += save_diamond_hands(transaction, &last_trades, block_data)?;
+    rows_count += save_positions(transaction, &last_trades, block_data)?;
+    rows_count += save_early_trades(
+        transaction,
+        &build_early_trades(trade_events),
+        block_data,
+        mint_cache,
+    )?;
+
+    Ok(rows_count)
+}
@@ -0,0 +1,429 @@
+// use as reference: js_src/src/parsing/protocol/raydium/raydium.parser.ts
+// and js_src/src/parsing/protocol/raydium/raydium.decoder.ts
+
+use serde_json::Value;
+use solana_sdk::bs58;
+use std::any::Any;
+
+use crate::compute_budget::TransactionFees;
+use crate::parsing::is_failed_transaction;
+use crate::transaction_log::InstructionLogs;
+use crate::{
+    Event, FunctionCallEvent, FunctionCallEventMeta, FunctionCallInstructionData, GenericEventType,
+    IndexerEventSource,
+};
+
+mod accounts;
+mod amm_info;
+pub mod pool_registry;
+mod ray_log;
+pub mod storage;
+use accounts::{resolve_liquidity_amm, DEPOSIT_ACCOUNTS_COUNT, WITHDRAW_ACCOUNTS_COUNT};
+pub use accounts::{RaydiumInitialize2Accounts, RaydiumSwapAccounts};
+use ray_log::find_ray_logs;
+pub use ray_log::{InitLog, RayLog, RaydiumSwapValues, SwapDirection};
+
+pub const RAYDIUM_POOL_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+pub struct RaydiumParser;
+
+pub struct RaydiumTradeFunctionCallEventMeta {
+    pub block_time: u64,
+    pub accounts: RaydiumSwapAccounts,
+    pub failed_transaction: bool,
+    // None for failed transactions and truncated logs
+    pub ray_log: Option<RayLog>,
+    pub swap: Option<RaydiumSwapValues>,
+}
+
+impl FunctionCallEventMeta for RaydiumTradeFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// pool creation
+#[allow(dead_code)]
+pub struct RaydiumInitializeFunctionCallEventMeta {
+    pub block_time: u64,
+    pub accounts: RaydiumInitialize2Accounts,
+    pub failed_transaction: bool,
+    pub init_log: Option<InitLog>,
+}
+
+impl FunctionCallEventMeta for RaydiumInitializeFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// deposits and withdraws
+#[allow(dead_code)]
+pub struct RaydiumLiquidityFunctionCallEventMeta {
+    pub block_time: u64,
+    pub amm: String,
+    pub failed_transaction: bool,
+    pub ray_log: Option<RayLog>,
+}
+
+impl FunctionCallEventMeta for RaydiumLiquidityFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl RaydiumParser {
+    pub fn new() -> Self {
+        RaydiumParser
+    }
+
+    /// Every instruction that changes the pool writes a ray log with its amounts,
+    /// the first one in the logs of the instruction belongs to it.
+    pub fn parse_instruction(
+        &self,
+        transaction_obj: &serde_json::Map<String, serde_json::Value>,
+        instruction: &Value,
+        addresses: &[String],
+        block_time: u64,
+        instruction_logs: Option<&InstructionLogs>,
+    ) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+
+        let program_id_index = instruction.get("programIdIndex").unwrap().as_u64().unwrap();
+        let program_id = addresses.get(program_id_index as usize).unwrap();
+
+        if program_id != RAYDIUM_POOL_V4_PROGRAM_ID {
+            return events;
+        }
+
+        let instruction_data_str = instruction.get("data").unwrap().as_str().unwrap();
+        let instruction_data_bytes = bs58::decode(instruction_data_str).into_vec().unwrap();
+
+        let decoded_instruction = decode_instruction_data(&instruction_data_bytes);
+        let signature = transaction_obj
+            .get("transaction")
+            .unwrap()
+            .get("signatures")
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string();
+        let failed_transaction = is_failed_transaction(transaction_obj);
+
+        // failed transactions don't have useful ray logs
+        let ray_log = if failed_transaction {
+            None
+        } else {
+            instruction_logs
+                .filter(|instruction_logs| instruction_logs.address == RAYDIUM_POOL_V4_PROGRAM_ID)
+                .and_then(|instruction_logs| {
+                    find_ray_logs(&instruction_logs.log_messages)
+                        .into_iter()
+                        .next()
+                })
+        };
+
+        let (event_type, event_meta): (GenericEventType, Box<dyn FunctionCallEventMeta>) =
+            match &decoded_instruction {
+                RaydiumInstructionData::Initialize2(_) => {
+                    if failed_transaction {
+                        return events;
+                    }
+                    let Some(accounts) =
+                        RaydiumInitialize2Accounts::resolve(instruction, addresses)
+                    else {
+                        return events;
+                    };
+                    let init_log = match ray_log {
+                        Some(RayLog::Init(init_log)) => Some(init_log),
+                        _ => None,
+                    };
+                    (
+                        GenericEventType::TokenMint,
+                        Box::new(RaydiumInitializeFunctionCallEventMeta {
+                            block_time,
+                            accounts,
+                            failed_transaction,
+                            init_log,
+                        }),
+                    )
+                }
+                RaydiumInstructionData::SwapBaseIn(_) | RaydiumInstructionData::SwapBaseOut(_) => {
+                    let Some(accounts) = RaydiumSwapAccounts::resolve(instruction, addresses)
+                    else {
+                        return events;
+                    };
+                    (
+                        GenericEventType::Trade,
+                        Box::new(RaydiumTradeFunctionCallEventMeta {
+                            block_time,
+                            accounts,
+                            failed_transaction,
+                            swap: ray_log.as_ref().and_then(|ray_log| ray_log.swap_values()),
+                            ray_log,
+                        }),
+                    )
+                }
+                RaydiumInstructionData::Deposit | RaydiumInstructionData::Withdraw => {
+                    if failed_transaction {
+                        return events;
+                    }
+                    let layout_accounts_count =
+                        if matches!(decoded_instruction, RaydiumInstructionData::Deposit) {
+                            DEPOSIT_ACCOUNTS_COUNT
+                        } else {
+                            WITHDRAW_ACCOUNTS_COUNT
+                        };
+                    let Some(amm) =
+                        resolve_liquidity_amm(instruction, addresses, layout_accounts_count)
+                    else {
+                        return events;
+                    };
+                    (
+                        GenericEventType::Info,
+                        Box::new(RaydiumLiquidityFunctionCallEventMeta {
+                            block_time,
+                            amm,
+                            failed_transaction,
+                            ray_log,
+                        }),
+                    )
+                }
+                _ => return events,
+            };
+
+        events.push(Event::FunctionCall(FunctionCallEvent {
+            source: IndexerEventSource::Raydium,
+            event_type,
+            slot: 0,
+            transaction_index: 0,
+            instruction_index: 0,
+            fees: TransactionFees::default(),
+            signature,
+            event_obj: Box::new(decoded_instruction),
+            event_meta,
+        }));
+
+        events
+    }
+}
+
+enum RaydiumPoolV4Discriminator {
+    Initialize = 0,
+    Initialize2 = 1,
+    MonitorStep = 2,
+    Deposit = 3,
+    Withdraw = 4,
+    MigrateToOpenBook = 5,
+    SetParams = 6,
+    WithdrawPnl = 7,
+    WithdrawSrm = 8,
+    SwapBaseIn = 9,
+    PreInitialize = 10,
+    SwapBaseOut = 11,
+    SimulateInfo = 12,
+    AdminCancelOrders = 13,
+    CreateConfigAccount = 14,
+    UpdateConfigAccount = 15,
+    Unknown,
+}
+
+impl RaydiumPoolV4Discriminator {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => RaydiumPoolV4Discriminator::Initialize,
+            1 => RaydiumPoolV4Discriminator::Initialize2,
+            2 => RaydiumPoolV4Discriminator::MonitorStep,
+            3 => RaydiumPoolV4Discriminator::Deposit,
+            4 => RaydiumPoolV4Discriminator::Withdraw,
+            5 => RaydiumPoolV4Discriminator::MigrateToOpenBook,
+            6 => RaydiumPoolV4Discriminator::SetParams,
+            7 => RaydiumPoolV4Discriminator::WithdrawPnl,
+            8 => RaydiumPoolV4Discriminator::WithdrawSrm,
+            9 => RaydiumPoolV4Discriminator::SwapBaseIn,
+            10 => RaydiumPoolV4Discriminator::PreInitialize,
+            11 => RaydiumPoolV4Discriminator::SwapBaseOut,
+            12 => RaydiumPoolV4Discriminator::SimulateInfo,
+            13 => RaydiumPoolV4Discriminator::AdminCancelOrders,
+            14 => RaydiumPoolV4Discriminator::CreateConfigAccount,
+            15 => RaydiumPoolV4Discriminator::UpdateConfigAccount,
+            _ => RaydiumPoolV4Discriminator::Unknown,
+        }
+    }
+}
+
+// discriminator: u8
+// nonce: u8
+// openTime: u64
+// initPcAmount: u64
+// initCoinAmount: u64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RaydiumInitialize2Values {
+    pub nonce: u8,
+    pub open_time: u64,
+    pub init_pc_amount: u64,
+    pub init_coin_amount: u64,
+}
+
+// discriminator: u8
+// amountIn: u64
+// minimumAmountOut: u64
+#[derive(Debug, Clone)]
+pub struct RaydiumSwapBaseInValues {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+// discriminator: u8
+// maxAmountIn: u64
+// amountOut: u64
+#[derive(Debug, Clone)]
+pub struct RaydiumSwapBaseOutValues {
+    pub max_amount_in: u64,
+    pub amount_out: u64,
+}
+
+// only the instructions that are indexed are decoded, the rest are kept as Other
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum RaydiumInstructionData {
+    Initialize2(Option<RaydiumInitialize2Values>),
+    Deposit,
+    Withdraw,
+    SwapBaseIn(Option<RaydiumSwapBaseInValues>),
+    SwapBaseOut(Option<RaydiumSwapBaseOutValues>),
+    Other(u8),
+    Unknown,
+}
+
+impl FunctionCallInstructionData for RaydiumInstructionData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn decode_instruction_data(instruction_data: &[u8]) -> RaydiumInstructionData {
+    let Some(discriminator) = instruction_data.first() else {
+        return RaydiumInstructionData::Unknown;
+    };
+
+    match RaydiumPoolV4Discriminator::from_u8(*discriminator) {
+        RaydiumPoolV4Discriminator::Initialize2 => {
+            RaydiumInstructionData::Initialize2(decode_initialize2(instruction_data))
+        }
+        RaydiumPoolV4Discriminator::Deposit => RaydiumInstructionData::Deposit,
+        RaydiumPoolV4Discriminator::Withdraw => RaydiumInstructionData::Withdraw,
+        RaydiumPoolV4Discriminator::SwapBaseIn => {
+            RaydiumInstructionData::SwapBaseIn(decode_swap_base_in(instruction_data))
+        }
+        RaydiumPoolV4Discriminator::SwapBaseOut => {
+            RaydiumInstructionData::SwapBaseOut(decode_swap_base_out(instruction_data))
+        }
+        RaydiumPoolV4Discriminator::Unknown => RaydiumInstructionData::Unknown,
+        _ => RaydiumInstructionData::Other(*discriminator),
+    }
+}
+
+fn decode_initialize2(instruction_data: &[u8]) -> Option<RaydiumInitialize2Values> {
+    let mut instruction_data_mut = &instruction_data[1..];
+    let nonce = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let open_time = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let init_pc_amount = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let init_coin_amount = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(RaydiumInitialize2Values {
+        nonce,
+        open_time,
+        init_pc_amount,
+        init_coin_amount,
+    })
+}
+
+fn decode_swap_base_in(instruction_data: &[u8]) -> Option<RaydiumSwapBaseInValues> {
+    let mut instruction_data_mut = &instruction_data[1..];
+    let amount_in = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let minimum_amount_out =
+        borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(RaydiumSwapBaseInValues {
+        amount_in,
+        minimum_amount_out,
+    })
+}
+
+fn decode_swap_base_out(instruction_data: &[u8]) -> Option<RaydiumSwapBaseOutValues> {
+    let mut instruction_data_mut = &instruction_data[1..];
+    let max_amount_in = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+    let amount_out = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok()?;
+
+    Some(RaydiumSwapBaseOutValues {
+        max_amount_in,
+        amount_out,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_swap_accounts_layouts() {
+        let addresses: Vec<String> = (0..18).map(|index| format!("account{}", index)).collect();
+
+        let instruction_18 = json!({ "accounts": (0..18).collect::<Vec<u64>>() });
+        let accounts = RaydiumSwapAccounts::resolve(&instruction_18, &addresses).unwrap();
+        assert_eq!(accounts.amm, "account1");
+        assert_eq!(accounts.amm_coin_vault, "account5");
+        assert_eq!(accounts.amm_pc_vault, "account6");
+        assert_eq!(accounts.user_wallet, "account17");
+
+        let instruction_17 = json!({ "accounts": (0..17).collect::<Vec<u64>>() });
+        let accounts = RaydiumSwapAccounts::resolve(&instruction_17, &addresses).unwrap();
+        assert_eq!(accounts.amm_coin_vault, "account4");
+        assert_eq!(accounts.user_source_token_account, "account14");
+        assert_eq!(accounts.user_wallet, "account16");
+
+        let instruction_16 = json!({ "accounts": (0..16).collect::<Vec<u64>>() });
+        assert!(RaydiumSwapAccounts::resolve(&instruction_16, &addresses).is_none());
+    }
+
+    #[test]
+    fn test_liquidity_amm_layouts() {
+        let addresses: Vec<String> = (0..20).map(|index| format!("account{}", index)).collect();
+
+        let deposit = json!({ "accounts": (0..14).collect::<Vec<u64>>() });
+        assert_eq!(
+            resolve_liquidity_amm(&deposit, &addresses, DEPOSIT_ACCOUNTS_COUNT),
+            Some("account1".to_string())
+        );
+        // the deposit layout is too short for a withdraw
+        assert_eq!(
+            resolve_liquidity_amm(&deposit, &addresses, WITHDRAW_ACCOUNTS_COUNT),
+            None
+        );
+        let truncated = json!({ "accounts": [0, 1] });
+        assert_eq!(
+            resolve_liquidity_amm(&truncated, &addresses, DEPOSIT_ACCOUNTS_COUNT),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_swap_base_in() {
+        let mut instruction_data = vec![9u8];
+        instruction_data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        instruction_data.extend_from_slice(&990u64.to_le_bytes());
+
+        let RaydiumInstructionData::SwapBaseIn(Some(values)) =
+            decode_instruction_data(&instruction_data)
+        else {
+            panic!("expected SwapBaseIn values");
+        };
+        assert_eq!(values.amount_in, 1_000_000);
+        assert_eq!(values.minimum_amount_out, 990);
+    }
+}
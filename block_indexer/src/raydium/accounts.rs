@@ -0,0 +1,107 @@
+// Account layouts of the Raydium pool v4 instructions.
+// The swaps are sent with 18 accounts or, without the amm target orders, with 17.
+
+use serde_json::Value;
+
+use crate::parsing::{get_accounts_count, get_address_as_string};
+
+// tokenProgram, splAssociatedTokenAccount, systemProgram, rent, amm, ammAuthority,
+// ammOpenOrders, lpMint, coinMint, pcMint, poolCoinTokenAccount, poolPcTokenAccount,
+// ammTargetOrders, ammConfig, createFeeDestination, marketProgram, market, userWallet, ...
+// (raydium.parser.ts still names 12-14 after the old withdraw queue layout)
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RaydiumInitialize2Accounts {
+    pub amm: String,
+    pub amm_open_orders: String,
+    pub lp_mint: String,
+    pub coin_mint: String,
+    pub pc_mint: String,
+    pub pool_coin_token_account: String,
+    pub pool_pc_token_account: String,
+    pub amm_target_orders: String,
+    pub market: String,
+    pub user_wallet: String,
+}
+
+impl RaydiumInitialize2Accounts {
+    // None when the instruction has fewer accounts than the layout
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        if get_accounts_count(instruction) < 18 {
+            return None;
+        }
+
+        Some(RaydiumInitialize2Accounts {
+            amm: get_address_as_string(4, addresses, instruction),
+            amm_open_orders: get_address_as_string(6, addresses, instruction),
+            lp_mint: get_address_as_string(7, addresses, instruction),
+            coin_mint: get_address_as_string(8, addresses, instruction),
+            pc_mint: get_address_as_string(9, addresses, instruction),
+            pool_coin_token_account: get_address_as_string(10, addresses, instruction),
+            pool_pc_token_account: get_address_as_string(11, addresses, instruction),
+            amm_target_orders: get_address_as_string(12, addresses, instruction),
+            market: get_address_as_string(16, addresses, instruction),
+            user_wallet: get_address_as_string(17, addresses, instruction),
+        })
+    }
+}
+
+// SwapBaseIn and SwapBaseOut share the layout
+//
+// 18 accounts: tokenProgram, amm, ammAuthority, ammOpenOrders, ammTargetOrders,
+// ammCoinVault, ammPcVault, marketProgram, market, marketBids, marketAsks,
+// marketEventQueue, marketCoinVault, marketPcVault, marketVaultSigner,
+// userSourceTokenAccount, userDestinationTokenAccount, userWallet
+//
+// 17 accounts: the same without ammTargetOrders
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RaydiumSwapAccounts {
+    pub amm: String,
+    pub amm_coin_vault: String,
+    pub amm_pc_vault: String,
+    pub user_source_token_account: String,
+    pub user_destination_token_account: String,
+    pub user_wallet: String,
+}
+
+impl RaydiumSwapAccounts {
+    // None for layouts that are not known
+    pub fn resolve(instruction: &Value, addresses: &[String]) -> Option<Self> {
+        let offset = match get_accounts_count(instruction) {
+            18 => 1,
+            17 => 0,
+            _ => return None,
+        };
+
+        Some(RaydiumSwapAccounts {
+            amm: get_address_as_string(1, addresses, instruction),
+            amm_coin_vault: get_address_as_string(4 + offset, addresses, instruction),
+            amm_pc_vault: get_address_as_string(5 + offset, addresses, instruction),
+            user_source_token_account: get_address_as_string(14 + offset, addresses, instruction),
+            user_destination_token_account: get_address_as_string(
+                15 + offset,
+                addresses,
+                instruction,
+            ),
+            user_wallet: get_address_as_string(16 + offset, addresses, instruction),
+        })
+    }
+}
+
+// Deposit (14 accounts) and Withdraw (20 accounts, 22 with the old withdraw
+// queue layout): tokenProgram, amm, ...
+pub const DEPOSIT_ACCOUNTS_COUNT: usize = 14;
+pub const WITHDRAW_ACCOUNTS_COUNT: usize = 20;
+
+// None when the instruction has fewer accounts than the layout
+pub fn resolve_liquidity_amm(
+    instruction: &Value,
+    addresses: &[String],
+    layout_accounts_count: usize,
+) -> Option<String> {
+    if get_accounts_count(instruction) < layout_accounts_count {
+        return None;
+    }
+    Some(get_address_as_string(1, addresses, instruction))
+}
@@ -0,0 +1,101 @@
+// AmmInfo account of the Raydium pool v4 (752 bytes, LIQUIDITY_STATE_LAYOUT_V4 in the sdk).
+// Only the fields needed to identify the pool are decoded.
+
+use crate::parsing::deserialize_address;
+
+pub const AMM_INFO_ACCOUNT_SIZE: usize = 752;
+
+// 32 u64 (status .. orderbookToInitTime), then the swap counters:
+// swapBaseInAmount u128, swapQuoteOutAmount u128, swapBase2QuoteFee u64,
+// swapQuoteInAmount u128, swapBaseOutAmount u128, swapQuote2BaseFee u64
+const STATUS_OFFSET: usize = 0;
+const COIN_DECIMALS_OFFSET: usize = 4 * 8;
+const PC_DECIMALS_OFFSET: usize = 5 * 8;
+const POOL_OPEN_TIME_OFFSET: usize = 28 * 8;
+const COIN_VAULT_OFFSET: usize = 336;
+const PC_VAULT_OFFSET: usize = COIN_VAULT_OFFSET + 32;
+const COIN_MINT_OFFSET: usize = COIN_VAULT_OFFSET + 2 * 32;
+const PC_MINT_OFFSET: usize = COIN_VAULT_OFFSET + 3 * 32;
+const LP_MINT_OFFSET: usize = COIN_VAULT_OFFSET + 4 * 32;
+const OPEN_ORDERS_OFFSET: usize = COIN_VAULT_OFFSET + 5 * 32;
+const MARKET_OFFSET: usize = COIN_VAULT_OFFSET + 6 * 32;
+const TARGET_ORDERS_OFFSET: usize = COIN_VAULT_OFFSET + 8 * 32;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct AmmInfo {
+    pub status: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub pool_open_time: u64,
+    pub coin_vault: String,
+    pub pc_vault: String,
+    pub coin_mint: String,
+    pub pc_mint: String,
+    pub lp_mint: String,
+    pub open_orders: String,
+    pub market: String,
+    pub target_orders: String,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_address(data: &[u8], offset: usize) -> String {
+    deserialize_address(&mut &data[offset..offset + 32])
+}
+
+// None when the data is not an AmmInfo account
+pub fn decode_amm_info(account_data: &[u8]) -> Option<AmmInfo> {
+    if account_data.len() != AMM_INFO_ACCOUNT_SIZE {
+        return None;
+    }
+
+    Some(AmmInfo {
+        status: read_u64(account_data, STATUS_OFFSET),
+        coin_decimals: read_u64(account_data, COIN_DECIMALS_OFFSET),
+        pc_decimals: read_u64(account_data, PC_DECIMALS_OFFSET),
+        pool_open_time: read_u64(account_data, POOL_OPEN_TIME_OFFSET),
+        coin_vault: read_address(account_data, COIN_VAULT_OFFSET),
+        pc_vault: read_address(account_data, PC_VAULT_OFFSET),
+        coin_mint: read_address(account_data, COIN_MINT_OFFSET),
+        pc_mint: read_address(account_data, PC_MINT_OFFSET),
+        lp_mint: read_address(account_data, LP_MINT_OFFSET),
+        open_orders: read_address(account_data, OPEN_ORDERS_OFFSET),
+        market: read_address(account_data, MARKET_OFFSET),
+        target_orders: read_address(account_data, TARGET_ORDERS_OFFSET),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::bs58;
+
+    #[test]
+    fn test_decode_amm_info() {
+        let mut account_data = vec![0u8; AMM_INFO_ACCOUNT_SIZE];
+        account_data[STATUS_OFFSET..STATUS_OFFSET + 8].copy_from_slice(&6u64.to_le_bytes());
+        account_data[COIN_DECIMALS_OFFSET..COIN_DECIMALS_OFFSET + 8]
+            .copy_from_slice(&6u64.to_le_bytes());
+        account_data[PC_DECIMALS_OFFSET..PC_DECIMALS_OFFSET + 8]
+            .copy_from_slice(&9u64.to_le_bytes());
+        account_data[COIN_MINT_OFFSET..COIN_MINT_OFFSET + 32].copy_from_slice(&[3u8; 32]);
+        account_data[PC_MINT_OFFSET..PC_MINT_OFFSET + 32].copy_from_slice(&[4u8; 32]);
+        account_data[TARGET_ORDERS_OFFSET..TARGET_ORDERS_OFFSET + 32].copy_from_slice(&[5u8; 32]);
+
+        let amm_info = decode_amm_info(&account_data).unwrap();
+        assert_eq!(amm_info.status, 6);
+        assert_eq!(amm_info.coin_decimals, 6);
+        assert_eq!(amm_info.pc_decimals, 9);
+        assert_eq!(amm_info.coin_mint, bs58::encode([3u8; 32]).into_string());
+        assert_eq!(amm_info.pc_mint, bs58::encode([4u8; 32]).into_string());
+        assert_eq!(
+            amm_info.target_orders,
+            bs58::encode([5u8; 32]).into_string()
+        );
+
+        assert!(decode_amm_info(&account_data[..700]).is_none());
+    }
+}
@@ -0,0 +1,286 @@
+// Pools of the Raydium pool v4 program by amm id. The swaps only reference the
+// amm and its vaults, the mints come from the Initialize2 of the pool (stored in
+// raydium_pool) or from a snapshot of its AmmInfo account.
+
+use postgres::{GenericClient, Row};
+use std::collections::HashMap;
+
+use super::amm_info::decode_amm_info;
+use super::{RaydiumSwapValues, SwapDirection};
+use crate::parsing::WSOL_MINT;
+
+#[derive(Debug, Clone)]
+pub struct RaydiumPool {
+    pub amm: String,
+    pub coin_mint: String,
+    pub pc_mint: String,
+    pub coin_vault: String,
+    pub pc_vault: String,
+    pub coin_decimals: Option<i32>,
+    pub pc_decimals: Option<i32>,
+}
+
+/// A swap seen from the token that is traded: buys receive the base mint and
+/// pay the quote mint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaydiumSwapTag {
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub is_buy: bool,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+impl RaydiumPool {
+    // SOL is the quote when it is one of the sides, otherwise pc (as Raydium names it)
+    pub fn base_is_coin(&self) -> bool {
+        self.coin_mint != WSOL_MINT
+    }
+
+    pub fn base_mint(&self) -> &str {
+        if self.base_is_coin() {
+            &self.coin_mint
+        } else {
+            &self.pc_mint
+        }
+    }
+
+    pub fn quote_mint(&self) -> &str {
+        if self.base_is_coin() {
+            &self.pc_mint
+        } else {
+            &self.coin_mint
+        }
+    }
+
+    pub fn base_decimals(&self) -> Option<i32> {
+        if self.base_is_coin() {
+            self.coin_decimals
+        } else {
+            self.pc_decimals
+        }
+    }
+
+    pub fn tag_swap(&self, swap: &RaydiumSwapValues) -> RaydiumSwapTag {
+        let receives_coin = swap.direction == SwapDirection::PcToCoin;
+        let is_buy = receives_coin == self.base_is_coin();
+        let (base_amount, quote_amount) = if is_buy {
+            (swap.amount_out, swap.amount_in)
+        } else {
+            (swap.amount_in, swap.amount_out)
+        };
+
+        RaydiumSwapTag {
+            base_mint: self.base_mint().to_string(),
+            quote_mint: self.quote_mint().to_string(),
+            is_buy,
+            base_amount,
+            quote_amount,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PoolRegistry {
+    pools: HashMap<String, RaydiumPool>,
+    // pools replaced since the last commit, restored if the block is rolled back
+    block_changes: Vec<(String, Option<RaydiumPool>)>,
+}
+
+const SELECT_POOLS_QUERY: &str = "
+    SELECT amm, coin_mint, pc_mint, pool_coin_token_account, pool_pc_token_account,
+        coin_decimals, pc_decimals, amm_info
+    FROM raydium_pool
+";
+
+// rows without the Initialize2 data fall back to the AmmInfo snapshot
+fn pool_from_row(row: &Row) -> Option<RaydiumPool> {
+    let amm: String = row.get("amm");
+    let coin_mint: Option<String> = row.get("coin_mint");
+    let pc_mint: Option<String> = row.get("pc_mint");
+    let coin_vault: Option<String> = row.get("pool_coin_token_account");
+    let pc_vault: Option<String> = row.get("pool_pc_token_account");
+
+    if let (Some(coin_mint), Some(pc_mint), Some(coin_vault), Some(pc_vault)) =
+        (coin_mint, pc_mint, coin_vault, pc_vault)
+    {
+        return Some(RaydiumPool {
+            amm,
+            coin_mint,
+            pc_mint,
+            coin_vault,
+            pc_vault,
+            coin_decimals: row.get("coin_decimals"),
+            pc_decimals: row.get("pc_decimals"),
+        });
+    }
+
+    let amm_info_data: Option<Vec<u8>> = row.get("amm_info");
+    let amm_info = decode_amm_info(&amm_info_data?)?;
+
+    Some(RaydiumPool {
+        amm,
+        coin_mint: amm_info.coin_mint,
+        pc_mint: amm_info.pc_mint,
+        coin_vault: amm_info.coin_vault,
+        pc_vault: amm_info.pc_vault,
+        coin_decimals: Some(amm_info.coin_decimals as i32),
+        pc_decimals: Some(amm_info.pc_decimals as i32),
+    })
+}
+
+impl PoolRegistry {
+    /// Warms the cache with every known pool.
+    pub fn load(client: &mut impl GenericClient) -> Result<Self, postgres::Error> {
+        let mut registry = PoolRegistry::default();
+
+        let rows = match client.query(SELECT_POOLS_QUERY, &[]) {
+            Ok(rows) => rows,
+            Err(err) => {
+                println!("Error reading raydium_pool: {:?}", err);
+                return Err(err);
+            }
+        };
+
+        for row in rows {
+            if let Some(pool) = pool_from_row(&row) {
+                registry.insert(pool);
+            }
+        }
+        registry.commit();
+
+        Ok(registry)
+    }
+
+    /// Looks for the pools that are not cached (e.g. snapshots stored after the
+    /// startup) and returns how many were found.
+    pub fn load_missing(
+        &mut self,
+        client: &mut impl GenericClient,
+        amms: &[&str],
+    ) -> Result<usize, postgres::Error> {
+        let missing_amms: Vec<&str> = amms
+            .iter()
+            .filter(|amm| !self.pools.contains_key(**amm))
+            .copied()
+            .collect();
+
+        if missing_amms.is_empty() {
+            return Ok(0);
+        }
+
+        let query = format!("{} WHERE amm = ANY($1)", SELECT_POOLS_QUERY);
+        let rows = match client.query(&query, &[&missing_amms]) {
+            Ok(rows) => rows,
+            Err(err) => {
+                println!("Error reading raydium_pool: {:?}", err);
+                return Err(err);
+            }
+        };
+
+        let mut pools_count = 0;
+        for row in rows {
+            if let Some(pool) = pool_from_row(&row) {
+                self.insert(pool);
+                pools_count += 1;
+            }
+        }
+
+        Ok(pools_count)
+    }
+
+    pub fn insert(&mut self, pool: RaydiumPool) {
+        let amm = pool.amm.clone();
+        let previous_pool = self.pools.insert(amm.clone(), pool);
+        self.block_changes.push((amm, previous_pool));
+    }
+
+    /// Keeps the pools added by the block, once it is stored.
+    pub fn commit(&mut self) {
+        self.block_changes.clear();
+    }
+
+    /// Restores the pools as they were before a block that was not stored.
+    pub fn rollback(&mut self) {
+        while let Some((amm, previous_pool)) = self.block_changes.pop() {
+            match previous_pool {
+                Some(pool) => self.pools.insert(amm, pool),
+                None => self.pools.remove(&amm),
+            };
+        }
+    }
+
+    pub fn get(&self, amm: &str) -> Option<&RaydiumPool> {
+        self.pools.get(amm)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_pool(coin_mint: &str, pc_mint: &str) -> RaydiumPool {
+        RaydiumPool {
+            amm: "amm".to_string(),
+            coin_mint: coin_mint.to_string(),
+            pc_mint: pc_mint.to_string(),
+            coin_vault: "coin_vault".to_string(),
+            pc_vault: "pc_vault".to_string(),
+            coin_decimals: Some(6),
+            pc_decimals: Some(9),
+        }
+    }
+
+    fn build_swap(direction: SwapDirection) -> RaydiumSwapValues {
+        RaydiumSwapValues {
+            direction,
+            amount_in: 100,
+            amount_out: 200,
+            pool_coin: 0,
+            pool_pc: 0,
+        }
+    }
+
+    #[test]
+    fn test_tag_swap() {
+        // token/SOL pool: pc to coin receives the token
+        let pool = build_pool("token", WSOL_MINT);
+        let tag = pool.tag_swap(&build_swap(SwapDirection::PcToCoin));
+        assert_eq!(tag.base_mint, "token");
+        assert_eq!(tag.quote_mint, WSOL_MINT);
+        assert!(tag.is_buy);
+        assert_eq!(tag.base_amount, 200);
+        assert_eq!(tag.quote_amount, 100);
+
+        // SOL/token pool: coin to pc receives the token
+        let pool = build_pool(WSOL_MINT, "token");
+        let tag = pool.tag_swap(&build_swap(SwapDirection::CoinToPc));
+        assert_eq!(tag.base_mint, "token");
+        assert!(tag.is_buy);
+        let tag = pool.tag_swap(&build_swap(SwapDirection::PcToCoin));
+        assert!(!tag.is_buy);
+        assert_eq!(tag.base_amount, 100);
+        assert_eq!(tag.quote_amount, 200);
+    }
+
+    #[test]
+    fn test_pool_registry_rollback() {
+        let mut registry = PoolRegistry::default();
+        registry.insert(build_pool("token", WSOL_MINT));
+        registry.commit();
+
+        let mut other_pool = build_pool("other", WSOL_MINT);
+        other_pool.amm = "other_amm".to_string();
+        registry.insert(other_pool);
+        registry.insert(build_pool("replaced", WSOL_MINT));
+        registry.rollback();
+
+        assert_eq!(registry.get("amm").unwrap().coin_mint, "token");
+        assert!(registry.get("other_amm").is_none());
+        assert_eq!(registry.len(), 1);
+    }
+}
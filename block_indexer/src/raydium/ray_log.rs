@@ -0,0 +1,331 @@
+// use as reference: js_src/src/parsing/protocol/raydium/ray-log.decoder.ts
+
+use base64::prelude::*;
+
+use crate::parsing::deserialize_address;
+
+// the amm logs its inputs and the pool state with msg!("ray_log: {}", base64)
+pub const RAY_LOG_PREFIX: &str = "Program log: ray_log: ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwapDirection {
+    CoinToPc,
+    PcToCoin,
+}
+
+impl SwapDirection {
+    fn from_u64(value: u64) -> Option<Self> {
+        match value {
+            1 => Some(SwapDirection::CoinToPc),
+            2 => Some(SwapDirection::PcToCoin),
+            _ => None,
+        }
+    }
+}
+
+// time: u64
+// pc_decimals: u8
+// coin_decimals: u8
+// pc_lot_size: u64
+// coin_lot_size: u64
+// pc_amount: u64
+// coin_amount: u64
+// market: pubkey
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct InitLog {
+    pub time: u64,
+    pub pc_decimals: u8,
+    pub coin_decimals: u8,
+    pub pc_lot_size: u64,
+    pub coin_lot_size: u64,
+    pub pc_amount: u64,
+    pub coin_amount: u64,
+    pub market: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct DepositLog {
+    // input
+    pub max_coin: u64,
+    pub max_pc: u64,
+    pub base: u64,
+    // pool info
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+    pub pool_lp: u64,
+    pub calc_pnl_x: u128,
+    pub calc_pnl_y: u128,
+    // calc result
+    pub deduct_coin: u64,
+    pub deduct_pc: u64,
+    pub mint_lp: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct WithdrawLog {
+    // input
+    pub withdraw_lp: u64,
+    // user info
+    pub user_lp: u64,
+    // pool info
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+    pub pool_lp: u64,
+    pub calc_pnl_x: u128,
+    pub calc_pnl_y: u128,
+    // calc result
+    pub out_coin: u64,
+    pub out_pc: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapBaseInLog {
+    // input
+    pub amount_in: u64,
+    pub minimum_out: u64,
+    pub direction: u64,
+    // user info
+    pub user_source: u64,
+    // pool info
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+    // calc result
+    pub out_amount: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapBaseOutLog {
+    // input
+    pub max_in: u64,
+    pub amount_out: u64,
+    pub direction: u64,
+    // user info
+    pub user_source: u64,
+    // pool info
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+    // calc result
+    pub deduct_in: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum RayLog {
+    Init(InitLog),
+    Deposit(DepositLog),
+    Withdraw(WithdrawLog),
+    SwapBaseIn(SwapBaseInLog),
+    SwapBaseOut(SwapBaseOutLog),
+}
+
+/// Result of a swap, the pool amounts in the ray log are the ones before the
+/// swap so the reserves after it are derived from the amounts in/out.
+#[derive(Debug, Clone)]
+pub struct RaydiumSwapValues {
+    pub direction: SwapDirection,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+}
+
+impl RayLog {
+    pub fn swap_values(&self) -> Option<RaydiumSwapValues> {
+        let (direction, amount_in, amount_out, pool_coin, pool_pc) = match self {
+            RayLog::SwapBaseIn(log) => (
+                log.direction,
+                log.amount_in,
+                log.out_amount,
+                log.pool_coin,
+                log.pool_pc,
+            ),
+            RayLog::SwapBaseOut(log) => (
+                log.direction,
+                log.deduct_in,
+                log.amount_out,
+                log.pool_coin,
+                log.pool_pc,
+            ),
+            _ => return None,
+        };
+        let direction = SwapDirection::from_u64(direction)?;
+
+        let (pool_coin, pool_pc) = match direction {
+            SwapDirection::CoinToPc => (
+                pool_coin.saturating_add(amount_in),
+                pool_pc.saturating_sub(amount_out),
+            ),
+            SwapDirection::PcToCoin => (
+                pool_coin.saturating_sub(amount_out),
+                pool_pc.saturating_add(amount_in),
+            ),
+        };
+
+        Some(RaydiumSwapValues {
+            direction,
+            amount_in,
+            amount_out,
+            pool_coin,
+            pool_pc,
+        })
+    }
+
+    /// Pool (coin, pc) amounts after a deposit or a withdraw.
+    pub fn liquidity_values(&self) -> Option<(u64, u64)> {
+        match self {
+            RayLog::Deposit(log) => Some((
+                log.pool_coin.saturating_add(log.deduct_coin),
+                log.pool_pc.saturating_add(log.deduct_pc),
+            )),
+            RayLog::Withdraw(log) => Some((
+                log.pool_coin.saturating_sub(log.out_coin),
+                log.pool_pc.saturating_sub(log.out_pc),
+            )),
+            RayLog::Init(log) => Some((log.coin_amount, log.pc_amount)),
+            _ => None,
+        }
+    }
+}
+
+fn deserialize_init_log(log_data: &mut &[u8]) -> Option<InitLog> {
+    let time = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let pc_decimals = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let coin_decimals = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let pc_lot_size = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let coin_lot_size = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let pc_amount = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    let coin_amount = borsh::BorshDeserialize::deserialize(log_data).ok()?;
+    if log_data.len() < 32 {
+        return None;
+    }
+    let market = deserialize_address(log_data);
+
+    Some(InitLog {
+        time,
+        pc_decimals,
+        coin_decimals,
+        pc_lot_size,
+        coin_lot_size,
+        pc_amount,
+        coin_amount,
+        market,
+    })
+}
+
+fn deserialize_deposit_log(log_data: &mut &[u8]) -> Option<DepositLog> {
+    Some(DepositLog {
+        max_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        max_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        base: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_lp: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        calc_pnl_x: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        calc_pnl_y: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        deduct_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        deduct_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        mint_lp: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+    })
+}
+
+fn deserialize_withdraw_log(log_data: &mut &[u8]) -> Option<WithdrawLog> {
+    Some(WithdrawLog {
+        withdraw_lp: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        user_lp: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_lp: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        calc_pnl_x: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        calc_pnl_y: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        out_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        out_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+    })
+}
+
+fn deserialize_swap_base_in_log(log_data: &mut &[u8]) -> Option<SwapBaseInLog> {
+    Some(SwapBaseInLog {
+        amount_in: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        minimum_out: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        direction: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        user_source: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        out_amount: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+    })
+}
+
+fn deserialize_swap_base_out_log(log_data: &mut &[u8]) -> Option<SwapBaseOutLog> {
+    Some(SwapBaseOutLog {
+        max_in: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        amount_out: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        direction: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        user_source: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_coin: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        pool_pc: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+        deduct_in: borsh::BorshDeserialize::deserialize(log_data).ok()?,
+    })
+}
+
+// None when the log is not base64 or is too short (e.g. failed transactions log something else)
+pub fn decode_ray_log(encoded_log: &str) -> Option<RayLog> {
+    let log_bytes = BASE64_STANDARD.decode(encoded_log).ok()?;
+    let (log_type, mut log_data) = log_bytes.split_first()?;
+
+    match log_type {
+        0 => deserialize_init_log(&mut log_data).map(RayLog::Init),
+        1 => deserialize_deposit_log(&mut log_data).map(RayLog::Deposit),
+        2 => deserialize_withdraw_log(&mut log_data).map(RayLog::Withdraw),
+        3 => deserialize_swap_base_in_log(&mut log_data).map(RayLog::SwapBaseIn),
+        4 => deserialize_swap_base_out_log(&mut log_data).map(RayLog::SwapBaseOut),
+        _ => None,
+    }
+}
+
+/// Returns the ray logs of the instruction in the order they were written.
+pub fn find_ray_logs(log_messages: &[String]) -> Vec<RayLog> {
+    log_messages
+        .iter()
+        .filter_map(|log_message| log_message.strip_prefix(RAY_LOG_PREFIX))
+        .filter_map(decode_ray_log)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_swap_base_in_log() {
+        let mut log_bytes = vec![3u8];
+        for value in [
+            1_000_000_000u64, // amount_in
+            900_000,          // minimum_out
+            2,                // direction (pc to coin)
+            5_000_000_000,    // user_source
+            80_000_000_000,   // pool_coin
+            40_000_000_000,   // pool_pc
+            1_950_000,        // out_amount
+        ] {
+            log_bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let ray_log = decode_ray_log(&BASE64_STANDARD.encode(&log_bytes)).unwrap();
+        let RayLog::SwapBaseIn(swap_log) = &ray_log else {
+            panic!("expected a SwapBaseIn log");
+        };
+        assert_eq!(swap_log.amount_in, 1_000_000_000);
+        assert_eq!(swap_log.out_amount, 1_950_000);
+
+        let swap = ray_log.swap_values().unwrap();
+        assert_eq!(swap.direction, SwapDirection::PcToCoin);
+        assert_eq!(swap.amount_in, 1_000_000_000);
+        assert_eq!(swap.amount_out, 1_950_000);
+        assert_eq!(swap.pool_coin, 80_000_000_000 - 1_950_000);
+        assert_eq!(swap.pool_pc, 41_000_000_000);
+
+        // truncated data
+        assert!(decode_ray_log(&BASE64_STANDARD.encode(&log_bytes[..20])).is_none());
+    }
+}
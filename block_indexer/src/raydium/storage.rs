@@ -0,0 +1,51 @@
+// use as reference: js_src/src/storage/protocol/raydium/storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use super::pool_registry::PoolRegistry;
+use crate::storage::{arrange_events_by_type, BlockData, ProtocolSaveResult, StorageError};
+use crate::{Event, GenericEventType};
+
+mod liquidity;
+mod mint;
+mod pool;
+mod time_series;
+mod trade;
+use liquidity::save_liquidity_events;
+use mint::save_mint_events;
+use pool::save_pool_events;
+use trade::save_trade_events;
+
+pub fn save_protocol_events(
+    transaction: &mut Transaction,
+    events: &[&Event],
+    block_data: &BlockData,
+    pool_registry: &mut PoolRegistry,
+) -> Result<ProtocolSaveResult, StorageError> {
+    let mut rows_count = 0;
+    let mut events_by_type = HashMap::new();
+
+    for (event_type, events) in arrange_events_by_type(events) {
+        events_by_type.insert(event_type, events.len());
+        match event_type {
+            GenericEventType::TokenMint => {
+                rows_count += save_pool_events(transaction, &events, block_data, pool_registry)?;
+                rows_count += save_mint_events(transaction, &events, block_data)?;
+            }
+            GenericEventType::Trade => {
+                rows_count += save_trade_events(transaction, &events, block_data, pool_registry)?;
+            }
+            // deposits and withdraws
+            GenericEventType::Info => {
+                rows_count += save_liquidity_events(transaction, &events, block_data)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ProtocolSaveResult {
+        rows_count,
+        events_by_type,
+    })
+}
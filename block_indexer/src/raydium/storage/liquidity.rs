@@ -0,0 +1,152 @@
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::raydium::RaydiumLiquidityFunctionCallEventMeta;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+/// Pool (coin, pc) amounts after the last deposit or withdraw of every pool
+/// in the block.
+fn read_pool_amounts<'a>(liquidity_events: &[&'a Event]) -> HashMap<&'a str, (u64, u64)> {
+    let mut pool_amounts = HashMap::new();
+    for event in liquidity_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let Some(event_meta) = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<RaydiumLiquidityFunctionCallEventMeta>()
+        else {
+            continue;
+        };
+        if event_meta.failed_transaction {
+            continue;
+        }
+        let Some(amounts) = event_meta
+            .ray_log
+            .as_ref()
+            .and_then(|ray_log| ray_log.liquidity_values())
+        else {
+            continue;
+        };
+        pool_amounts.insert(event_meta.amm.as_str(), amounts);
+    }
+    pool_amounts
+}
+
+/// Only the followed pools (in raydium_data) are updated.
+pub fn save_liquidity_events(
+    transaction: &mut Transaction,
+    liquidity_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let pool_amounts = read_pool_amounts(liquidity_events);
+    if pool_amounts.is_empty() {
+        return Ok(0);
+    }
+
+    let update_query = format!(
+        "
+        UPDATE raydium_data
+        SET
+            coin_amount = tmp.pool_coin,
+            pc_amount = tmp.pool_pc,
+            updated = tmp.updated
+        FROM (
+            VALUES {}
+        ) AS tmp(amm, pool_coin, pool_pc, updated)
+        WHERE raydium_data.amm = tmp.amm;
+    ",
+        build_values_placeholders(
+            pool_amounts.len(),
+            &["::text", "::text::numeric", "::text::numeric", "::bigint"],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(pool_amounts.len() * 4);
+    for (amm, (pool_coin, pool_pc)) in &pool_amounts {
+        values.push(Box::new(amm.to_string()));
+        values.push(Box::new(pool_coin.to_string()));
+        values.push(Box::new(pool_pc.to_string()));
+        values.push(Box::new(block_data.block_time));
+    }
+
+    match execute_with_values(transaction, &update_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error updating raydium_data liquidity: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_budget::TransactionFees;
+    use crate::raydium::ray_log::{DepositLog, RayLog, WithdrawLog};
+    use crate::raydium::RaydiumInstructionData;
+    use crate::{FunctionCallEvent, GenericEventType, IndexerEventSource};
+
+    fn build_event(amm: &str, ray_log: RayLog) -> Event {
+        let instruction_data = match ray_log {
+            RayLog::Deposit(_) => RaydiumInstructionData::Deposit,
+            _ => RaydiumInstructionData::Withdraw,
+        };
+        Event::FunctionCall(FunctionCallEvent {
+            source: IndexerEventSource::Raydium,
+            event_type: GenericEventType::Info,
+            slot: 0,
+            transaction_index: 0,
+            instruction_index: 0,
+            fees: TransactionFees::default(),
+            signature: "signature".to_string(),
+            event_obj: Box::new(instruction_data),
+            event_meta: Box::new(RaydiumLiquidityFunctionCallEventMeta {
+                block_time: 0,
+                amm: amm.to_string(),
+                failed_transaction: false,
+                ray_log: Some(ray_log),
+            }),
+        })
+    }
+
+    #[test]
+    fn test_read_pool_amounts() {
+        let deposit = build_event(
+            "amm",
+            RayLog::Deposit(DepositLog {
+                max_coin: 100,
+                max_pc: 10,
+                base: 0,
+                pool_coin: 1000,
+                pool_pc: 100,
+                pool_lp: 0,
+                calc_pnl_x: 0,
+                calc_pnl_y: 0,
+                deduct_coin: 100,
+                deduct_pc: 10,
+                mint_lp: 0,
+            }),
+        );
+        let withdraw = build_event(
+            "amm",
+            RayLog::Withdraw(WithdrawLog {
+                withdraw_lp: 0,
+                user_lp: 0,
+                pool_coin: 1100,
+                pool_pc: 110,
+                pool_lp: 0,
+                calc_pnl_x: 0,
+                calc_pnl_y: 0,
+                out_coin: 50,
+                out_pc: 5,
+            }),
+        );
+
+        let events = [&deposit];
+        assert_eq!(read_pool_amounts(&events).get("amm"), Some(&(1100, 110)));
+        // the last event of the pool in the block is kept
+        let events = [&deposit, &withdraw];
+        assert_eq!(read_pool_amounts(&events).get("amm"), Some(&(1050, 105)));
+    }
+}
@@ -0,0 +1,188 @@
+// use as reference: js_src/src/storage/protocol/raydium/mint.storage.ts
+
+use postgres::Transaction;
+use std::collections::HashSet;
+
+use crate::raydium::{RaydiumInitializeFunctionCallEventMeta, RaydiumInstructionData};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+struct PoolCreation<'a> {
+    event_meta: &'a RaydiumInitializeFunctionCallEventMeta,
+    instruction_data: Option<&'a RaydiumInstructionData>,
+}
+
+// only the pools of the tokens launched in our protocols are followed
+fn filter_pools_of_interest<'a>(
+    transaction: &mut Transaction,
+    pool_creations: Vec<PoolCreation<'a>>,
+) -> Result<Vec<PoolCreation<'a>>, postgres::Error> {
+    let mints: Vec<&str> = pool_creations
+        .iter()
+        .flat_map(|pool_creation| {
+            let accounts = &pool_creation.event_meta.accounts;
+            [accounts.coin_mint.as_str(), accounts.pc_mint.as_str()]
+        })
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+
+    let select_query = "
+        SELECT mint FROM moonshot_data WHERE mint = ANY($1)
+        UNION
+        SELECT mint FROM pump_data WHERE mint = ANY($1);
+    ";
+
+    let rows = match transaction.query(select_query, &[&mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading mints of interest: {:?}", err);
+            return Err(err);
+        }
+    };
+    let mints_of_interest: HashSet<String> = rows.iter().map(|row| row.get("mint")).collect();
+
+    Ok(pool_creations
+        .into_iter()
+        .filter(|pool_creation| {
+            let accounts = &pool_creation.event_meta.accounts;
+            mints_of_interest.contains(&accounts.coin_mint)
+                || mints_of_interest.contains(&accounts.pc_mint)
+        })
+        .collect())
+}
+
+pub fn save_mint_events(
+    transaction: &mut Transaction,
+    mint_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let pool_creations: Vec<PoolCreation> = mint_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            let event_meta = function_call_event
+                .event_meta
+                .as_any()
+                .downcast_ref::<RaydiumInitializeFunctionCallEventMeta>()?;
+            Some(PoolCreation {
+                event_meta,
+                instruction_data: function_call_event
+                    .event_obj
+                    .as_any()
+                    .downcast_ref::<RaydiumInstructionData>(),
+            })
+        })
+        .collect();
+
+    if pool_creations.is_empty() {
+        return Ok(0);
+    }
+
+    let pool_creations = filter_pools_of_interest(transaction, pool_creations)?;
+    if pool_creations.is_empty() {
+        return Ok(0);
+    }
+
+    // TODO: ON CONFLICT (amm) update the fields (because the re-indexing of old blocks when new blocks with prices are indexed)
+    let insert_query = format!(
+        "
+        INSERT INTO raydium_data (
+            amm,
+            amm_open_orders,
+            lp_mint,
+            coin_mint,
+            pc_mint,
+            pool_coin_token_account,
+            pool_pc_token_account,
+            amm_target_orders,
+            open_time,
+            init_pc_amount,
+            init_coin_amount,
+            pc_decimals,
+            coin_decimals,
+            pc_lot_size,
+            coin_lot_size,
+            pc_amount,
+            coin_amount,
+            market,
+            created,
+            updated,
+            create_event_slot
+        ) VALUES {}
+        ON CONFLICT (amm) DO NOTHING;
+    ",
+        build_values_placeholders(
+            pool_creations.len(),
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(pool_creations.len() * 21);
+    for pool_creation in &pool_creations {
+        let accounts = &pool_creation.event_meta.accounts;
+        let init_log = pool_creation.event_meta.init_log.as_ref();
+        let initialize_values = match pool_creation.instruction_data {
+            Some(RaydiumInstructionData::Initialize2(values)) => values.as_ref(),
+            _ => None,
+        };
+
+        values.push(Box::new(accounts.amm.clone()));
+        values.push(Box::new(accounts.amm_open_orders.clone()));
+        values.push(Box::new(accounts.lp_mint.clone()));
+        values.push(Box::new(accounts.coin_mint.clone()));
+        values.push(Box::new(accounts.pc_mint.clone()));
+        values.push(Box::new(accounts.pool_coin_token_account.clone()));
+        values.push(Box::new(accounts.pool_pc_token_account.clone()));
+        values.push(Box::new(accounts.amm_target_orders.clone()));
+        values.push(Box::new(
+            initialize_values.map(|values| values.open_time as i64),
+        ));
+        values.push(Box::new(
+            initialize_values.map(|values| values.init_pc_amount.to_string()),
+        ));
+        values.push(Box::new(
+            initialize_values.map(|values| values.init_coin_amount.to_string()),
+        ));
+        values.push(Box::new(init_log.map(|log| log.pc_decimals as i32)));
+        values.push(Box::new(init_log.map(|log| log.coin_decimals as i32)));
+        values.push(Box::new(init_log.map(|log| log.pc_lot_size.to_string())));
+        values.push(Box::new(init_log.map(|log| log.coin_lot_size.to_string())));
+        values.push(Box::new(init_log.map(|log| log.pc_amount.to_string())));
+        values.push(Box::new(init_log.map(|log| log.coin_amount.to_string())));
+        values.push(Box::new(accounts.market.clone()));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(block_data.slot));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting raydium_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::raydium::pool_registry::{PoolRegistry, RaydiumPool};
+use crate::raydium::{RaydiumInitializeFunctionCallEventMeta, RaydiumInstructionData};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+/// Persists the pools created in the block and adds them to the registry, so the
+/// swaps of the same block can already be resolved.
+pub fn save_pool_events(
+    transaction: &mut Transaction,
+    mint_events: &[&Event],
+    block_data: &BlockData,
+    pool_registry: &mut PoolRegistry,
+) -> Result<u64, postgres::Error> {
+    // a pool can't be twice in the same upsert
+    let mut pool_events: HashMap<&str, (&RaydiumInitializeFunctionCallEventMeta, u64)> =
+        HashMap::new();
+    for event in mint_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let Some(event_meta) = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<RaydiumInitializeFunctionCallEventMeta>()
+        else {
+            continue;
+        };
+        let open_time = match function_call_event
+            .event_obj
+            .as_any()
+            .downcast_ref::<RaydiumInstructionData>()
+        {
+            Some(RaydiumInstructionData::Initialize2(Some(values))) => values.open_time,
+            _ => 0,
+        };
+        pool_events.insert(&event_meta.accounts.amm, (event_meta, open_time));
+    }
+
+    if pool_events.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO raydium_pool (
+            amm,
+            coin_mint,
+            pc_mint,
+            pool_coin_token_account,
+            pool_pc_token_account,
+            lp_mint,
+            amm_open_orders,
+            amm_target_orders,
+            market,
+            coin_decimals,
+            pc_decimals,
+            open_time,
+            created,
+            create_event_slot
+        ) VALUES {}
+        ON CONFLICT (amm) DO UPDATE
+        SET
+            coin_mint = EXCLUDED.coin_mint,
+            pc_mint = EXCLUDED.pc_mint,
+            pool_coin_token_account = EXCLUDED.pool_coin_token_account,
+            pool_pc_token_account = EXCLUDED.pool_pc_token_account,
+            lp_mint = EXCLUDED.lp_mint,
+            amm_open_orders = EXCLUDED.amm_open_orders,
+            amm_target_orders = EXCLUDED.amm_target_orders,
+            market = EXCLUDED.market,
+            coin_decimals = COALESCE(EXCLUDED.coin_decimals, raydium_pool.coin_decimals),
+            pc_decimals = COALESCE(EXCLUDED.pc_decimals, raydium_pool.pc_decimals),
+            open_time = EXCLUDED.open_time,
+            created = EXCLUDED.created,
+            create_event_slot = EXCLUDED.create_event_slot;
+    ",
+        build_values_placeholders(
+            pool_events.len(),
+            &["", "", "", "", "", "", "", "", "", "", "", "", "", ""],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(pool_events.len() * 14);
+    for (event_meta, open_time) in pool_events.values() {
+        let accounts = &event_meta.accounts;
+        let init_log = event_meta.init_log.as_ref();
+        let pool = RaydiumPool {
+            amm: accounts.amm.clone(),
+            coin_mint: accounts.coin_mint.clone(),
+            pc_mint: accounts.pc_mint.clone(),
+            coin_vault: accounts.pool_coin_token_account.clone(),
+            pc_vault: accounts.pool_pc_token_account.clone(),
+            coin_decimals: init_log.map(|log| log.coin_decimals as i32),
+            pc_decimals: init_log.map(|log| log.pc_decimals as i32),
+        };
+
+        values.push(Box::new(pool.amm.clone()));
+        values.push(Box::new(pool.coin_mint.clone()));
+        values.push(Box::new(pool.pc_mint.clone()));
+        values.push(Box::new(pool.coin_vault.clone()));
+        values.push(Box::new(pool.pc_vault.clone()));
+        values.push(Box::new(accounts.lp_mint.clone()));
+        values.push(Box::new(accounts.amm_open_orders.clone()));
+        values.push(Box::new(accounts.amm_target_orders.clone()));
+        values.push(Box::new(accounts.market.clone()));
+        values.push(Box::new(pool.coin_decimals));
+        values.push(Box::new(pool.pc_decimals));
+        values.push(Box::new(*open_time as i64));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(block_data.slot));
+
+        pool_registry.insert(pool);
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting raydium_pool: {:?}", err);
+            Err(err)
+        }
+    }
+}
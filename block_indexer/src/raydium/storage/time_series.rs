@@ -0,0 +1,96 @@
+// use as reference: js_src/src/storage/protocol/raydium/trade.storage.ts (buildS1Bars, saveBars)
+
+use postgres::Transaction;
+
+use super::trade::{compute_price, RaydiumTrade};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::time_series::{Bar, BarAggregator};
+
+/// Bars of every timeframe for the executed trades (non failed and with the ray
+/// log). Unlike the Node version every trade is added, not only the last one of
+/// each pool. The price is in SOL and the volume in lamports.
+pub fn build_bars(trades: &[RaydiumTrade]) -> Vec<Bar> {
+    let mut aggregator = BarAggregator::new();
+
+    for trade in trades {
+        let Some(tag) = &trade.tag else {
+            continue;
+        };
+
+        aggregator.add_trade(
+            &tag.base_mint,
+            trade.event_meta.block_time as i64,
+            compute_price(&trade.pool, tag),
+            tag.quote_amount,
+            tag.is_buy,
+        );
+    }
+
+    aggregator.into_bars()
+}
+
+pub fn save_bars(
+    transaction: &mut Transaction,
+    bars: &[Bar],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if bars.is_empty() {
+        return Ok(0);
+    }
+
+    // open is only set by the first insert of the bar
+    let insert_query = format!(
+        "
+        INSERT INTO raydium_price_bar (
+            timeframe, mint, timestamp, open, high, low, close, volume, buy_count, sell_count, created
+        ) VALUES {}
+        ON CONFLICT (timeframe, mint, timestamp)
+        DO UPDATE SET
+            high = GREATEST(EXCLUDED.high, raydium_price_bar.high),
+            low = LEAST(EXCLUDED.low, raydium_price_bar.low),
+            close = EXCLUDED.close,
+            volume = raydium_price_bar.volume + EXCLUDED.volume,
+            buy_count = raydium_price_bar.buy_count + EXCLUDED.buy_count,
+            sell_count = raydium_price_bar.sell_count + EXCLUDED.sell_count;
+    ",
+        build_values_placeholders(
+            bars.len(),
+            &[
+                "",
+                "",
+                "",
+                "::float8",
+                "::float8",
+                "::float8",
+                "::float8",
+                "::text::numeric",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(bars.len() * 11);
+    for bar in bars {
+        values.push(Box::new(bar.timeframe.seconds()));
+        values.push(Box::new(bar.mint.clone()));
+        values.push(Box::new(bar.timestamp));
+        values.push(Box::new(bar.open));
+        values.push(Box::new(bar.high));
+        values.push(Box::new(bar.low));
+        values.push(Box::new(bar.close));
+        values.push(Box::new(bar.volume.to_string()));
+        values.push(Box::new(bar.buy_count));
+        values.push(Box::new(bar.sell_count));
+        values.push(Box::new(block_data.block_time));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting/updating raydium_price_bar: {:?}", err);
+            Err(err)
+        }
+    }
+}
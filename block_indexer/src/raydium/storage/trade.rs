@@ -0,0 +1,582 @@
+// use as reference: js_src/src/storage/protocol/raydium/trade.storage.ts
+// and js_src/src/storage/protocol/raydium/consolidation.ts
+
+use postgres::Transaction;
+use std::collections::{HashMap, HashSet};
+
+use super::time_series::{build_bars, save_bars};
+use crate::compute_budget::TransactionFees;
+use crate::diamond_hands::storage::save_diamond_hands;
+use crate::last_trades::{save_last_trades, LastTrade};
+use crate::moonshot::storage::curve::read_curve_params;
+use crate::parsing::WSOL_MINT;
+use crate::positions::storage::save_positions;
+use crate::raydium::pool_registry::{PoolRegistry, RaydiumPool, RaydiumSwapTag};
+use crate::raydium::{RayLog, RaydiumInstructionData, RaydiumTradeFunctionCallEventMeta};
+use crate::sol_price::{lamports_to_usd, sol_to_usd};
+use crate::storage::{
+    build_values_placeholders, execute_with_values, BlockData, SqlValue, StorageError,
+};
+use crate::Event;
+
+const DEFAULT_TOKEN_DECIMALS: i32 = 6;
+const SOL_DECIMALS: i32 = 9;
+
+/// A swap of one of the pools we follow, resolved with the pool registry.
+pub struct RaydiumTrade<'a> {
+    pub signature: &'a str,
+    pub transaction_index: u32,
+    pub instruction_index: u32,
+    pub fees: TransactionFees,
+    pub instruction_data: Option<&'a RaydiumInstructionData>,
+    pub event_meta: &'a RaydiumTradeFunctionCallEventMeta,
+    pub pool: RaydiumPool,
+    // None for failed transactions and truncated logs
+    pub tag: Option<RaydiumSwapTag>,
+}
+
+impl RaydiumTrade<'_> {
+    pub fn mint(&self) -> &str {
+        self.pool.base_mint()
+    }
+}
+
+/// Price of the base token in SOL.
+pub fn compute_price(pool: &RaydiumPool, tag: &RaydiumSwapTag) -> f64 {
+    if tag.base_amount == 0 {
+        return 0.0;
+    }
+    let base_decimals = pool.base_decimals().unwrap_or(DEFAULT_TOKEN_DECIMALS);
+    (tag.quote_amount as f64 / 10f64.powi(SOL_DECIMALS))
+        / (tag.base_amount as f64 / 10f64.powi(base_decimals))
+}
+
+/// Marketcap in USD of the whole supply (raw amount) at the given USD price.
+pub fn compute_marketcap_usd(price_usd: f64, supply: u64, base_decimals: i32) -> f64 {
+    price_usd * (supply as f64 / 10f64.powi(base_decimals))
+}
+
+// raw total supply of our tokens: pump_data for Pump.fun, the curve params for Moonshot
+fn read_token_supplies(
+    transaction: &mut Transaction,
+    mints: &[&str],
+) -> Result<HashMap<String, u64>, postgres::Error> {
+    let select_query = "
+        SELECT mint, token_total_supply::text AS token_total_supply
+        FROM pump_data
+        WHERE mint = ANY($1) AND token_total_supply IS NOT NULL;
+    ";
+    let rows = match transaction.query(select_query, &[&mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading pump_data supplies: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    let mut supplies: HashMap<String, u64> = read_curve_params(transaction, mints)?
+        .into_iter()
+        .map(|(mint, params)| (mint, params.total_supply))
+        .collect();
+    for row in &rows {
+        let token_total_supply: &str = row.get("token_total_supply");
+        if let Ok(supply) = token_total_supply.parse() {
+            supplies.insert(row.get("mint"), supply);
+        }
+    }
+    Ok(supplies)
+}
+
+// the pools of interest are the ones in raydium_data (pools of our tokens) that trade against SOL
+fn resolve_trades<'a>(
+    transaction: &mut Transaction,
+    trade_events: &[&'a Event],
+    pool_registry: &mut PoolRegistry,
+) -> Result<Vec<RaydiumTrade<'a>>, postgres::Error> {
+    let amms: Vec<&str> = trade_events
+        .iter()
+        .map(|event| get_trade_meta(event).accounts.amm.as_str())
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+
+    let select_query = "SELECT amm FROM raydium_data WHERE amm = ANY($1);";
+    let rows = match transaction.query(select_query, &[&amms]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading raydium_data: {:?}", err);
+            return Err(err);
+        }
+    };
+    let amms_of_interest: HashSet<String> = rows.iter().map(|row| row.get("amm")).collect();
+    if amms_of_interest.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let amms_of_interest_list: Vec<&str> =
+        amms_of_interest.iter().map(|amm| amm.as_str()).collect();
+    pool_registry.load_missing(transaction, &amms_of_interest_list)?;
+
+    let mut trades = Vec::new();
+    for event in trade_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let event_meta = get_trade_meta(event);
+        if !amms_of_interest.contains(&event_meta.accounts.amm) {
+            continue;
+        }
+        let Some(pool) = pool_registry.get(&event_meta.accounts.amm) else {
+            continue;
+        };
+        if pool.quote_mint() != WSOL_MINT {
+            continue;
+        }
+
+        trades.push(RaydiumTrade {
+            signature: &function_call_event.signature,
+            transaction_index: function_call_event.transaction_index,
+            instruction_index: function_call_event.instruction_index,
+            fees: function_call_event.fees,
+            instruction_data: function_call_event
+                .event_obj
+                .as_any()
+                .downcast_ref::<RaydiumInstructionData>(),
+            event_meta,
+            pool: pool.clone(),
+            tag: event_meta.swap.as_ref().map(|swap| pool.tag_swap(swap)),
+        });
+    }
+
+    Ok(trades)
+}
+
+fn get_trade_meta(event: &Event) -> &RaydiumTradeFunctionCallEventMeta {
+    let Event::FunctionCall(function_call_event) = event;
+    function_call_event
+        .event_meta
+        .as_any()
+        .downcast_ref::<RaydiumTradeFunctionCallEventMeta>()
+        .unwrap()
+}
+
+/// Per pool summary of the trades of a block, the price and the pool amounts
+/// are the ones after the last trade. The volumes are in lamports.
+pub struct ConsolidatedRaydiumTrades<'a> {
+    pub amm: &'a str,
+    pub mint: &'a str,
+    pub base_decimals: i32,
+    pub price: f64,
+    pub buy_count: i64,
+    pub sell_count: i64,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    pub volume: u64,
+    pub pool_coin: u64,
+    pub pool_pc: u64,
+}
+
+// only the executed trades count (non failed with the ray log)
+pub fn consolidate_trades<'a>(trades: &'a [RaydiumTrade]) -> Vec<ConsolidatedRaydiumTrades<'a>> {
+    let mut consolidated_trades: Vec<ConsolidatedRaydiumTrades> = Vec::new();
+    let mut index_by_amm: HashMap<&str, usize> = HashMap::new();
+
+    for trade in trades {
+        let (Some(tag), Some(swap)) = (&trade.tag, &trade.event_meta.swap) else {
+            continue;
+        };
+        let price = compute_price(&trade.pool, tag);
+
+        let index = *index_by_amm.entry(&trade.pool.amm).or_insert_with(|| {
+            consolidated_trades.push(ConsolidatedRaydiumTrades {
+                amm: &trade.pool.amm,
+                mint: trade.mint(),
+                base_decimals: trade.pool.base_decimals().unwrap_or(DEFAULT_TOKEN_DECIMALS),
+                price,
+                buy_count: 0,
+                sell_count: 0,
+                buy_volume: 0,
+                sell_volume: 0,
+                volume: 0,
+                pool_coin: swap.pool_coin,
+                pool_pc: swap.pool_pc,
+            });
+            consolidated_trades.len() - 1
+        });
+
+        let consolidated = &mut consolidated_trades[index];
+        if tag.is_buy {
+            consolidated.buy_count += 1;
+            consolidated.buy_volume += tag.quote_amount;
+        } else {
+            consolidated.sell_count += 1;
+            consolidated.sell_volume += tag.quote_amount;
+        }
+        consolidated.volume += tag.quote_amount;
+        consolidated.price = price;
+        consolidated.pool_coin = swap.pool_coin;
+        consolidated.pool_pc = swap.pool_pc;
+    }
+
+    consolidated_trades
+}
+
+fn save_prices(
+    transaction: &mut Transaction,
+    trades: &[RaydiumTrade],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let consolidated_trades = consolidate_trades(trades);
+    if consolidated_trades.is_empty() {
+        return Ok(0);
+    }
+
+    let mints: Vec<&str> = consolidated_trades
+        .iter()
+        .map(|consolidated| consolidated.mint)
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+    let supplies = read_token_supplies(transaction, &mints)?;
+
+    let update_query = format!(
+        "
+        UPDATE raydium_data
+        SET
+            price = tmp.price,
+            updated = tmp.updated,
+            buy_count = tmp.buy_count + COALESCE(raydium_data.buy_count, 0),
+            sell_count = tmp.sell_count + COALESCE(raydium_data.sell_count, 0),
+            buy_volume = tmp.buy_volume + COALESCE(raydium_data.buy_volume, 0),
+            sell_volume = tmp.sell_volume + COALESCE(raydium_data.sell_volume, 0),
+            volume = tmp.volume + COALESCE(raydium_data.volume, 0),
+            coin_amount = tmp.pool_coin,
+            pc_amount = tmp.pool_pc,
+            price_usd = tmp.price_usd,
+            volume_usd = COALESCE(tmp.volume_usd, 0) + COALESCE(raydium_data.volume_usd, 0),
+            marketcap_usd = tmp.marketcap_usd
+        FROM (
+            VALUES {}
+        ) AS tmp(amm, price, updated, buy_count, sell_count, buy_volume, sell_volume, volume, pool_coin, pool_pc, price_usd, volume_usd, marketcap_usd)
+        WHERE raydium_data.amm = tmp.amm;
+    ",
+        build_values_placeholders(
+            consolidated_trades.len(),
+            &[
+                "::text",
+                "::float8",
+                "::bigint",
+                "::bigint",
+                "::bigint",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::float8",
+                "::float8",
+                "::float8",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(consolidated_trades.len() * 13);
+    for consolidated in &consolidated_trades {
+        values.push(Box::new(consolidated.amm.to_string()));
+        values.push(Box::new(consolidated.price));
+        values.push(Box::new(block_data.block_time));
+        values.push(Box::new(consolidated.buy_count));
+        values.push(Box::new(consolidated.sell_count));
+        values.push(Box::new(consolidated.buy_volume.to_string()));
+        values.push(Box::new(consolidated.sell_volume.to_string()));
+        values.push(Box::new(consolidated.volume.to_string()));
+        values.push(Box::new(consolidated.pool_coin.to_string()));
+        values.push(Box::new(consolidated.pool_pc.to_string()));
+        let price_usd = sol_to_usd(consolidated.price, block_data.sol_price);
+        values.push(Box::new(price_usd));
+        values.push(Box::new(lamports_to_usd(
+            consolidated.volume,
+            block_data.sol_price,
+        )));
+        // NULL when the supply of the token is not stored
+        values.push(Box::new(
+            price_usd
+                .zip(supplies.get(consolidated.mint))
+                .map(|(price_usd, supply)| {
+                    compute_marketcap_usd(price_usd, *supply, consolidated.base_decimals)
+                }),
+        ));
+    }
+
+    match execute_with_values(transaction, &update_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error updating raydium_data: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+// ray log fields as stored in raydium_trade (the failed transactions only have
+// the values requested in the instruction)
+struct RaydiumTradeRow {
+    log_type: &'static str,
+    amount_in: Option<u64>,
+    minimum_out: Option<u64>,
+    direction: Option<i16>,
+    user_source: Option<u64>,
+    pool_coin: Option<u64>,
+    pool_pc: Option<u64>,
+    out_amount: Option<u64>,
+    max_in: Option<u64>,
+    amount_out: Option<u64>,
+    deduct_in: Option<u64>,
+}
+
+impl RaydiumTradeRow {
+    fn build(trade: &RaydiumTrade) -> Self {
+        let mut row = RaydiumTradeRow {
+            log_type: "Unknown",
+            amount_in: None,
+            minimum_out: None,
+            direction: None,
+            user_source: None,
+            pool_coin: None,
+            pool_pc: None,
+            out_amount: None,
+            max_in: None,
+            amount_out: None,
+            deduct_in: None,
+        };
+
+        match &trade.event_meta.ray_log {
+            Some(RayLog::SwapBaseIn(log)) => {
+                row.log_type = "SwapBaseIn";
+                row.amount_in = Some(log.amount_in);
+                row.minimum_out = Some(log.minimum_out);
+                row.direction = Some(log.direction as i16);
+                row.user_source = Some(log.user_source);
+                row.pool_coin = Some(log.pool_coin);
+                row.pool_pc = Some(log.pool_pc);
+                row.out_amount = Some(log.out_amount);
+            }
+            Some(RayLog::SwapBaseOut(log)) => {
+                row.log_type = "SwapBaseOut";
+                row.max_in = Some(log.max_in);
+                row.amount_out = Some(log.amount_out);
+                row.direction = Some(log.direction as i16);
+                row.user_source = Some(log.user_source);
+                row.pool_coin = Some(log.pool_coin);
+                row.pool_pc = Some(log.pool_pc);
+                row.deduct_in = Some(log.deduct_in);
+            }
+            _ => match trade.instruction_data {
+                Some(RaydiumInstructionData::SwapBaseIn(values)) => {
+                    row.log_type = "SwapBaseIn";
+                    row.amount_in = values.as_ref().map(|values| values.amount_in);
+                    row.minimum_out = values.as_ref().map(|values| values.minimum_amount_out);
+                }
+                Some(RaydiumInstructionData::SwapBaseOut(values)) => {
+                    row.log_type = "SwapBaseOut";
+                    row.max_in = values.as_ref().map(|values| values.max_amount_in);
+                    row.amount_out = values.as_ref().map(|values| values.amount_out);
+                }
+                _ => {}
+            },
+        }
+
+        row
+    }
+}
+
+// this includes failed transactions
+fn save_trade_history(
+    transaction: &mut Transaction,
+    trades: &[RaydiumTrade],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    if trades.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO raydium_trade (
+            signer,
+            amm,
+            mint,
+            transaction_id,
+            log_type,
+            amount_in,
+            minimum_out,
+            direction,
+            user_source,
+            pool_coin,
+            pool_pc,
+            out_amount,
+            max_in,
+            amount_out,
+            deduct_in,
+            is_buy,
+            created,
+            failed_transaction,
+            priority_fee,
+            compute_unit_limit,
+            compute_units_consumed,
+            base_fee,
+            jito_tip
+        ) VALUES {};
+    ",
+        build_values_placeholders(
+            trades.len(),
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(trades.len() * 23);
+    for trade in trades {
+        let row = RaydiumTradeRow::build(trade);
+
+        values.push(Box::new(trade.event_meta.accounts.user_wallet.clone()));
+        values.push(Box::new(trade.pool.amm.clone()));
+        values.push(Box::new(trade.mint().to_string()));
+        values.push(Box::new(trade.signature.to_string()));
+        values.push(Box::new(row.log_type));
+        values.push(Box::new(row.amount_in.map(|value| value.to_string())));
+        values.push(Box::new(row.minimum_out.map(|value| value.to_string())));
+        values.push(Box::new(row.direction));
+        values.push(Box::new(row.user_source.map(|value| value.to_string())));
+        values.push(Box::new(row.pool_coin.map(|value| value.to_string())));
+        values.push(Box::new(row.pool_pc.map(|value| value.to_string())));
+        values.push(Box::new(row.out_amount.map(|value| value.to_string())));
+        values.push(Box::new(row.max_in.map(|value| value.to_string())));
+        values.push(Box::new(row.amount_out.map(|value| value.to_string())));
+        values.push(Box::new(row.deduct_in.map(|value| value.to_string())));
+        values.push(Box::new(trade.tag.as_ref().map(|tag| tag.is_buy)));
+        values.push(Box::new(block_data.block_time as i32));
+        values.push(Box::new(trade.event_meta.failed_transaction));
+        values.push(Box::new(trade.fees.priority_fee as i64));
+        values.push(Box::new(trade.fees.compute_unit_limit as i32));
+        values.push(Box::new(trade.fees.compute_units_consumed as i32));
+        values.push(Box::new(trade.fees.base_fee as i64));
+        values.push(Box::new(trade.fees.jito_tip as i64));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting raydium_trade: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+fn build_last_trades(trades: &[RaydiumTrade], block_data: &BlockData) -> Vec<LastTrade> {
+    trades
+        .iter()
+        .filter_map(|trade| {
+            let tag = trade.tag.as_ref()?;
+            Some(LastTrade {
+                mint: trade.mint().to_string(),
+                slot: block_data.slot,
+                transaction_index: trade.transaction_index,
+                instruction_index: trade.instruction_index,
+                timestamp: trade.event_meta.block_time as i64,
+                is_buy: tag.is_buy,
+                token_amount: tag.base_amount,
+                sol_amount: tag.quote_amount,
+                sender: trade.event_meta.accounts.user_wallet.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn save_trade_events(
+    transaction: &mut Transaction,
+    trade_events: &[&Event],
+    block_data: &BlockData,
+    pool_registry: &mut PoolRegistry,
+) -> Result<u64, StorageError> {
+    if trade_events.is_empty() {
+        return Ok(0);
+    }
+
+    let trades = resolve_trades(transaction, trade_events, pool_registry)?;
+    if trades.is_empty() {
+        return Ok(0);
+    }
+
+    let mut rows_count = 0;
+
+    rows_count += save_prices(transaction, &trades, block_data)?;
+    rows_count += save_bars(transaction, &build_bars(&trades), block_data)?;
+    rows_count += save_trade_history(transaction, &trades, block_data)?;
+    let last_trades = build_last_trades(&trades, block_data);
+    rows_count += save_last_trades(transaction, &last_trades, block_data)?;
+    rows_count += save_diamond_hands(transaction, &last_trades, block_data)?;
+    rows_count += save_positions(transaction, &last_trades, block_data)?;
+
+    Ok(rows_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_price() {
+        let pool = RaydiumPool {
+            amm: "amm".to_string(),
+            coin_mint: "token".to_string(),
+            pc_mint: WSOL_MINT.to_string(),
+            coin_vault: "coin_vault".to_string(),
+            pc_vault: "pc_vault".to_string(),
+            coin_decimals: Some(6),
+            pc_decimals: Some(9),
+        };
+        // 2 SOL for 1000 tokens
+        let tag = RaydiumSwapTag {
+            base_mint: "token".to_string(),
+            quote_mint: WSOL_MINT.to_string(),
+            is_buy: true,
+            base_amount: 1_000_000_000,
+            quote_amount: 2_000_000_000,
+        };
+        assert!((compute_price(&pool, &tag) - 0.002).abs() < 1e-12);
+
+        let tag = RaydiumSwapTag {
+            base_amount: 0,
+            ..tag
+        };
+        assert_eq!(compute_price(&pool, &tag), 0.0);
+    }
+
+    #[test]
+    fn test_compute_marketcap_usd() {
+        // 1B tokens with 6 decimals at 0.0004 USD
+        let marketcap_usd = compute_marketcap_usd(0.0004, 1_000_000_000_000_000, 6);
+        assert!((marketcap_usd - 400_000.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,447 @@
+// Sandwiches of a block: a wallet buys a token, other wallets buy it after at a
+// worse price, and the first wallet sells in a later transaction of the same
+// block (or the reverse, sell first and buy back after the sells of the
+// victims). Only the Pump.fun, Moonshot and the followed Raydium pools are checked.
+
+use postgres::Transaction;
+use std::collections::{HashMap, HashSet};
+
+use crate::moonshot::{MoonshotTradeFunctionCallEventMeta, MoonshotTradeType};
+use crate::parsing::WSOL_MINT;
+use crate::pumpfun::PumpfunTradeFunctionCallEventMeta;
+use crate::raydium::pool_registry::PoolRegistry;
+use crate::raydium::RaydiumTradeFunctionCallEventMeta;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::{Event, GenericEventType, IndexerEventSource};
+
+/// The trades of a wallet on a mint in a transaction, merged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockTrade<'a> {
+    pub source: IndexerEventSource,
+    pub mint: &'a str,
+    pub wallet: &'a str,
+    pub signature: &'a str,
+    pub transaction_index: u32,
+    pub is_buy: bool,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sandwich<'a> {
+    pub front_run: BlockTrade<'a>,
+    pub victim: BlockTrade<'a>,
+    pub back_run: BlockTrade<'a>,
+    // lamports paid by the victim above the price of the front run
+    pub victim_loss: u64,
+    // of the tokens bought in the front run and sold in the back run
+    pub attacker_profit: i128,
+}
+
+/// Returns the executed trades of the block, in chain order.
+pub fn read_block_trades<'a>(
+    events: &'a [Event],
+    pool_registry: &'a PoolRegistry,
+) -> Vec<BlockTrade<'a>> {
+    let mut trades: Vec<BlockTrade> = Vec::new();
+
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        if function_call_event.event_type != GenericEventType::Trade {
+            continue;
+        }
+        let event_meta = function_call_event.event_meta.as_any();
+
+        let trade = if let Some(event_meta) =
+            event_meta.downcast_ref::<PumpfunTradeFunctionCallEventMeta>()
+        {
+            let Some(trade_event) = &event_meta.event_log else {
+                continue;
+            };
+            (
+                trade_event.mint.as_str(),
+                trade_event.user.as_str(),
+                trade_event.is_buy,
+                trade_event.token_amount,
+                trade_event.sol_amount,
+            )
+        } else if let Some(event_meta) =
+            event_meta.downcast_ref::<MoonshotTradeFunctionCallEventMeta>()
+        {
+            if event_meta.failed_transaction || event_meta.event_log.is_none() {
+                continue;
+            }
+            (
+                event_meta.mint.as_str(),
+                event_meta.sender.as_str(),
+                matches!(event_meta.trade_type, MoonshotTradeType::Buy),
+                event_meta.token_amount,
+                event_meta.collateral_amount,
+            )
+        } else if let Some(event_meta) =
+            event_meta.downcast_ref::<RaydiumTradeFunctionCallEventMeta>()
+        {
+            let Some(swap) = &event_meta.swap else {
+                continue;
+            };
+            let Some(pool) = pool_registry.get(&event_meta.accounts.amm) else {
+                continue;
+            };
+            if event_meta.failed_transaction || pool.quote_mint() != WSOL_MINT {
+                continue;
+            }
+            let tag = pool.tag_swap(swap);
+            (
+                pool.base_mint(),
+                event_meta.accounts.user_wallet.as_str(),
+                tag.is_buy,
+                tag.base_amount,
+                tag.quote_amount,
+            )
+        } else {
+            continue;
+        };
+        let (mint, wallet, is_buy, token_amount, sol_amount) = trade;
+
+        // the trades of a transaction are consecutive
+        if let Some(last_trade) = trades.last_mut() {
+            if last_trade.transaction_index == function_call_event.transaction_index
+                && last_trade.source == function_call_event.source
+                && last_trade.mint == mint
+                && last_trade.wallet == wallet
+                && last_trade.is_buy == is_buy
+            {
+                last_trade.token_amount += token_amount;
+                last_trade.sol_amount += sol_amount;
+                continue;
+            }
+        }
+
+        trades.push(BlockTrade {
+            source: function_call_event.source,
+            mint,
+            wallet,
+            signature: &function_call_event.signature,
+            transaction_index: function_call_event.transaction_index,
+            is_buy,
+            token_amount,
+            sol_amount,
+        });
+    }
+
+    trades
+}
+
+/// Lamports the victim paid above (buys) or received below (sells) the front
+/// run price.
+fn estimate_victim_loss(front_run: &BlockTrade, victim: &BlockTrade) -> u64 {
+    if front_run.token_amount == 0 {
+        return 0;
+    }
+    let fair_sol_amount =
+        victim.token_amount as u128 * front_run.sol_amount as u128 / front_run.token_amount as u128;
+    if front_run.is_buy {
+        (victim.sol_amount as u128).saturating_sub(fair_sol_amount) as u64
+    } else {
+        fair_sol_amount.saturating_sub(victim.sol_amount as u128) as u64
+    }
+}
+
+fn estimate_attacker_profit(front_run: &BlockTrade, back_run: &BlockTrade) -> i128 {
+    let quantity = front_run.token_amount.min(back_run.token_amount) as u128;
+    if quantity == 0 {
+        return 0;
+    }
+    let front_run_sol = front_run.sol_amount as u128 * quantity / front_run.token_amount as u128;
+    let back_run_sol = back_run.sol_amount as u128 * quantity / back_run.token_amount as u128;
+    if front_run.is_buy {
+        back_run_sol as i128 - front_run_sol as i128
+    } else {
+        front_run_sol as i128 - back_run_sol as i128
+    }
+}
+
+fn is_victim_candidate(front_run: &BlockTrade, back_run: &BlockTrade, trade: &BlockTrade) -> bool {
+    trade.is_buy == front_run.is_buy
+        && trade.wallet != front_run.wallet
+        && trade.transaction_index > front_run.transaction_index
+        && trade.transaction_index < back_run.transaction_index
+}
+
+/// Positions of the (front run, back run) trades of a mint. The back run is the
+/// first opposite trade of the same wallet not closing another front run.
+fn match_attacks(mint_trades: &[&BlockTrade]) -> Vec<(usize, usize)> {
+    let mut attacks = Vec::new();
+    let mut closed_back_runs = vec![false; mint_trades.len()];
+
+    for front_run_position in 0..mint_trades.len() {
+        let front_run = mint_trades[front_run_position];
+        let Some(back_run_position) =
+            (front_run_position + 1..mint_trades.len()).find(|&position| {
+                let trade = mint_trades[position];
+                !closed_back_runs[position]
+                    && trade.wallet == front_run.wallet
+                    && trade.is_buy != front_run.is_buy
+                    && trade.transaction_index > front_run.transaction_index
+            })
+        else {
+            continue;
+        };
+        let back_run = mint_trades[back_run_position];
+
+        if mint_trades[front_run_position + 1..back_run_position]
+            .iter()
+            .any(|trade| is_victim_candidate(front_run, back_run, trade))
+        {
+            closed_back_runs[back_run_position] = true;
+            attacks.push((front_run_position, back_run_position));
+        }
+    }
+
+    attacks
+}
+
+/// The trades of other wallets in the direction of the front run, between the
+/// front and the back run, are the victims. The trades of the attacks are not
+/// victims, and a victim inside nested attacks belongs to the innermost one.
+pub fn detect_sandwiches<'a>(trades: &[BlockTrade<'a>]) -> Vec<Sandwich<'a>> {
+    let mut trades_by_mint: HashMap<(IndexerEventSource, &str), Vec<&BlockTrade>> = HashMap::new();
+    for trade in trades {
+        trades_by_mint
+            .entry((trade.source, trade.mint))
+            .or_default()
+            .push(trade);
+    }
+
+    let mut sandwiches = Vec::new();
+    for mint_trades in trades_by_mint.values() {
+        let attacks = match_attacks(mint_trades);
+        let attack_positions: HashSet<usize> = attacks
+            .iter()
+            .flat_map(|&(front_run_position, back_run_position)| {
+                [front_run_position, back_run_position]
+            })
+            .collect();
+
+        for (victim_position, victim) in mint_trades.iter().enumerate() {
+            if attack_positions.contains(&victim_position) {
+                continue;
+            }
+            // the attacks are ordered by front run, the last one is the innermost
+            let Some(&(front_run_position, back_run_position)) =
+                attacks
+                    .iter()
+                    .rev()
+                    .find(|&&(front_run_position, back_run_position)| {
+                        front_run_position < victim_position
+                            && victim_position < back_run_position
+                            && is_victim_candidate(
+                                mint_trades[front_run_position],
+                                mint_trades[back_run_position],
+                                victim,
+                            )
+                    })
+            else {
+                continue;
+            };
+            let front_run = mint_trades[front_run_position];
+            let back_run = mint_trades[back_run_position];
+
+            sandwiches.push(Sandwich {
+                front_run: front_run.clone(),
+                victim: (*victim).clone(),
+                back_run: back_run.clone(),
+                victim_loss: estimate_victim_loss(front_run, victim),
+                attacker_profit: estimate_attacker_profit(front_run, back_run),
+            });
+        }
+    }
+
+    sandwiches.sort_by_key(|sandwich| sandwich.victim.transaction_index);
+    sandwiches
+}
+
+/// `pool_registry` must already have the pools of the block's Raydium trades.
+pub fn save_sandwiches(
+    transaction: &mut Transaction,
+    events: &[Event],
+    block_data: &BlockData,
+    pool_registry: &PoolRegistry,
+) -> Result<u64, postgres::Error> {
+    let trades = read_block_trades(events, pool_registry);
+    let sandwiches = detect_sandwiches(&trades);
+    if sandwiches.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO sandwiches (
+            slot,
+            source,
+            mint,
+            attacker,
+            victim,
+            front_run_transaction_id,
+            victim_transaction_id,
+            back_run_transaction_id,
+            victim_token_amount,
+            victim_sol_amount,
+            victim_loss,
+            attacker_profit,
+            created
+        ) VALUES {}
+        ON CONFLICT (victim_transaction_id, mint) DO NOTHING;
+    ",
+        build_values_placeholders(
+            sandwiches.len(),
+            &[
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "::text::numeric",
+                "",
+            ],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(sandwiches.len() * 13);
+    for sandwich in &sandwiches {
+        values.push(Box::new(block_data.slot));
+        values.push(Box::new(sandwich.victim.source.name().to_string()));
+        values.push(Box::new(sandwich.victim.mint.to_string()));
+        values.push(Box::new(sandwich.front_run.wallet.to_string()));
+        values.push(Box::new(sandwich.victim.wallet.to_string()));
+        values.push(Box::new(sandwich.front_run.signature.to_string()));
+        values.push(Box::new(sandwich.victim.signature.to_string()));
+        values.push(Box::new(sandwich.back_run.signature.to_string()));
+        values.push(Box::new(sandwich.victim.token_amount.to_string()));
+        values.push(Box::new(sandwich.victim.sol_amount.to_string()));
+        values.push(Box::new(sandwich.victim_loss.to_string()));
+        values.push(Box::new(sandwich.attacker_profit.to_string()));
+        values.push(Box::new(block_data.block_time as i32));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting sandwiches: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_trade<'a>(
+        wallet: &'a str,
+        transaction_index: u32,
+        is_buy: bool,
+        token_amount: u64,
+        sol_amount: u64,
+    ) -> BlockTrade<'a> {
+        BlockTrade {
+            source: IndexerEventSource::Pumpfun,
+            mint: "mint",
+            wallet,
+            signature: "signature",
+            transaction_index,
+            is_buy,
+            token_amount,
+            sol_amount,
+        }
+    }
+
+    #[test]
+    fn test_detect_sandwiches() {
+        let trades = [
+            build_trade("attacker", 1, true, 1000, 100),
+            build_trade("victim", 2, true, 900, 135),
+            build_trade("seller", 3, false, 100, 12),
+            build_trade("attacker", 4, false, 1000, 120),
+            // no victim between
+            build_trade("wallet", 5, true, 1000, 100),
+            build_trade("wallet", 6, false, 1000, 110),
+            BlockTrade {
+                mint: "other",
+                ..build_trade("victim", 7, true, 1000, 100)
+            },
+        ];
+
+        let sandwiches = detect_sandwiches(&trades);
+        assert_eq!(sandwiches.len(), 1);
+        let sandwich = &sandwiches[0];
+        assert_eq!(sandwich.front_run.transaction_index, 1);
+        assert_eq!(sandwich.victim.wallet, "victim");
+        assert_eq!(sandwich.back_run.transaction_index, 4);
+        // 900 tokens at the front run price cost 90
+        assert_eq!(sandwich.victim_loss, 45);
+        assert_eq!(sandwich.attacker_profit, 20);
+    }
+
+    #[test]
+    fn test_detect_sandwiches_multiple_victims() {
+        let trades = [
+            build_trade("attacker", 1, true, 1000, 100),
+            build_trade("victim1", 2, true, 1000, 120),
+            build_trade("victim2", 3, true, 1000, 130),
+            build_trade("attacker", 4, false, 1000, 125),
+        ];
+
+        let sandwiches = detect_sandwiches(&trades);
+        assert_eq!(sandwiches.len(), 2);
+        assert_eq!(sandwiches[0].victim.wallet, "victim1");
+        assert_eq!(sandwiches[0].victim_loss, 20);
+        assert_eq!(sandwiches[1].victim.wallet, "victim2");
+        assert_eq!(sandwiches[1].victim_loss, 30);
+        assert!(sandwiches
+            .iter()
+            .all(|sandwich| sandwich.attacker_profit == 25));
+    }
+
+    #[test]
+    fn test_detect_sandwiches_overlapping_attackers() {
+        let trades = [
+            build_trade("outer", 1, true, 1000, 100),
+            build_trade("inner", 2, true, 1000, 110),
+            build_trade("victim1", 3, true, 1000, 130),
+            build_trade("inner", 4, false, 1000, 125),
+            build_trade("victim2", 5, true, 1000, 140),
+            build_trade("outer", 6, false, 1000, 135),
+        ];
+
+        let sandwiches = detect_sandwiches(&trades);
+        assert_eq!(sandwiches.len(), 2);
+        // the buy of the inner attacker is not a victim of the outer one
+        assert_eq!(sandwiches[0].victim.wallet, "victim1");
+        assert_eq!(sandwiches[0].front_run.wallet, "inner");
+        assert_eq!(sandwiches[0].victim_loss, 20);
+        assert_eq!(sandwiches[1].victim.wallet, "victim2");
+        assert_eq!(sandwiches[1].front_run.wallet, "outer");
+        assert_eq!(sandwiches[1].back_run.transaction_index, 6);
+    }
+
+    #[test]
+    fn test_detect_sell_sandwiches() {
+        let trades = [
+            build_trade("attacker", 1, false, 1000, 100),
+            build_trade("victim", 2, false, 1000, 80),
+            build_trade("attacker", 3, true, 1000, 85),
+        ];
+
+        let sandwiches = detect_sandwiches(&trades);
+        assert_eq!(sandwiches.len(), 1);
+        assert_eq!(sandwiches[0].victim_loss, 20);
+        assert_eq!(sandwiches[0].attacker_profit, 15);
+    }
+}
@@ -0,0 +1,210 @@
+// SOL/USD rate of every block, from the reserves of a Raydium SOL/USDC pool
+// after its last swap of the block. The blocks without swaps of the pool keep
+// the rate of the previous one.
+
+use postgres::{GenericClient, Transaction};
+use std::sync::LazyLock;
+
+use crate::parsing::WSOL_MINT;
+use crate::raydium::pool_registry::{PoolRegistry, RaydiumPool};
+use crate::raydium::RaydiumTradeFunctionCallEventMeta;
+use crate::storage::BlockData;
+use crate::{Event, GenericEventType};
+
+// Raydium v4 SOL/USDC pool, coin is SOL and pc is USDC. SOL_USDC_POOL replaces
+// it with another pool of the same sides.
+pub const SOL_USDC_POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fJ7HmVrrhSpEAQuo";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const USDC_UNITS: f64 = 1_000_000.0;
+
+pub static REFERENCE_POOL: LazyLock<String> =
+    LazyLock::new(|| std::env::var("SOL_USDC_POOL").unwrap_or_else(|_| SOL_USDC_POOL.to_string()));
+
+/// The rate is only derived from a pool with SOL as coin and USDC as pc.
+pub fn is_sol_usdc_pool(pool: &RaydiumPool) -> bool {
+    pool.coin_mint == WSOL_MINT && pool.pc_mint == USDC_MINT
+}
+
+/// USD value of an amount in SOL.
+pub fn sol_to_usd(sol_amount: f64, sol_price: Option<f64>) -> Option<f64> {
+    sol_price.map(|sol_price| sol_amount * sol_price)
+}
+
+pub fn lamports_to_usd(lamports: u64, sol_price: Option<f64>) -> Option<f64> {
+    sol_to_usd(lamports as f64 / LAMPORTS_PER_SOL, sol_price)
+}
+
+/// Price of SOL from the (coin, pc) reserves of the reference pool.
+pub fn compute_sol_price(pool_coin: u64, pool_pc: u64) -> Option<f64> {
+    if pool_coin == 0 {
+        return None;
+    }
+    Some((pool_pc as f64 / USDC_UNITS) / (pool_coin as f64 / LAMPORTS_PER_SOL))
+}
+
+#[derive(Debug, Default)]
+pub struct SolPriceTracker {
+    // slot of the last swap of the reference pool and the rate after it
+    last_price: Option<(i64, f64)>,
+    // rate of the block being stored, kept by `commit`
+    pending_price: Option<(i64, f64)>,
+}
+
+impl SolPriceTracker {
+    /// Starts from the last stored rate.
+    pub fn load(client: &mut impl GenericClient) -> Result<Self, postgres::Error> {
+        let select_query = "SELECT slot, price FROM sol_price ORDER BY slot DESC LIMIT 1;";
+        let row = match client.query_opt(select_query, &[]) {
+            Ok(row) => row,
+            Err(err) => {
+                println!("Error reading sol_price: {:?}", err);
+                return Err(err);
+            }
+        };
+
+        Ok(SolPriceTracker {
+            last_price: row.map(|row| (row.get("slot"), row.get("price"))),
+            pending_price: None,
+        })
+    }
+
+    /// Reads the swaps of the reference pool in a block, returns the rate of
+    /// the block. The pool must be in the registry. The rate of the block is
+    /// staged until `commit`.
+    pub fn update(
+        &mut self,
+        events: &[Event],
+        slot: i64,
+        pool_registry: &PoolRegistry,
+    ) -> Option<f64> {
+        if !pool_registry
+            .get(&REFERENCE_POOL)
+            .is_some_and(is_sol_usdc_pool)
+        {
+            return self.price();
+        }
+
+        let last_swap = events.iter().rev().find_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            if function_call_event.event_type != GenericEventType::Trade {
+                return None;
+            }
+            let event_meta = function_call_event
+                .event_meta
+                .as_any()
+                .downcast_ref::<RaydiumTradeFunctionCallEventMeta>()?;
+            if event_meta.failed_transaction || event_meta.accounts.amm != *REFERENCE_POOL {
+                return None;
+            }
+            event_meta.swap.as_ref()
+        });
+
+        self.pending_price = last_swap
+            .and_then(|swap| compute_sol_price(swap.pool_coin, swap.pool_pc))
+            .map(|price| (slot, price));
+        self.price()
+    }
+
+    fn current_price(&self) -> Option<(i64, f64)> {
+        self.pending_price.or(self.last_price)
+    }
+
+    pub fn price(&self) -> Option<f64> {
+        self.current_price().map(|(_, price)| price)
+    }
+
+    /// The rate is stored in the slots with swaps of the reference pool.
+    pub fn is_updated_at(&self, slot: i64) -> bool {
+        self.current_price()
+            .is_some_and(|(price_slot, _)| price_slot == slot)
+    }
+
+    pub fn commit(&mut self) {
+        if let Some(pending_price) = self.pending_price.take() {
+            self.last_price = Some(pending_price);
+        }
+    }
+
+    pub fn rollback(&mut self) {
+        self.pending_price = None;
+    }
+}
+
+pub fn save_sol_price(
+    transaction: &mut Transaction,
+    block_data: &BlockData,
+    tracker: &SolPriceTracker,
+) -> Result<u64, postgres::Error> {
+    let Some(price) = tracker.price() else {
+        return Ok(0);
+    };
+    if !tracker.is_updated_at(block_data.slot) {
+        return Ok(0);
+    }
+
+    let insert_query = "
+        INSERT INTO sol_price (slot, price, created)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (slot) DO NOTHING;
+    ";
+
+    match transaction.execute(
+        insert_query,
+        &[&block_data.slot, &price, &(block_data.block_time as i32)],
+    ) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting sol_price: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_sol_price() {
+        // 1000 SOL and 150000 USDC
+        let price = compute_sol_price(1_000 * 1_000_000_000, 150_000 * 1_000_000).unwrap();
+        assert!((price - 150.0).abs() < 1e-9);
+        assert_eq!(compute_sol_price(0, 1), None);
+
+        assert_eq!(lamports_to_usd(500_000_000, Some(150.0)), Some(75.0));
+        assert_eq!(lamports_to_usd(500_000_000, None), None);
+
+        let mut pool_registry = PoolRegistry::default();
+        let mut pool = RaydiumPool {
+            amm: REFERENCE_POOL.clone(),
+            coin_mint: WSOL_MINT.to_string(),
+            pc_mint: USDC_MINT.to_string(),
+            coin_vault: "coin_vault".to_string(),
+            pc_vault: "pc_vault".to_string(),
+            coin_decimals: Some(9),
+            pc_decimals: Some(6),
+        };
+        assert!(is_sol_usdc_pool(&pool));
+        pool.pc_mint = "other".to_string();
+        assert!(!is_sol_usdc_pool(&pool));
+        pool_registry.insert(pool);
+
+        let mut tracker = SolPriceTracker::default();
+        assert_eq!(tracker.update(&[], 10, &pool_registry), None);
+        tracker.last_price = Some((10, 150.0));
+        assert_eq!(tracker.update(&[], 11, &pool_registry), Some(150.0));
+        assert!(tracker.is_updated_at(10));
+        assert!(!tracker.is_updated_at(11));
+
+        tracker.pending_price = Some((12, 160.0));
+        assert_eq!(tracker.price(), Some(160.0));
+        assert!(tracker.is_updated_at(12));
+        tracker.rollback();
+        assert_eq!(tracker.price(), Some(150.0));
+        tracker.pending_price = Some((12, 160.0));
+        tracker.commit();
+        assert_eq!(tracker.last_price, Some((12, 160.0)));
+    }
+}
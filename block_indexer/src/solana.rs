@@ -0,0 +1,362 @@
+// use as reference: js_src/src/parsing/protocol/solana/solana.parser.ts
+
+use serde_json::Value;
+use solana_sdk::bs58;
+use std::any::Any;
+
+use crate::compute_budget::TransactionFees;
+use crate::parsing::{get_address_as_string, get_address_index, is_failed_transaction};
+use crate::transaction_log::InstructionLogs;
+use crate::{
+    Event, FunctionCallEvent, FunctionCallEventMeta, FunctionCallInstructionData, GenericEventType,
+    IndexerEventSource,
+};
+
+mod balance_change;
+mod decoder;
+pub mod jito;
+pub mod storage;
+pub use balance_change::SplTokenBalanceChange;
+use balance_change::TransactionTokenBalances;
+use decoder::{decode_spl_instruction_data, decode_system_instruction_data};
+pub use decoder::{SplTransferCheckedValues, SplTransferValues, SystemTransferValues};
+use jito::is_jito_tip_account;
+
+pub const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const SPL_TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+pub struct SolanaParser;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum SolanaInstructionData {
+    SystemTransfer(SystemTransferValues),
+    SplTransfer(SplTransferValues),
+    SplTransferChecked(SplTransferCheckedValues),
+    Unknown,
+}
+
+impl FunctionCallInstructionData for SolanaInstructionData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FunctionCallInstructionData for SplTokenBalanceChange {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct SolTransferFunctionCallEventMeta {
+    pub block_time: u64,
+    pub from_address: String,
+    pub to_address: String,
+    // sent to a Jito tip account
+    pub is_jito_tip: bool,
+    pub failed_transaction: bool,
+}
+
+impl FunctionCallEventMeta for SolTransferFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// the addresses are the owners of the token accounts
+#[allow(dead_code)]
+pub struct SplTokenTransferFunctionCallEventMeta {
+    pub block_time: u64,
+    pub from_address: String,
+    pub to_address: String,
+    pub from_token_account: String,
+    pub to_token_account: String,
+    pub authority: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub failed_transaction: bool,
+}
+
+impl FunctionCallEventMeta for SplTokenTransferFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[allow(dead_code)]
+pub struct SplTokenBalanceChangeFunctionCallEventMeta {
+    pub block_time: u64,
+    pub failed_transaction: bool,
+}
+
+impl FunctionCallEventMeta for SplTokenBalanceChangeFunctionCallEventMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn get_signature(transaction_obj: &serde_json::Map<String, serde_json::Value>) -> String {
+    transaction_obj
+        .get("transaction")
+        .unwrap()
+        .get("signatures")
+        .unwrap()
+        .get(0)
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+impl SolanaParser {
+    pub fn new() -> Self {
+        SolanaParser
+    }
+
+    pub fn parse_instruction(
+        &self,
+        transaction_obj: &serde_json::Map<String, serde_json::Value>,
+        instruction: &Value,
+        addresses: &[String],
+        block_time: u64,
+        _instruction_logs: Option<&InstructionLogs>,
+    ) -> Vec<Event> {
+        let mut events: Vec<Event> = Vec::new();
+
+        let program_id_index = instruction.get("programIdIndex").unwrap().as_u64().unwrap();
+        let program_id = addresses.get(program_id_index as usize).unwrap();
+
+        let is_spl_program =
+            program_id == SPL_TOKEN_PROGRAM_ID || program_id == SPL_TOKEN_2022_PROGRAM_ID;
+        if !is_spl_program && program_id != SYSTEM_PROGRAM_ID {
+            return events;
+        }
+
+        let instruction_data_str = instruction.get("data").unwrap().as_str().unwrap();
+        let instruction_data_bytes = bs58::decode(instruction_data_str).into_vec().unwrap();
+
+        let decoded_instruction = if is_spl_program {
+            decode_spl_instruction_data(&instruction_data_bytes)
+        } else {
+            decode_system_instruction_data(&instruction_data_bytes)
+        };
+        let failed_transaction = is_failed_transaction(transaction_obj);
+
+        let (event_type, event_meta): (GenericEventType, Box<dyn FunctionCallEventMeta>) =
+            match &decoded_instruction {
+                SolanaInstructionData::SystemTransfer(_) => {
+                    let to_address = get_address_as_string(1, addresses, instruction);
+                    (
+                        GenericEventType::SolTransfer,
+                        Box::new(SolTransferFunctionCallEventMeta {
+                            block_time,
+                            from_address: get_address_as_string(0, addresses, instruction),
+                            is_jito_tip: is_jito_tip_account(&to_address),
+                            to_address,
+                            failed_transaction,
+                        }),
+                    )
+                }
+                // 0 - source, 1 - destination, 2 - authority
+                SolanaInstructionData::SplTransfer(_) => {
+                    let Some(event_meta) = build_spl_transfer_event_meta(
+                        transaction_obj,
+                        instruction,
+                        addresses,
+                        block_time,
+                        (0, 1, 2),
+                        failed_transaction,
+                    ) else {
+                        return events;
+                    };
+                    (GenericEventType::SplTokenTransfer, Box::new(event_meta))
+                }
+                // 0 - source, 1 - mint, 2 - destination, 3 - authority
+                SolanaInstructionData::SplTransferChecked(_) => {
+                    let Some(event_meta) = build_spl_transfer_event_meta(
+                        transaction_obj,
+                        instruction,
+                        addresses,
+                        block_time,
+                        (0, 2, 3),
+                        failed_transaction,
+                    ) else {
+                        return events;
+                    };
+                    (GenericEventType::SplTokenTransfer, Box::new(event_meta))
+                }
+                SolanaInstructionData::Unknown => return events,
+            };
+
+        events.push(Event::FunctionCall(FunctionCallEvent {
+            source: IndexerEventSource::Solana,
+            event_type,
+            slot: 0,
+            transaction_index: 0,
+            instruction_index: 0,
+            fees: TransactionFees::default(),
+            signature: get_signature(transaction_obj),
+            event_obj: Box::new(decoded_instruction),
+            event_meta,
+        }));
+
+        events
+    }
+
+    /// Emits a balance change for every owner and mint whose token balance changed
+    /// in the transaction. Failed transactions don't change balances.
+    pub fn parse_transaction(
+        &self,
+        transaction_obj: &serde_json::Map<String, serde_json::Value>,
+        block_time: u64,
+    ) -> Vec<Event> {
+        if is_failed_transaction(transaction_obj) {
+            return Vec::new();
+        }
+
+        let meta = transaction_obj.get("meta").unwrap();
+        let signature = get_signature(transaction_obj);
+
+        TransactionTokenBalances::read(meta)
+            .changes()
+            .into_iter()
+            .map(|change| {
+                Event::FunctionCall(FunctionCallEvent {
+                    source: IndexerEventSource::Solana,
+                    event_type: GenericEventType::SplTokenBalanceChange,
+                    slot: 0,
+                    transaction_index: 0,
+                    instruction_index: 0,
+                    fees: TransactionFees::default(),
+                    signature: signature.clone(),
+                    event_obj: Box::new(change),
+                    event_meta: Box::new(SplTokenBalanceChangeFunctionCallEventMeta {
+                        block_time,
+                        failed_transaction: false,
+                    }),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The token accounts are resolved to their owners and mint with the token balances
+/// of the transaction, transfers of accounts without balances are discarded.
+fn build_spl_transfer_event_meta(
+    transaction_obj: &serde_json::Map<String, serde_json::Value>,
+    instruction: &Value,
+    addresses: &[String],
+    block_time: u64,
+    (source_index, destination_index, authority_index): (u64, u64, u64),
+    failed_transaction: bool,
+) -> Option<SplTokenTransferFunctionCallEventMeta> {
+    let accounts_count = instruction
+        .get("accounts")
+        .or_else(|| instruction.get("accountKeyIndexes"))
+        .and_then(|accounts| accounts.as_array())
+        .map(|accounts| accounts.len() as u64)?;
+    if accounts_count <= authority_index {
+        return None;
+    }
+
+    let token_balances = TransactionTokenBalances::read(transaction_obj.get("meta").unwrap());
+    let source_balance = token_balances.find(get_address_index(source_index, instruction));
+    let destination_balance =
+        token_balances.find(get_address_index(destination_index, instruction))?;
+
+    let authority = get_address_as_string(authority_index, addresses, instruction);
+    // the source token account may not have balances (e.g. closed before the transaction)
+    let from_address = source_balance
+        .map(|balance| balance.owner.clone())
+        .unwrap_or_else(|| authority.clone());
+    let mint_balance = source_balance.unwrap_or(destination_balance);
+
+    Some(SplTokenTransferFunctionCallEventMeta {
+        block_time,
+        from_address,
+        to_address: destination_balance.owner.clone(),
+        from_token_account: get_address_as_string(source_index, addresses, instruction),
+        to_token_account: get_address_as_string(destination_index, addresses, instruction),
+        authority,
+        mint: mint_balance.mint.clone(),
+        decimals: mint_balance.decimals,
+        failed_transaction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_spl_transfer_checked() {
+        let addresses: Vec<String> = vec![
+            "wallet".to_string(),
+            "source".to_string(),
+            "destination".to_string(),
+            "token".to_string(),
+            SPL_TOKEN_PROGRAM_ID.to_string(),
+        ];
+
+        let mut instruction_data = vec![12u8];
+        instruction_data.extend_from_slice(&250u64.to_le_bytes());
+        instruction_data.push(6);
+
+        let instruction = json!({
+            "programIdIndex": 4,
+            "accounts": [1, 3, 2, 0],
+            "data": bs58::encode(instruction_data).into_string(),
+        });
+        let balance = |account_index: u64, owner: &str, amount: &str| {
+            json!({
+                "accountIndex": account_index,
+                "owner": owner,
+                "mint": "token",
+                "uiTokenAmount": { "amount": amount, "decimals": 6 },
+            })
+        };
+        let transaction_obj = json!({
+            "transaction": { "signatures": ["signature"] },
+            "meta": {
+                "err": null,
+                "preTokenBalances": [balance(1, "wallet", "1000")],
+                "postTokenBalances": [balance(1, "wallet", "750"), balance(2, "receiver", "250")],
+            },
+        });
+        let transaction_obj = transaction_obj.as_object().unwrap();
+
+        let events = SolanaParser::new().parse_instruction(
+            transaction_obj,
+            &instruction,
+            &addresses,
+            1739711240,
+            None,
+        );
+        assert_eq!(events.len(), 1);
+        let Event::FunctionCall(event) = &events[0];
+        assert_eq!(event.event_type, GenericEventType::SplTokenTransfer);
+        let event_meta = event
+            .event_meta
+            .as_any()
+            .downcast_ref::<SplTokenTransferFunctionCallEventMeta>()
+            .unwrap();
+        assert_eq!(event_meta.from_address, "wallet");
+        assert_eq!(event_meta.to_address, "receiver");
+        assert_eq!(event_meta.to_token_account, "destination");
+        assert_eq!(event_meta.mint, "token");
+        let Some(SolanaInstructionData::SplTransferChecked(values)) = event
+            .event_obj
+            .as_any()
+            .downcast_ref::<SolanaInstructionData>(
+        ) else {
+            panic!("expected TransferChecked values");
+        };
+        assert_eq!(values.amount, 250);
+
+        let events = SolanaParser::new().parse_transaction(transaction_obj, 1739711240);
+        assert_eq!(events.len(), 2);
+    }
+}
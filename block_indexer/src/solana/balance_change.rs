@@ -0,0 +1,166 @@
+// Token balances of the transaction (preTokenBalances/postTokenBalances of the meta)
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct TokenBalance {
+    pub account_index: u64,
+    pub owner: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub amount: u64,
+}
+
+/// Balance of the tokens of a mint held by an owner in the token accounts that
+/// the transaction touched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplTokenBalanceChange {
+    pub owner: String,
+    pub mint: String,
+    pub decimals: u8,
+    pub old_amount: u64,
+    pub new_amount: u64,
+}
+
+// old blocks don't have the owner of the token accounts
+fn token_balance_from_value(balance: &Value) -> Option<TokenBalance> {
+    let ui_token_amount = balance.get("uiTokenAmount")?;
+    Some(TokenBalance {
+        account_index: balance.get("accountIndex")?.as_u64()?,
+        owner: balance.get("owner")?.as_str()?.to_string(),
+        mint: balance.get("mint")?.as_str()?.to_string(),
+        decimals: ui_token_amount.get("decimals")?.as_u64()? as u8,
+        amount: ui_token_amount
+            .get("amount")?
+            .as_str()?
+            .parse::<u64>()
+            .ok()?,
+    })
+}
+
+fn read_token_balances(meta: &Value, key: &str) -> Vec<TokenBalance> {
+    meta.get(key)
+        .and_then(|balances| balances.as_array())
+        .map(|balances| {
+            balances
+                .iter()
+                .filter_map(token_balance_from_value)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub struct TransactionTokenBalances {
+    pub pre_balances: Vec<TokenBalance>,
+    pub post_balances: Vec<TokenBalance>,
+}
+
+impl TransactionTokenBalances {
+    pub fn read(meta: &Value) -> Self {
+        TransactionTokenBalances {
+            pre_balances: read_token_balances(meta, "preTokenBalances"),
+            post_balances: read_token_balances(meta, "postTokenBalances"),
+        }
+    }
+
+    /// Owner and mint of a token account, the post balance is preferred because
+    /// the account may have been created by the transaction.
+    pub fn find(&self, account_index: u64) -> Option<&TokenBalance> {
+        self.post_balances
+            .iter()
+            .chain(self.pre_balances.iter())
+            .find(|balance| balance.account_index == account_index)
+    }
+
+    /// Sums the balances of the token accounts by (owner, mint) and returns the ones
+    /// that changed, in the order the token accounts appear in the transaction.
+    pub fn changes(&self) -> Vec<SplTokenBalanceChange> {
+        let mut changes: Vec<SplTokenBalanceChange> = Vec::new();
+        let mut positions: HashMap<(String, String), usize> = HashMap::new();
+
+        let mut add_balance = |balance: &TokenBalance, is_post: bool| {
+            let key = (balance.owner.clone(), balance.mint.clone());
+            let position = *positions.entry(key).or_insert_with(|| {
+                changes.push(SplTokenBalanceChange {
+                    owner: balance.owner.clone(),
+                    mint: balance.mint.clone(),
+                    decimals: balance.decimals,
+                    old_amount: 0,
+                    new_amount: 0,
+                });
+                changes.len() - 1
+            });
+            let change = &mut changes[position];
+            if is_post {
+                change.new_amount += balance.amount;
+            } else {
+                change.old_amount += balance.amount;
+            }
+        };
+
+        let mut balances: Vec<(&TokenBalance, bool)> = self
+            .pre_balances
+            .iter()
+            .map(|balance| (balance, false))
+            .chain(self.post_balances.iter().map(|balance| (balance, true)))
+            .collect();
+        balances.sort_by_key(|(balance, _)| balance.account_index);
+        for (balance, is_post) in balances {
+            add_balance(balance, is_post);
+        }
+
+        changes
+            .into_iter()
+            .filter(|change| change.old_amount != change.new_amount)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn build_balance(account_index: u64, owner: &str, mint: &str, amount: &str) -> Value {
+        json!({
+            "accountIndex": account_index,
+            "owner": owner,
+            "mint": mint,
+            "uiTokenAmount": { "amount": amount, "decimals": 6 },
+        })
+    }
+
+    #[test]
+    fn test_balance_changes() {
+        let meta = json!({
+            "preTokenBalances": [
+                build_balance(1, "alice", "token", "100"),
+                build_balance(2, "bob", "token", "50"),
+                build_balance(3, "alice", "other", "7"),
+            ],
+            "postTokenBalances": [
+                build_balance(1, "alice", "token", "60"),
+                build_balance(2, "bob", "token", "50"),
+                build_balance(3, "alice", "other", "7"),
+                // token account created by the transaction
+                build_balance(4, "bob", "token", "40"),
+                // second token account of the same owner and mint
+                build_balance(5, "alice", "token", "10"),
+            ],
+        });
+
+        let token_balances = TransactionTokenBalances::read(&meta);
+        let changes = token_balances.changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].owner, "alice");
+        assert_eq!(changes[0].old_amount, 100);
+        assert_eq!(changes[0].new_amount, 70);
+        assert_eq!(changes[1].owner, "bob");
+        assert_eq!(changes[1].old_amount, 50);
+        assert_eq!(changes[1].new_amount, 90);
+
+        assert_eq!(token_balances.find(4).unwrap().owner, "bob");
+        assert!(token_balances.find(6).is_none());
+    }
+}
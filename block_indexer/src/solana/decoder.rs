@@ -0,0 +1,91 @@
+// use as reference: js_src/src/parsing/protocol/solana/solana.decoder.ts
+// and js_src/src/parsing/protocol/solana/instruction.decoder.ts
+
+use super::SolanaInstructionData;
+
+enum SplDiscriminator {
+    Transfer = 3,
+    TransferChecked = 12,
+}
+
+enum SystemDiscriminator {
+    Transfer = 2,
+}
+
+// discriminator: u8
+// amount: u64
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SplTransferValues {
+    pub amount: u64,
+}
+
+// discriminator: u8
+// amount: u64
+// decimals: u8
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct SplTransferCheckedValues {
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+// instruction: u32
+// lamports: u64
+#[derive(Debug, Clone)]
+pub struct SystemTransferValues {
+    pub lamports: u64,
+}
+
+// same layout for the Token and the Token-2022 programs
+pub fn decode_spl_instruction_data(instruction_data: &[u8]) -> SolanaInstructionData {
+    let Some(discriminator) = instruction_data.first() else {
+        return SolanaInstructionData::Unknown;
+    };
+    let mut instruction_data_mut = &instruction_data[1..];
+
+    if *discriminator == SplDiscriminator::Transfer as u8 {
+        let values = borsh::BorshDeserialize::deserialize(&mut instruction_data_mut)
+            .ok()
+            .map(|amount| SplTransferValues { amount });
+        return match values {
+            Some(values) => SolanaInstructionData::SplTransfer(values),
+            None => SolanaInstructionData::Unknown,
+        };
+    }
+
+    if *discriminator == SplDiscriminator::TransferChecked as u8 {
+        let amount: Option<u64> =
+            borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok();
+        let decimals: Option<u8> =
+            borsh::BorshDeserialize::deserialize(&mut instruction_data_mut).ok();
+        return match (amount, decimals) {
+            (Some(amount), Some(decimals)) => {
+                SolanaInstructionData::SplTransferChecked(SplTransferCheckedValues {
+                    amount,
+                    decimals,
+                })
+            }
+            _ => SolanaInstructionData::Unknown,
+        };
+    }
+
+    SolanaInstructionData::Unknown
+}
+
+pub fn decode_system_instruction_data(instruction_data: &[u8]) -> SolanaInstructionData {
+    let mut instruction_data_mut = instruction_data;
+    let Ok(instruction) = <u32 as borsh::BorshDeserialize>::deserialize(&mut instruction_data_mut)
+    else {
+        return SolanaInstructionData::Unknown;
+    };
+
+    if instruction != SystemDiscriminator::Transfer as u32 {
+        return SolanaInstructionData::Unknown;
+    }
+
+    match borsh::BorshDeserialize::deserialize(&mut instruction_data_mut) {
+        Ok(lamports) => SolanaInstructionData::SystemTransfer(SystemTransferValues { lamports }),
+        Err(_) => SolanaInstructionData::Unknown,
+    }
+}
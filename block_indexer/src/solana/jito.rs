@@ -0,0 +1,109 @@
+// Jito tips: SOL transfers to the tip accounts of the Jito block engine, paid
+// by the transactions sent in bundles.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use super::{SolTransferFunctionCallEventMeta, SolanaInstructionData};
+use crate::{Event, GenericEventType};
+
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+// JITO_TIP_ACCOUNTS can replace the list, comma separated
+static TIP_ACCOUNTS: LazyLock<HashSet<String>> =
+    LazyLock::new(|| match std::env::var("JITO_TIP_ACCOUNTS") {
+        Ok(accounts) => accounts
+            .split(',')
+            .map(|account| account.trim().to_string())
+            .filter(|account| !account.is_empty())
+            .collect(),
+        Err(_) => JITO_TIP_ACCOUNTS
+            .iter()
+            .map(|account| account.to_string())
+            .collect(),
+    });
+
+pub fn is_jito_tip_account(address: &str) -> bool {
+    TIP_ACCOUNTS.contains(address)
+}
+
+/// Lamports tipped by the events of a transaction. A failed transaction
+/// doesn't pay its tips.
+pub fn read_jito_tip(events: &[Event]) -> u64 {
+    events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            if function_call_event.event_type != GenericEventType::SolTransfer {
+                return None;
+            }
+            let event_meta = function_call_event
+                .event_meta
+                .as_any()
+                .downcast_ref::<SolTransferFunctionCallEventMeta>()?;
+            if !event_meta.is_jito_tip || event_meta.failed_transaction {
+                return None;
+            }
+            match function_call_event
+                .event_obj
+                .as_any()
+                .downcast_ref::<SolanaInstructionData>()?
+            {
+                SolanaInstructionData::SystemTransfer(values) => Some(values.lamports),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_budget::TransactionFees;
+    use crate::solana::SystemTransferValues;
+    use crate::{FunctionCallEvent, IndexerEventSource};
+
+    fn build_transfer_event(to_address: &str, lamports: u64, failed_transaction: bool) -> Event {
+        Event::FunctionCall(FunctionCallEvent {
+            source: IndexerEventSource::Solana,
+            event_type: GenericEventType::SolTransfer,
+            slot: 0,
+            transaction_index: 0,
+            instruction_index: 0,
+            fees: TransactionFees::default(),
+            signature: "signature".to_string(),
+            event_obj: Box::new(SolanaInstructionData::SystemTransfer(
+                SystemTransferValues { lamports },
+            )),
+            event_meta: Box::new(SolTransferFunctionCallEventMeta {
+                block_time: 0,
+                from_address: "wallet".to_string(),
+                to_address: to_address.to_string(),
+                is_jito_tip: is_jito_tip_account(to_address),
+                failed_transaction,
+            }),
+        })
+    }
+
+    #[test]
+    fn test_read_jito_tip() {
+        let events = vec![
+            build_transfer_event(JITO_TIP_ACCOUNTS[0], 10_000, false),
+            build_transfer_event("wallet2", 5_000_000, false),
+            build_transfer_event(JITO_TIP_ACCOUNTS[3], 2_000, false),
+        ];
+        assert_eq!(read_jito_tip(&events), 12_000);
+
+        let events = vec![build_transfer_event(JITO_TIP_ACCOUNTS[0], 10_000, true)];
+        assert_eq!(read_jito_tip(&events), 0);
+    }
+}
@@ -0,0 +1,51 @@
+// use as reference: js_src/src/storage/protocol/solana/storage.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::mint_cache::MintCache;
+use crate::storage::{arrange_events_by_type, BlockData, ProtocolSaveResult};
+use crate::{Event, GenericEventType};
+
+mod balance_change;
+mod holders;
+mod jito_tips;
+use balance_change::save_balance_change_events;
+use jito_tips::save_jito_tips;
+
+pub fn save_protocol_events(
+    transaction: &mut Transaction,
+    events: &[&Event],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<ProtocolSaveResult, postgres::Error> {
+    let mut rows_count = 0;
+    let mut events_by_type = HashMap::new();
+
+    for (event_type, events) in arrange_events_by_type(events) {
+        events_by_type.insert(event_type, events.len());
+        match event_type {
+            GenericEventType::SplTokenBalanceChange => {
+                rows_count +=
+                    save_balance_change_events(transaction, &events, block_data, mint_cache)?;
+            }
+            GenericEventType::SolTransfer => {
+                rows_count += save_jito_tips(transaction, &events, block_data)?;
+                // TODO: the transfers table only keeps the transfers of the users' wallets,
+                // which are in the backend database
+            }
+            GenericEventType::SplTokenTransfer => {
+                // TODO: the transfers table only keeps the transfers of the users' wallets,
+                // which are in the backend database
+            }
+            _ => {
+                // TODO: store the rest of the Solana events
+            }
+        }
+    }
+
+    Ok(ProtocolSaveResult {
+        rows_count,
+        events_by_type,
+    })
+}
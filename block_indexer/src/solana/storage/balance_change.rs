@@ -0,0 +1,45 @@
+// use as reference: js_src/src/storage/protocol/solana/balance-change.storage.ts
+
+use postgres::Transaction;
+
+use super::holders::{consolidate_balance_changes, save_holders};
+use crate::mint_cache::MintCache;
+use crate::solana::SplTokenBalanceChange;
+use crate::storage::BlockData;
+use crate::Event;
+
+pub fn save_balance_change_events(
+    transaction: &mut Transaction,
+    balance_change_events: &[&Event],
+    block_data: &BlockData,
+    mint_cache: &mut MintCache,
+) -> Result<u64, postgres::Error> {
+    let balance_changes: Vec<&SplTokenBalanceChange> = balance_change_events
+        .iter()
+        .filter_map(|event| {
+            let Event::FunctionCall(function_call_event) = event;
+            function_call_event
+                .event_obj
+                .as_any()
+                .downcast_ref::<SplTokenBalanceChange>()
+        })
+        .collect();
+
+    if balance_changes.is_empty() {
+        return Ok(0);
+    }
+
+    // only the tokens launched in our protocols are followed
+    let mints: Vec<&str> = balance_changes
+        .iter()
+        .map(|balance_change| balance_change.mint.as_str())
+        .collect();
+    let mints_data = mint_cache.resolve(transaction, &mints)?;
+    if mints_data.is_empty() {
+        return Ok(0);
+    }
+
+    let holders = consolidate_balance_changes(&balance_changes, &mints_data);
+
+    save_holders(transaction, &holders, block_data)
+}
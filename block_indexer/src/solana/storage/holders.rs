@@ -0,0 +1,193 @@
+// use as reference: js_src/src/storage/protocol/solana/holders.storage.ts
+// and js_src/src/storage/protocol/solana/consolidation.ts
+
+use postgres::Transaction;
+use std::collections::HashMap;
+
+use crate::mint_cache::MintData;
+use crate::solana::SplTokenBalanceChange;
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolderBalance<'a> {
+    pub wallet: &'a str,
+    pub mint: &'a str,
+    pub decimals: i32,
+    pub token_amount: u64,
+    pub is_bonding_curve: bool,
+    pub is_developer: bool,
+}
+
+/// Keeps the last balance of every (wallet, mint) of the block. The balance
+/// changes are in the order of the transactions.
+pub fn consolidate_balance_changes<'a>(
+    balance_changes: &[&'a SplTokenBalanceChange],
+    mints_data: &HashMap<String, MintData>,
+) -> Vec<HolderBalance<'a>> {
+    let mut holders: Vec<HolderBalance> = Vec::new();
+    let mut positions: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for balance_change in balance_changes {
+        let Some(mint_data) = mints_data.get(&balance_change.mint) else {
+            continue;
+        };
+        let holder = HolderBalance {
+            wallet: &balance_change.owner,
+            mint: &balance_change.mint,
+            decimals: balance_change.decimals as i32,
+            token_amount: balance_change.new_amount,
+            is_bonding_curve: mint_data.is_bonding_curve(&balance_change.owner),
+            is_developer: mint_data.is_developer(&balance_change.owner),
+        };
+        match positions.get(&(holder.wallet, holder.mint)) {
+            Some(position) => holders[*position] = holder,
+            None => {
+                positions.insert((holder.wallet, holder.mint), holders.len());
+                holders.push(holder);
+            }
+        }
+    }
+
+    holders
+}
+
+/// The amounts are the balances after the block (not deltas), so indexing a
+/// block again leaves the same rows. Older blocks don't overwrite newer balances.
+pub fn save_holders(
+    transaction: &mut Transaction,
+    holders: &[HolderBalance],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let (empty_holders, holders): (Vec<&HolderBalance>, Vec<&HolderBalance>) =
+        holders.iter().partition(|holder| holder.token_amount == 0);
+
+    let mut rows_count = 0;
+
+    if !holders.is_empty() {
+        let insert_query = format!(
+            "
+            INSERT INTO holders (
+                wallet,
+                mint,
+                decimals,
+                is_bonding_curve,
+                is_developer,
+                token_amount,
+                updated
+            ) VALUES {}
+            ON CONFLICT (wallet, mint) DO UPDATE
+            SET
+                token_amount = EXCLUDED.token_amount,
+                decimals = EXCLUDED.decimals,
+                is_bonding_curve = EXCLUDED.is_bonding_curve,
+                is_developer = EXCLUDED.is_developer,
+                updated = EXCLUDED.updated
+            WHERE holders.updated IS NULL OR holders.updated <= EXCLUDED.updated;
+        ",
+            build_values_placeholders(holders.len(), &["", "", "", "", "", "::text::numeric", ""])
+        );
+
+        let mut values: Vec<SqlValue> = Vec::with_capacity(holders.len() * 7);
+        for holder in &holders {
+            values.push(Box::new(holder.wallet.to_string()));
+            values.push(Box::new(holder.mint.to_string()));
+            values.push(Box::new(holder.decimals));
+            values.push(Box::new(holder.is_bonding_curve));
+            values.push(Box::new(holder.is_developer));
+            values.push(Box::new(holder.token_amount.to_string()));
+            values.push(Box::new(block_data.block_time as i32));
+        }
+
+        rows_count += match execute_with_values(transaction, &insert_query, &values) {
+            Ok(count) => count,
+            Err(err) => {
+                println!("Error inserting/updating holders: {:?}", err);
+                return Err(err);
+            }
+        };
+    }
+
+    if !empty_holders.is_empty() {
+        let delete_query = format!(
+            "
+            DELETE FROM holders
+            USING (VALUES {}) AS empty_holders (wallet, mint, updated)
+            WHERE holders.wallet = empty_holders.wallet
+                AND holders.mint = empty_holders.mint
+                AND (holders.updated IS NULL OR holders.updated <= empty_holders.updated);
+        ",
+            build_values_placeholders(empty_holders.len(), &["::varchar", "::varchar", "::int"])
+        );
+
+        let mut values: Vec<SqlValue> = Vec::with_capacity(empty_holders.len() * 3);
+        for holder in &empty_holders {
+            values.push(Box::new(holder.wallet.to_string()));
+            values.push(Box::new(holder.mint.to_string()));
+            values.push(Box::new(block_data.block_time as i32));
+        }
+
+        rows_count += match execute_with_values(transaction, &delete_query, &values) {
+            Ok(count) => count,
+            Err(err) => {
+                println!("Error deleting holders: {:?}", err);
+                return Err(err);
+            }
+        };
+    }
+
+    Ok(rows_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IndexerEventSource;
+
+    fn build_change(
+        owner: &str,
+        mint: &str,
+        old_amount: u64,
+        new_amount: u64,
+    ) -> SplTokenBalanceChange {
+        SplTokenBalanceChange {
+            owner: owner.to_string(),
+            mint: mint.to_string(),
+            decimals: 6,
+            old_amount,
+            new_amount,
+        }
+    }
+
+    #[test]
+    fn test_consolidate_balance_changes() {
+        let mints_data = HashMap::from([(
+            "token".to_string(),
+            MintData {
+                mint: "token".to_string(),
+                protocol: IndexerEventSource::Pumpfun,
+                bonding_curve: Some("curve".to_string()),
+                developer: Some("dev".to_string()),
+                create_event_slot: Some(1),
+            },
+        )]);
+
+        let balance_changes = [
+            build_change("curve", "token", 1000, 900),
+            build_change("dev", "token", 0, 100),
+            build_change("alice", "other", 0, 5),
+            build_change("curve", "token", 900, 950),
+            build_change("dev", "token", 100, 50),
+        ];
+        let balance_changes: Vec<&SplTokenBalanceChange> = balance_changes.iter().collect();
+
+        let holders = consolidate_balance_changes(&balance_changes, &mints_data);
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].wallet, "curve");
+        assert_eq!(holders[0].token_amount, 950);
+        assert!(holders[0].is_bonding_curve);
+        assert!(!holders[0].is_developer);
+        assert_eq!(holders[1].wallet, "dev");
+        assert_eq!(holders[1].token_amount, 50);
+        assert!(holders[1].is_developer);
+    }
+}
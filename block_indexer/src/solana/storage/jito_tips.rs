@@ -0,0 +1,92 @@
+// Jito tips paid in a block, to separate the bundled transactions from the
+// rest.
+
+use postgres::Transaction;
+use std::collections::HashSet;
+
+use crate::solana::{SolTransferFunctionCallEventMeta, SolanaInstructionData};
+use crate::storage::BlockData;
+use crate::Event;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockJitoTips {
+    pub transaction_count: i32,
+    pub tip_count: i32,
+    pub total_lamports: u64,
+    pub max_lamports: u64,
+}
+
+pub fn sum_jito_tips(sol_transfer_events: &[&Event]) -> BlockJitoTips {
+    let mut block_tips = BlockJitoTips::default();
+    let mut transactions = HashSet::new();
+
+    for event in sol_transfer_events {
+        let Event::FunctionCall(function_call_event) = event;
+        let Some(event_meta) = function_call_event
+            .event_meta
+            .as_any()
+            .downcast_ref::<SolTransferFunctionCallEventMeta>()
+        else {
+            continue;
+        };
+        let Some(SolanaInstructionData::SystemTransfer(values)) = function_call_event
+            .event_obj
+            .as_any()
+            .downcast_ref::<SolanaInstructionData>()
+        else {
+            continue;
+        };
+        if !event_meta.is_jito_tip || event_meta.failed_transaction {
+            continue;
+        }
+
+        transactions.insert(function_call_event.transaction_index);
+        block_tips.tip_count += 1;
+        block_tips.total_lamports += values.lamports;
+        block_tips.max_lamports = block_tips.max_lamports.max(values.lamports);
+    }
+    block_tips.transaction_count = transactions.len() as i32;
+
+    block_tips
+}
+
+pub fn save_jito_tips(
+    transaction: &mut Transaction,
+    sol_transfer_events: &[&Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let block_tips = sum_jito_tips(sol_transfer_events);
+    if block_tips.tip_count == 0 {
+        return Ok(0);
+    }
+
+    let insert_query = "
+        INSERT INTO jito_tips (
+            slot,
+            transaction_count,
+            tip_count,
+            total_lamports,
+            max_lamports,
+            created
+        ) VALUES ($1, $2, $3, $4::text::numeric, $5::text::numeric, $6)
+        ON CONFLICT (slot) DO NOTHING;
+    ";
+
+    match transaction.execute(
+        insert_query,
+        &[
+            &block_data.slot,
+            &block_tips.transaction_count,
+            &block_tips.tip_count,
+            &block_tips.total_lamports.to_string(),
+            &block_tips.max_lamports.to_string(),
+            &(block_data.block_time as i32),
+        ],
+    ) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting jito_tips: {:?}", err);
+            Err(err)
+        }
+    }
+}
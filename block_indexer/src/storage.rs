@@ -0,0 +1,372 @@
+// use as reference: js_src/src/storage/storage.ts
+
+use postgres::types::ToSql;
+use postgres::{Client, Transaction};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::compute_budget::storage::save_block_fees;
+use crate::launch_bundles::funding::FundingRegistry;
+use crate::launch_bundles::save_launch_bundles;
+use crate::mint_cache::{MintCache, MINT_CACHE_CAPACITY};
+use crate::moonshot;
+use crate::pumpfun;
+use crate::raydium;
+use crate::raydium::pool_registry::PoolRegistry;
+use crate::sandwiches::save_sandwiches;
+use crate::sol_price::{is_sol_usdc_pool, save_sol_price, SolPriceTracker, REFERENCE_POOL};
+use crate::solana;
+use crate::token_lifecycle::storage::save_token_states;
+use crate::{Event, GenericEventType, IndexerEventSource};
+
+const UNIQUE_VIOLATION_ERROR_CODE: &str = "23505";
+
+/// The buffers compressed by the storage can fail too, not only the database.
+#[derive(Debug)]
+pub enum StorageError {
+    Postgres(postgres::Error),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Postgres(err) => write!(f, "{}", err),
+            StorageError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Postgres(err) => Some(err),
+            StorageError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<postgres::Error> for StorageError {
+    fn from(err: postgres::Error) -> Self {
+        StorageError::Postgres(err)
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+const GENERIC_EVENT_TYPE_PRIORITY_ORDER: [GenericEventType; 7] = [
+    GenericEventType::TokenMint,
+    GenericEventType::Trade,
+    GenericEventType::Info,
+    GenericEventType::SplTokenBalanceChange,
+    GenericEventType::SplTokenTransfer,
+    GenericEventType::SolTransfer,
+    GenericEventType::Complete,
+];
+
+const INDEXER_EVENT_SOURCE_PRIORITY_ORDER: [IndexerEventSource; 4] = [
+    IndexerEventSource::Pumpfun,
+    IndexerEventSource::Moonshot,
+    IndexerEventSource::Raydium,
+    IndexerEventSource::Solana,
+];
+
+/// State kept in memory between blocks, loaded once at startup.
+pub struct StorageCache {
+    pub raydium_pools: PoolRegistry,
+    pub mints: MintCache,
+    pub funding: FundingRegistry,
+    pub sol_price: SolPriceTracker,
+}
+
+impl StorageCache {
+    pub fn load(client: &mut Client) -> Result<Self, postgres::Error> {
+        let mut raydium_pools = PoolRegistry::load(client)?;
+        raydium_pools.load_missing(client, &[REFERENCE_POOL.as_str()])?;
+        if !raydium_pools
+            .get(&REFERENCE_POOL)
+            .is_some_and(is_sol_usdc_pool)
+        {
+            println!(
+                "Error: SOL_USDC_POOL {} is not a known SOL/USDC pool, no USD values",
+                *REFERENCE_POOL
+            );
+        }
+
+        let mut cache = StorageCache {
+            raydium_pools,
+            mints: MintCache::new(MINT_CACHE_CAPACITY),
+            funding: FundingRegistry::new(),
+            sol_price: SolPriceTracker::load(client)?,
+        };
+        cache.commit();
+        Ok(cache)
+    }
+
+    /// Keeps the changes of the block, once its transaction is committed.
+    pub fn commit(&mut self) {
+        self.raydium_pools.commit();
+        self.mints.commit();
+        self.funding.commit();
+        self.sol_price.commit();
+    }
+
+    /// Discards the changes of a block that was not stored.
+    pub fn rollback(&mut self) {
+        self.raydium_pools.rollback();
+        self.mints.rollback();
+        self.funding.rollback();
+        self.sol_price.rollback();
+    }
+}
+
+pub struct BlockData {
+    pub slot: i64,
+    pub block_time: i64,
+    // SOL/USD rate, None until the first swap of the reference pool
+    pub sol_price: Option<f64>,
+}
+
+pub type EventsBySourceAndType = HashMap<IndexerEventSource, HashMap<GenericEventType, usize>>;
+
+pub struct ProtocolSaveResult {
+    pub rows_count: u64,
+    pub events_by_type: HashMap<GenericEventType, usize>,
+}
+
+pub fn arrange_events_by_source(events: &[Event]) -> HashMap<IndexerEventSource, Vec<&Event>> {
+    let mut events_by_source: HashMap<IndexerEventSource, Vec<&Event>> = HashMap::new();
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        events_by_source
+            .entry(function_call_event.source)
+            .or_default()
+            .push(event);
+    }
+    events_by_source
+}
+
+/// Returns the events grouped by type, in the order they have to be stored
+/// (mints before trades, trades before completions, ...).
+pub fn arrange_events_by_type<'a>(events: &[&'a Event]) -> Vec<(GenericEventType, Vec<&'a Event>)> {
+    let mut events_by_type: HashMap<GenericEventType, Vec<&Event>> = HashMap::new();
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        events_by_type
+            .entry(function_call_event.event_type)
+            .or_default()
+            .push(event);
+    }
+    GENERIC_EVENT_TYPE_PRIORITY_ORDER
+        .iter()
+        .filter_map(|event_type| {
+            events_by_type
+                .remove(event_type)
+                .map(|events| (*event_type, events))
+        })
+        .collect()
+}
+
+pub type SqlValue = Box<dyn ToSql + Sync>;
+
+/// Builds the "($1, $2), ($3, $4)" part of a multi-row insert. `columns` has the
+/// cast of every column placeholder ("" when no cast is needed), e.g.
+/// "::text::numeric" to bind a String into a numeric column.
+pub fn build_values_placeholders(row_count: usize, columns: &[&str]) -> String {
+    (0..row_count)
+        .map(|row| {
+            let placeholders: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(column, cast)| format!("${}{}", row * columns.len() + column + 1, cast))
+                .collect();
+            format!("({})", placeholders.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn execute_with_values(
+    transaction: &mut Transaction,
+    query: &str,
+    values: &[SqlValue],
+) -> Result<u64, postgres::Error> {
+    let params: Vec<&(dyn ToSql + Sync)> = values.iter().map(|value| value.as_ref()).collect();
+    transaction.execute(query, &params)
+}
+
+/// Reads a numeric column selected as text. The values written by the Node
+/// indexer may have decimals, they are truncated.
+pub fn parse_numeric(value: &str) -> i128 {
+    value.split('.').next().unwrap().parse().unwrap()
+}
+
+fn unix_time_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+/// Returns false when the block was already indexed.
+fn save_block(
+    transaction: &mut Transaction,
+    block_data: &BlockData,
+) -> Result<bool, postgres::Error> {
+    let now = unix_time_millis();
+
+    let insert_query = "
+        INSERT INTO public.blocks (
+            slot, status, block_time, indexing_time, created, verified
+        ) VALUES ($1, 'I', $2, $3, $4, NULL)
+    ";
+
+    let result = transaction.execute(
+        insert_query,
+        &[
+            &block_data.slot,
+            &block_data.block_time,
+            &(now / 1000),
+            &now,
+        ],
+    );
+
+    match result {
+        Ok(_) => Ok(true),
+        Err(err) => {
+            if err.code().map(|code| code.code()) == Some(UNIQUE_VIOLATION_ERROR_CODE) {
+                return Ok(false);
+            }
+            println!("Error inserting/updating blocks: {:?}", err);
+            Err(err)
+        }
+    }
+}
+
+/// The changes of the caches (the SOL rate included) are only kept if the block
+/// is stored.
+pub fn save_events(
+    client: &mut Client,
+    block_data: &BlockData,
+    events: &[Event],
+    cache: &mut StorageCache,
+) -> Result<(u64, EventsBySourceAndType), StorageError> {
+    let result = save_block_events(client, block_data, events, cache);
+    match result {
+        Ok(Some(saved)) => {
+            cache.commit();
+            Ok(saved)
+        }
+        Ok(None) => {
+            cache.rollback();
+            Ok((0, HashMap::new()))
+        }
+        Err(err) => {
+            cache.rollback();
+            Err(err)
+        }
+    }
+}
+
+// None when the block was already indexed
+fn save_block_events(
+    client: &mut Client,
+    block_data: &BlockData,
+    events: &[Event],
+    cache: &mut StorageCache,
+) -> Result<Option<(u64, EventsBySourceAndType)>, StorageError> {
+    let mut events_by_source = arrange_events_by_source(events);
+
+    cache.mints.add_mint_events(events);
+    cache.funding.add_transfer_events(events, block_data.slot);
+
+    let mut rows_count = 0;
+    let mut events_by_source_and_type = HashMap::new();
+
+    let mut transaction = client.transaction()?;
+
+    rows_count += save_sol_price(&mut transaction, block_data, &cache.sol_price)?;
+
+    for source in INDEXER_EVENT_SOURCE_PRIORITY_ORDER {
+        let Some(source_events) = events_by_source.remove(&source) else {
+            continue;
+        };
+        let result = match source {
+            IndexerEventSource::Pumpfun => pumpfun::storage::save_protocol_events(
+                &mut transaction,
+                &source_events,
+                block_data,
+                &mut cache.mints,
+            )?,
+            IndexerEventSource::Moonshot => moonshot::storage::save_protocol_events(
+                &mut transaction,
+                &source_events,
+                block_data,
+                &mut cache.mints,
+            )?,
+            IndexerEventSource::Raydium => raydium::storage::save_protocol_events(
+                &mut transaction,
+                &source_events,
+                block_data,
+                &mut cache.raydium_pools,
+            )?,
+            IndexerEventSource::Solana => solana::storage::save_protocol_events(
+                &mut transaction,
+                &source_events,
+                block_data,
+                &mut cache.mints,
+            )?,
+        };
+        rows_count += result.rows_count;
+        events_by_source_and_type.insert(source, result.events_by_type);
+    }
+
+    rows_count += save_launch_bundles(&mut transaction, events, block_data, &cache.funding)?;
+    rows_count += save_token_states(&mut transaction, events, block_data)?;
+    rows_count += save_sandwiches(&mut transaction, events, block_data, &cache.raydium_pools)?;
+    rows_count += save_block_fees(&mut transaction, events, block_data)?;
+
+    if !save_block(&mut transaction, block_data)? {
+        println!(
+            "Block {} was already indexed, rolling back",
+            block_data.slot
+        );
+        transaction.rollback()?;
+        return Ok(None);
+    }
+
+    transaction.commit()?;
+
+    Ok(Some((rows_count, events_by_source_and_type)))
+}
+
+pub fn build_events_report(events_by_source_and_type: &EventsBySourceAndType) -> String {
+    let mut report = Vec::new();
+    for source in INDEXER_EVENT_SOURCE_PRIORITY_ORDER {
+        let Some(events_by_type) = events_by_source_and_type.get(&source) else {
+            continue;
+        };
+        for event_type in GENERIC_EVENT_TYPE_PRIORITY_ORDER {
+            if let Some(count) = events_by_type.get(&event_type) {
+                report.push(format!("{:?} {:?}: {}", source, event_type, count));
+            }
+        }
+    }
+    report.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numeric() {
+        assert_eq!(parse_numeric("1234.5678"), 1234);
+        assert_eq!(parse_numeric("-12.5"), -12);
+        assert_eq!(parse_numeric("42"), 42);
+    }
+}
@@ -0,0 +1,140 @@
+// use as reference: js_src/src/storage/time-series.ts
+
+// OHLCV bars of every timeframe, shared by the protocols' time series storage.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timeframe {
+    S1 = 1,
+    M1 = 60,
+    M5 = 300,
+    M15 = 900,
+    H1 = 3600,
+    H4 = 14400,
+    D1 = 86400,
+}
+
+pub const TIMEFRAMES: [Timeframe; 7] = [
+    Timeframe::S1,
+    Timeframe::M1,
+    Timeframe::M5,
+    Timeframe::M15,
+    Timeframe::H1,
+    Timeframe::H4,
+    Timeframe::D1,
+];
+
+impl Timeframe {
+    pub fn seconds(&self) -> i64 {
+        *self as i64
+    }
+
+    /// Start of the bar that contains `timestamp`.
+    pub fn bar_timestamp(&self, timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(self.seconds())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bar {
+    pub timeframe: Timeframe,
+    pub timestamp: i64,
+    pub mint: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+    pub buy_count: i64,
+    pub sell_count: i64,
+}
+
+/// Folds the trades of a block into bars. The trades have to be added in
+/// execution order (open is the first price, close the last).
+#[derive(Default)]
+pub struct BarAggregator {
+    bars: Vec<Bar>,
+    bar_index: HashMap<(Timeframe, String, i64), usize>,
+}
+
+impl BarAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_trade(&mut self, mint: &str, timestamp: i64, price: f64, volume: u64, is_buy: bool) {
+        for timeframe in TIMEFRAMES {
+            let bar_timestamp = timeframe.bar_timestamp(timestamp);
+            let key = (timeframe, mint.to_string(), bar_timestamp);
+
+            match self.bar_index.get(&key) {
+                Some(&index) => {
+                    let bar = &mut self.bars[index];
+                    bar.high = bar.high.max(price);
+                    bar.low = bar.low.min(price);
+                    bar.close = price;
+                    bar.volume += volume;
+                    if is_buy {
+                        bar.buy_count += 1;
+                    } else {
+                        bar.sell_count += 1;
+                    }
+                }
+                None => {
+                    self.bar_index.insert(key, self.bars.len());
+                    self.bars.push(Bar {
+                        timeframe,
+                        timestamp: bar_timestamp,
+                        mint: mint.to_string(),
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        volume,
+                        buy_count: if is_buy { 1 } else { 0 },
+                        sell_count: if is_buy { 0 } else { 1 },
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn into_bars(self) -> Vec<Bar> {
+        self.bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_aggregator() {
+        let mut aggregator = BarAggregator::new();
+        aggregator.add_trade("mint", 1_700_000_039, 2.0, 100, true);
+        aggregator.add_trade("mint", 1_700_000_039, 3.0, 50, false);
+        aggregator.add_trade("mint", 1_700_000_041, 1.0, 10, true);
+        aggregator.add_trade("other", 1_700_000_041, 5.0, 1, true);
+        let bars = aggregator.into_bars();
+
+        // "mint": 2 S1 bars, 2 M1 bars (39 and 41 are in different minutes), 1 bar for the rest
+        assert_eq!(bars.iter().filter(|bar| bar.mint == "mint").count(), 9);
+        assert_eq!(bars.iter().filter(|bar| bar.mint == "other").count(), 7);
+
+        let s1 = bars
+            .iter()
+            .find(|bar| bar.timeframe == Timeframe::S1 && bar.timestamp == 1_700_000_039)
+            .unwrap();
+        assert_eq!((s1.open, s1.high, s1.low, s1.close), (2.0, 3.0, 2.0, 3.0));
+        assert_eq!((s1.volume, s1.buy_count, s1.sell_count), (150, 1, 1));
+
+        let d1 = bars
+            .iter()
+            .find(|bar| bar.timeframe == Timeframe::D1 && bar.mint == "mint")
+            .unwrap();
+        assert_eq!(d1.timestamp, 1_700_000_039 - 1_700_000_039 % 86400);
+        assert_eq!((d1.open, d1.high, d1.low, d1.close), (2.0, 3.0, 1.0, 1.0));
+        assert_eq!((d1.volume, d1.buy_count, d1.sell_count), (160, 2, 1));
+    }
+}
@@ -0,0 +1,344 @@
+// Lifecycle of the tokens launched on a bonding curve: created, traded on the
+// curve, completed (the curve is full) and migrated to a Raydium pool. The
+// phase only moves forward, the events of a block are applied in chain order.
+
+use crate::moonshot::curve::MoonshotCurveParams;
+use crate::moonshot::{
+    MoonshotCompleteFunctionCallEventMeta, MoonshotTokenMintFunctionCallEventMeta,
+    MoonshotTradeFunctionCallEventMeta,
+};
+use crate::parsing::WSOL_MINT;
+use crate::pumpfun::curve::INITIAL_REAL_TOKEN_RESERVES;
+use crate::pumpfun::{
+    PumpfunCompleteFunctionCallEventMeta, PumpfunTokenMintFunctionCallEventMeta,
+    PumpfunTradeFunctionCallEventMeta,
+};
+use crate::raydium::RaydiumInitializeFunctionCallEventMeta;
+use crate::{Event, GenericEventType, IndexerEventSource};
+
+pub mod storage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TokenPhase {
+    Created,
+    BondingCurve,
+    Completed,
+    Migrated,
+}
+
+impl TokenPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenPhase::Created => "created",
+            TokenPhase::BondingCurve => "bonding_curve",
+            TokenPhase::Completed => "completed",
+            TokenPhase::Migrated => "migrated",
+        }
+    }
+
+    pub fn parse(phase: &str) -> Option<Self> {
+        match phase {
+            "created" => Some(TokenPhase::Created),
+            "bonding_curve" => Some(TokenPhase::BondingCurve),
+            "completed" => Some(TokenPhase::Completed),
+            "migrated" => Some(TokenPhase::Migrated),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenEvent<'a> {
+    Mint {
+        mint: &'a str,
+        protocol: IndexerEventSource,
+    },
+    // progress of the curve, 0 to 100
+    Trade {
+        mint: &'a str,
+        protocol: IndexerEventSource,
+        curve_progress: f64,
+    },
+    Complete {
+        mint: &'a str,
+        protocol: IndexerEventSource,
+    },
+    PoolCreated {
+        mint: &'a str,
+        amm: &'a str,
+    },
+}
+
+impl TokenEvent<'_> {
+    pub fn mint(&self) -> &str {
+        match self {
+            TokenEvent::Mint { mint, .. }
+            | TokenEvent::Trade { mint, .. }
+            | TokenEvent::Complete { mint, .. }
+            | TokenEvent::PoolCreated { mint, .. } => mint,
+        }
+    }
+}
+
+/// The timestamps are block times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenState {
+    pub protocol: String,
+    pub phase: TokenPhase,
+    pub curve_progress: f64,
+    // None for the tokens created before they were tracked
+    pub created_at: Option<i64>,
+    pub created_slot: Option<i64>,
+    pub first_trade_at: Option<i64>,
+    pub last_trade_at: Option<i64>,
+    pub completed_at: Option<i64>,
+    pub migrated_at: Option<i64>,
+    pub migration_pool: Option<String>,
+}
+
+impl TokenState {
+    fn new(protocol: IndexerEventSource, phase: TokenPhase) -> Self {
+        TokenState {
+            protocol: protocol.name().to_string(),
+            phase,
+            curve_progress: 0.0,
+            created_at: None,
+            created_slot: None,
+            first_trade_at: None,
+            last_trade_at: None,
+            completed_at: None,
+            migrated_at: None,
+            migration_pool: None,
+        }
+    }
+
+    fn advance(&mut self, phase: TokenPhase) {
+        self.phase = self.phase.max(phase);
+    }
+
+    /// A token without state is only created by the events of its curve, the
+    /// pools of other tokens are ignored. Returns whether the state changed.
+    pub fn apply(
+        state: &mut Option<TokenState>,
+        event: &TokenEvent,
+        slot: i64,
+        block_time: i64,
+    ) -> bool {
+        match event {
+            TokenEvent::Mint { protocol, .. } => {
+                let state =
+                    state.get_or_insert_with(|| TokenState::new(*protocol, TokenPhase::Created));
+                state.created_at.get_or_insert(block_time);
+                state.created_slot.get_or_insert(slot);
+            }
+            TokenEvent::Trade {
+                protocol,
+                curve_progress,
+                ..
+            } => {
+                let state = state
+                    .get_or_insert_with(|| TokenState::new(*protocol, TokenPhase::BondingCurve));
+                state.advance(TokenPhase::BondingCurve);
+                state.first_trade_at.get_or_insert(block_time);
+                state.last_trade_at = Some(block_time);
+                if state.phase == TokenPhase::BondingCurve {
+                    state.curve_progress = *curve_progress;
+                }
+            }
+            TokenEvent::Complete { protocol, .. } => {
+                let state =
+                    state.get_or_insert_with(|| TokenState::new(*protocol, TokenPhase::Completed));
+                state.advance(TokenPhase::Completed);
+                state.curve_progress = 100.0;
+                state.completed_at.get_or_insert(block_time);
+            }
+            TokenEvent::PoolCreated { amm, .. } => {
+                let Some(state) = state else {
+                    return false;
+                };
+                if state.phase == TokenPhase::Migrated {
+                    return false;
+                }
+                state.advance(TokenPhase::Migrated);
+                state.curve_progress = 100.0;
+                state.migrated_at = Some(block_time);
+                state.migration_pool = Some(amm.to_string());
+            }
+        }
+        true
+    }
+}
+
+fn pumpfun_curve_progress(real_token_reserves: u64) -> f64 {
+    let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(real_token_reserves);
+    (sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0).min(100.0)
+}
+
+/// Returns the lifecycle events of the executed mints, curve trades,
+/// completions and Raydium pool creations of the block, in chain order.
+pub fn read_token_events(events: &[Event]) -> Vec<TokenEvent<'_>> {
+    let mut token_events = Vec::new();
+
+    for event in events {
+        let Event::FunctionCall(function_call_event) = event;
+        let protocol = function_call_event.source;
+        let event_meta = function_call_event.event_meta.as_any();
+
+        let token_event = match function_call_event.event_type {
+            GenericEventType::TokenMint => {
+                if let Some(event_meta) =
+                    event_meta.downcast_ref::<PumpfunTokenMintFunctionCallEventMeta>()
+                {
+                    (!event_meta.failed_transaction).then_some(TokenEvent::Mint {
+                        mint: &event_meta.event_log.mint,
+                        protocol,
+                    })
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<MoonshotTokenMintFunctionCallEventMeta>()
+                {
+                    (!event_meta.failed_transaction).then_some(TokenEvent::Mint {
+                        mint: &event_meta.mint,
+                        protocol,
+                    })
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<RaydiumInitializeFunctionCallEventMeta>()
+                {
+                    let accounts = &event_meta.accounts;
+                    let mint = if accounts.coin_mint == WSOL_MINT {
+                        &accounts.pc_mint
+                    } else {
+                        &accounts.coin_mint
+                    };
+                    (!event_meta.failed_transaction).then_some(TokenEvent::PoolCreated {
+                        mint,
+                        amm: &accounts.amm,
+                    })
+                } else {
+                    None
+                }
+            }
+            GenericEventType::Trade => {
+                if let Some(event_meta) =
+                    event_meta.downcast_ref::<PumpfunTradeFunctionCallEventMeta>()
+                {
+                    event_meta
+                        .event_log
+                        .as_ref()
+                        .map(|trade_event| TokenEvent::Trade {
+                            mint: &trade_event.mint,
+                            protocol,
+                            curve_progress: pumpfun_curve_progress(trade_event.real_token_reserves),
+                        })
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<MoonshotTradeFunctionCallEventMeta>()
+                {
+                    (!event_meta.failed_transaction && event_meta.event_log.is_some()).then_some(
+                        TokenEvent::Trade {
+                            mint: &event_meta.mint,
+                            protocol,
+                            // the percentage polynomial is fitted to the launch curve
+                            curve_progress: event_meta
+                                .curve_state(&MoonshotCurveParams::default())
+                                .percentage,
+                        },
+                    )
+                } else {
+                    None
+                }
+            }
+            GenericEventType::Complete => {
+                if let Some(event_meta) =
+                    event_meta.downcast_ref::<PumpfunCompleteFunctionCallEventMeta>()
+                {
+                    (!event_meta.failed_transaction).then_some(TokenEvent::Complete {
+                        mint: &event_meta.event_log.mint,
+                        protocol,
+                    })
+                } else if let Some(event_meta) =
+                    event_meta.downcast_ref::<MoonshotCompleteFunctionCallEventMeta>()
+                {
+                    (!event_meta.failed_transaction).then_some(TokenEvent::Complete {
+                        mint: &event_meta.mint,
+                        protocol,
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(token_event) = token_event {
+            token_events.push(token_event);
+        }
+    }
+
+    token_events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_token_events() {
+        let events = [
+            TokenEvent::Mint {
+                mint: "mint",
+                protocol: IndexerEventSource::Pumpfun,
+            },
+            TokenEvent::Trade {
+                mint: "mint",
+                protocol: IndexerEventSource::Pumpfun,
+                curve_progress: 40.0,
+            },
+            TokenEvent::Complete {
+                mint: "mint",
+                protocol: IndexerEventSource::Pumpfun,
+            },
+            // a late trade doesn't move the phase back
+            TokenEvent::Trade {
+                mint: "mint",
+                protocol: IndexerEventSource::Pumpfun,
+                curve_progress: 99.0,
+            },
+        ];
+
+        let mut state = None;
+        for (slot, event) in events.iter().enumerate() {
+            assert!(TokenState::apply(
+                &mut state,
+                event,
+                slot as i64,
+                1000 + slot as i64
+            ));
+        }
+        let state_ref = state.as_ref().unwrap();
+        assert_eq!(state_ref.phase, TokenPhase::Completed);
+        assert_eq!(state_ref.curve_progress, 100.0);
+        assert_eq!(state_ref.created_slot, Some(0));
+        assert_eq!(state_ref.first_trade_at, Some(1001));
+        assert_eq!(state_ref.last_trade_at, Some(1003));
+        assert_eq!(state_ref.completed_at, Some(1002));
+
+        let pool_created = TokenEvent::PoolCreated {
+            mint: "mint",
+            amm: "amm",
+        };
+        assert!(TokenState::apply(&mut state, &pool_created, 5, 1005));
+        assert_eq!(state.as_ref().unwrap().phase, TokenPhase::Migrated);
+        assert_eq!(
+            state.as_ref().unwrap().migration_pool.as_deref(),
+            Some("amm")
+        );
+        assert!(!TokenState::apply(&mut state, &pool_created, 6, 1006));
+
+        // pools of tokens not launched on a curve
+        let mut state = None;
+        assert!(!TokenState::apply(&mut state, &pool_created, 5, 1005));
+        assert_eq!(state, None);
+
+        assert_eq!(pumpfun_curve_progress(INITIAL_REAL_TOKEN_RESERVES), 0.0);
+        assert_eq!(pumpfun_curve_progress(0), 100.0);
+    }
+}
@@ -0,0 +1,157 @@
+use postgres::Transaction;
+use std::collections::{HashMap, HashSet};
+
+use super::{read_token_events, TokenPhase, TokenState};
+use crate::storage::{build_values_placeholders, execute_with_values, BlockData, SqlValue};
+use crate::Event;
+
+fn read_token_states(
+    transaction: &mut Transaction,
+    mints: &[&str],
+) -> Result<HashMap<String, TokenState>, postgres::Error> {
+    let select_query = "
+        SELECT
+            mint,
+            protocol,
+            phase,
+            curve_progress,
+            created_at,
+            created_slot,
+            first_trade_at,
+            last_trade_at,
+            completed_at,
+            migrated_at,
+            migration_pool
+        FROM token_state
+        WHERE mint = ANY($1)
+        FOR UPDATE;
+    ";
+    let rows = match transaction.query(select_query, &[&mints]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            println!("Error reading token_state: {:?}", err);
+            return Err(err);
+        }
+    };
+
+    let mut states = HashMap::with_capacity(rows.len());
+    for row in rows {
+        let phase: String = row.get("phase");
+        let Some(phase) = TokenPhase::parse(&phase) else {
+            continue;
+        };
+        states.insert(
+            row.get("mint"),
+            TokenState {
+                protocol: row.get("protocol"),
+                phase,
+                curve_progress: row.get("curve_progress"),
+                created_at: row.get("created_at"),
+                created_slot: row.get("created_slot"),
+                first_trade_at: row.get("first_trade_at"),
+                last_trade_at: row.get("last_trade_at"),
+                completed_at: row.get("completed_at"),
+                migrated_at: row.get("migrated_at"),
+                migration_pool: row.get("migration_pool"),
+            },
+        );
+    }
+
+    Ok(states)
+}
+
+pub fn save_token_states(
+    transaction: &mut Transaction,
+    events: &[Event],
+    block_data: &BlockData,
+) -> Result<u64, postgres::Error> {
+    let token_events = read_token_events(events);
+    if token_events.is_empty() {
+        return Ok(0);
+    }
+
+    let mints: Vec<&str> = token_events
+        .iter()
+        .map(|token_event| token_event.mint())
+        .collect::<HashSet<&str>>()
+        .into_iter()
+        .collect();
+    let mut current_states = read_token_states(transaction, &mints)?;
+
+    let mut states: HashMap<&str, Option<TokenState>> = HashMap::with_capacity(mints.len());
+    let mut changed_mints: Vec<&str> = Vec::new();
+    for token_event in &token_events {
+        let mint = token_event.mint();
+        let state = states
+            .entry(mint)
+            .or_insert_with(|| current_states.remove(mint));
+        if TokenState::apply(state, token_event, block_data.slot, block_data.block_time)
+            && !changed_mints.contains(&mint)
+        {
+            changed_mints.push(mint);
+        }
+    }
+    if changed_mints.is_empty() {
+        return Ok(0);
+    }
+
+    let insert_query = format!(
+        "
+        INSERT INTO token_state (
+            mint,
+            protocol,
+            phase,
+            curve_progress,
+            created_at,
+            created_slot,
+            first_trade_at,
+            last_trade_at,
+            completed_at,
+            migrated_at,
+            migration_pool,
+            updated_slot
+        ) VALUES {}
+        ON CONFLICT (mint) DO UPDATE
+        SET
+            phase = EXCLUDED.phase,
+            curve_progress = EXCLUDED.curve_progress,
+            created_at = EXCLUDED.created_at,
+            created_slot = EXCLUDED.created_slot,
+            first_trade_at = EXCLUDED.first_trade_at,
+            last_trade_at = EXCLUDED.last_trade_at,
+            completed_at = EXCLUDED.completed_at,
+            migrated_at = EXCLUDED.migrated_at,
+            migration_pool = EXCLUDED.migration_pool,
+            updated_slot = EXCLUDED.updated_slot;
+    ",
+        build_values_placeholders(
+            changed_mints.len(),
+            &["", "", "", "::float8", "", "", "", "", "", "", "", ""],
+        )
+    );
+
+    let mut values: Vec<SqlValue> = Vec::with_capacity(changed_mints.len() * 12);
+    for mint in &changed_mints {
+        let state = states.get(mint).unwrap().as_ref().unwrap();
+        values.push(Box::new(mint.to_string()));
+        values.push(Box::new(state.protocol.clone()));
+        values.push(Box::new(state.phase.as_str().to_string()));
+        values.push(Box::new(state.curve_progress));
+        values.push(Box::new(state.created_at));
+        values.push(Box::new(state.created_slot));
+        values.push(Box::new(state.first_trade_at));
+        values.push(Box::new(state.last_trade_at));
+        values.push(Box::new(state.completed_at));
+        values.push(Box::new(state.migrated_at));
+        values.push(Box::new(state.migration_pool.clone()));
+        values.push(Box::new(block_data.slot));
+    }
+
+    match execute_with_values(transaction, &insert_query, &values) {
+        Ok(count) => Ok(count),
+        Err(err) => {
+            println!("Error inserting token_state: {:?}", err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// use as reference: js_src/src/parsing/transaction-log.parser.ts
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+const LOG_TRUNCATION_STRING: &str = "Log truncated";
+
+#[derive(Debug, Clone)]
+pub struct InstructionLogs {
+    pub address: String,
+    pub log_messages: Vec<String>,
+}
+
+struct InvokeNode {
+    address: String,
+    logs: Vec<String>,
+    child_nodes: Vec<usize>,
+    parent_node: Option<usize>,
+    outcome: Option<String>,
+}
+
+// Program ComputeBudget111111111111111111111111111111 invoke [1]
+fn match_invoke(log_message: &str) -> Option<(&str, u64)> {
+    let rest = log_message.strip_prefix("Program ")?;
+    let (address, rest) = rest.split_once(' ')?;
+    let stack_height = rest.strip_prefix("invoke [")?.strip_suffix(']')?;
+    if !is_address(address) {
+        return None;
+    }
+    Some((address, stack_height.parse::<u64>().ok()?))
+}
+
+// Program ComputeBudget111111111111111111111111111111 success
+// Program 8BR3zs8zSXetpnDjCtHWnkpSkNSydWb3PTTDuVKku2uu failed: custom program error: 0x2
+fn match_invoke_end(log_message: &str) -> Option<(&str, &str)> {
+    let rest = log_message.strip_prefix("Program ")?;
+    let (address, outcome) = rest.split_once(' ')?;
+    if !is_address(address) {
+        return None;
+    }
+    if outcome == "success" || outcome.starts_with("failed:") {
+        Some((address, outcome))
+    } else {
+        None
+    }
+}
+
+fn is_address(address: &str) -> bool {
+    (32..=44).contains(&address.len())
+        && address
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+pub fn parse_log_messages(log_messages: &[String]) -> Result<Vec<InstructionLogs>, String> {
+    let mut nodes: Vec<InvokeNode> = Vec::new();
+    let mut root_nodes: Vec<usize> = Vec::new();
+    let mut current_node: Option<usize> = None;
+
+    for log_message in log_messages {
+        if let Some((address, _stack_height)) = match_invoke(log_message) {
+            let node_index = nodes.len();
+            nodes.push(InvokeNode {
+                address: address.to_string(),
+                logs: vec![log_message.clone()],
+                child_nodes: Vec::new(),
+                parent_node: current_node,
+                outcome: None,
+            });
+            match current_node {
+                Some(parent_index) => nodes[parent_index].child_nodes.push(node_index),
+                None => root_nodes.push(node_index),
+            }
+            current_node = Some(node_index);
+        } else if let Some((address, outcome)) = match_invoke_end(log_message) {
+            let node_index = current_node.ok_or("There is no current node")?;
+            let node = &mut nodes[node_index];
+            if node.address != address {
+                return Err("Closing a different node".to_string());
+            }
+            if node.outcome.is_some() {
+                return Err("Closing node twice".to_string());
+            }
+            node.logs.push(log_message.clone());
+            node.outcome = Some(outcome.to_string());
+            current_node = node.parent_node;
+        } else if log_message == LOG_TRUNCATION_STRING {
+            // a "Log truncated" might appear before creating a node at level 0
+            if let Some(node_index) = current_node {
+                let node = &mut nodes[node_index];
+                node.logs.push(log_message.clone());
+                node.outcome = Some("truncated".to_string());
+            }
+            // truncated data is discarded
+            current_node = None;
+            break;
+        } else {
+            let node_index = current_node.ok_or("There is no current node")?;
+            nodes[node_index].logs.push(log_message.clone());
+        }
+    }
+
+    if current_node.is_some() {
+        return Err("logs parsing incomplete".to_string());
+    }
+
+    let mut logs: Vec<InstructionLogs> = Vec::new();
+    for node_index in root_nodes {
+        add_logs_sequentially(&nodes, node_index, &mut logs);
+    }
+
+    Ok(logs)
+}
+
+fn add_logs_sequentially(nodes: &[InvokeNode], node_index: usize, logs: &mut Vec<InstructionLogs>) {
+    let node = &nodes[node_index];
+    logs.push(InstructionLogs {
+        address: node.address.clone(),
+        log_messages: node.logs.clone(),
+    });
+    for child_index in &node.child_nodes {
+        add_logs_sequentially(nodes, *child_index, logs);
+    }
+}
+
+/// Returns the log messages of every invoked instruction (outer and inner) in
+/// execution order, so they can be consumed one by one while the instructions
+/// of the transaction are being parsed.
+pub fn parse_transaction_logs(
+    transaction_obj: &serde_json::Map<String, Value>,
+) -> VecDeque<InstructionLogs> {
+    let log_messages: Vec<String> = transaction_obj
+        .get("meta")
+        .and_then(|meta| meta.get("logMessages"))
+        .and_then(|log_messages| log_messages.as_array())
+        .map(|log_messages| {
+            log_messages
+                .iter()
+                .filter_map(|log_message| log_message.as_str())
+                .map(|log_message| log_message.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match parse_log_messages(&log_messages) {
+        Ok(logs) => logs.into(),
+        Err(err) => {
+            println!("Error parsing transaction logs: {}", err);
+            VecDeque::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_messages() {
+        let log_messages: Vec<String> = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG invoke [1]",
+            "Program log: Instruction: Buy",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+            "Program data: vdt/007mYe4=",
+            "Program MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG success",
+        ]
+        .iter()
+        .map(|log_message| log_message.to_string())
+        .collect();
+
+        let logs = parse_log_messages(&log_messages).unwrap();
+
+        assert_eq!(logs.len(), 3);
+        assert_eq!(
+            logs[0].address,
+            "ComputeBudget111111111111111111111111111111"
+        );
+        assert_eq!(
+            logs[1].address,
+            "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG"
+        );
+        assert_eq!(logs[1].log_messages.len(), 4);
+        assert_eq!(logs[1].log_messages[2], "Program data: vdt/007mYe4=");
+        assert_eq!(logs[2].address, "11111111111111111111111111111111");
+    }
+}